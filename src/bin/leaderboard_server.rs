@@ -0,0 +1,232 @@
+use std::{io::Read, process};
+
+use serde::Serialize;
+use tiny_http::{Server, Request, Response, Method, Header};
+
+use snake::game::{PROFILE_NAME_MAX_LENGTH, LEADERBOARD_MAX_BODY, simulation::simulate, leaderboard::{ScoreSubmission, SubmissionReply, ErrorReply, LeaderboardTable, LeaderboardEntry, check_ranked, check_input_log}};
+use snake::main_menu::sub_menu::MODE_COUNT;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+fn main() {
+    let address = match std::env::args().nth(1) {
+        Some(arg) if arg == "-h" || arg == "--help" => {
+            println!("{}", usage());
+            return;
+        },
+        Some(address) => address,
+        None => DEFAULT_ADDRESS.to_owned()
+    };
+
+    if let Err(error) = run(&address) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
+
+fn usage() -> String {
+    format!(
+"Utilisation : leaderboard-server [<hôte:port>]
+
+Lance le serveur de classement sur <hôte:port> ({} par défaut, 0.0.0.0:7878 pour le réseau).
+Les scores envoyés ne sont classés que si leur partie, rejouée depuis son journal de touches,
+retrouve le score annoncé.",
+        DEFAULT_ADDRESS
+    )
+}
+
+/// Serveur de classement du réseau local, sans fenêtre :
+/// `POST /scores` pour envoyer un score, `GET /scores/<mode>` pour les meilleurs scores d'un mode.
+/// Les requêtes sont traitées une à une, dans l'ordre d'arrivée.
+fn run(address: &str) -> Result<(), String> {
+    let server = Server::http(address).map_err(|error| format!("impossible d'écouter sur {} : {}", address, error))?;
+    let mut leaderboard_table = LeaderboardTable::load();
+    println!("serveur de classement à l'écoute sur http://{}", address);
+
+    for mut request in server.incoming_requests() {
+        let (status_code, body) = match handle_request(&mut request, &mut leaderboard_table) {
+            Ok(body) => (200, body),
+            Err((status_code, error)) => {
+                println!("requête refusée ({} {}) : {}", request.method(), request.url(), error);
+                (status_code, json(&ErrorReply { error }))
+            }
+        };
+
+        let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+        let _ = request.respond(Response::from_string(body).with_status_code(status_code).with_header(header));
+    }
+    Ok(())
+}
+
+fn json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn handle_request(request: &mut Request, leaderboard_table: &mut LeaderboardTable) -> Result<String, (u16, String)> {
+    let url = request.url().to_owned();
+    match (request.method(), url.strip_prefix("/scores")) {
+        (Method::Get, Some(mode)) => {
+            let wall_type = mode.strip_prefix('/')
+                .and_then(|wall_type| wall_type.parse::<usize>().ok())
+                .filter(|wall_type| *wall_type < MODE_COUNT)
+                .ok_or_else(|| (404, format!("mode inconnu : {}", mode.trim_start_matches('/'))))?;
+            Ok(json(&leaderboard_table.modes.get(&wall_type).cloned().unwrap_or_default()))
+        },
+        (Method::Post, Some("")) => {
+            let mut body = String::new();
+            request.as_reader().take(LEADERBOARD_MAX_BODY).read_to_string(&mut body)
+                .map_err(|error| (400, error.to_string()))?;
+            let submission = parse_submission(&body).map_err(|error| (400, error))?;
+
+            let rank = record_submission(submission, leaderboard_table).map_err(|error| (422, error))?;
+            if rank.is_some() {
+                leaderboard_table.save();
+            }
+            Ok(json(&SubmissionReply { rank }))
+        },
+        _ => Err((404, format!("adresse inconnue : {}", url)))
+    }
+}
+
+fn parse_submission(body: &str) -> Result<ScoreSubmission, String> {
+    serde_json::from_str(body).map_err(|error| format!("score illisible : {}", error))
+}
+
+/// Rejoue la partie à partir de son journal de touches : le score n'est classé que si la simulation le retrouve.
+/// Le classement modifié est écrit par `handle_request`.
+fn record_submission(submission: ScoreSubmission, leaderboard_table: &mut LeaderboardTable) -> Result<Option<usize>, String> {
+    let name = submission.name.trim();
+    if name.is_empty() || name.chars().count() > PROFILE_NAME_MAX_LENGTH {
+        return Err(format!("nom invalide : {}", submission.name));
+    }
+    check_ranked(&submission.game_type)?;
+    if leaderboard_table.contains(submission.replay_hash) {
+        return Err("partie déjà envoyée".to_owned());
+    }
+    check_input_log(&submission.input_log)?;
+
+    let seed = submission.game_type.seed;
+    let simulation_result = simulate(&submission.game_type, &submission.input_log);
+    if simulation_result.outcome.is_none() {
+        return Err("partie inachevée à la fin du journal".to_owned());
+    }
+    if simulation_result.score != submission.score {
+        return Err(format!("score annoncé {}, score rejoué {}", submission.score, simulation_result.score));
+    }
+    if simulation_result.run_recording.direction_hash(seed) != submission.replay_hash {
+        return Err("empreinte des directions différente de la partie rejouée".to_owned());
+    }
+
+    let wall_type = submission.game_type.wall_type;
+    let rank = leaderboard_table.insert(wall_type, LeaderboardEntry {
+        name: name.to_owned(),
+        score: submission.score,
+        seed,
+        replay_hash: submission.replay_hash
+    });
+    println!("score accepté : {} {} en mode {}, place {:?}", name, submission.score, wall_type, rank);
+    Ok(rank)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use snake::game::replay::{InputLog, LoggedFrame};
+    use snake::main_menu::sub_menu::{GameType, mode_multiplier};
+    use super::*;
+
+    /// Images de 60 par seconde, en nanosecondes.
+    const FRAME_DELTA: u64 = 16_666_667;
+    const FRAME_COUNT: usize = 3_000;
+
+    /// Mur extérieur sans touche pressée : le serpent va tout droit jusqu'au mur.
+    fn game_type() -> GameType {
+        GameType {
+            wall_type: 1,
+            multiplier: mode_multiplier(1),
+            seed: 7,
+            ..Default::default()
+        }
+    }
+
+    fn input_log(frame_count: usize, delta: u64) -> InputLog {
+        InputLog {
+            start_accumulator: 0,
+            frames: vec![LoggedFrame { delta, pressed: Vec::new(), released: Vec::new() }; frame_count]
+        }
+    }
+
+    /// Score et empreinte de la partie, rejouée une seule fois pour tous les tests.
+    fn finished_game() -> (u32, u64) {
+        static FINISHED_GAME: OnceLock<(u32, u64)> = OnceLock::new();
+        *FINISHED_GAME.get_or_init(|| {
+            let game_type = game_type();
+            let simulation_result = simulate(&game_type, &input_log(FRAME_COUNT, FRAME_DELTA));
+            assert!(simulation_result.outcome.is_some(), "partie inachevée après {} images", FRAME_COUNT);
+            (simulation_result.score, simulation_result.run_recording.direction_hash(game_type.seed))
+        })
+    }
+
+    fn submission(score: u32, replay_hash: u64, input_log: InputLog) -> ScoreSubmission {
+        ScoreSubmission {
+            name: "Testeur".to_owned(),
+            game_type: game_type(),
+            score,
+            replay_hash,
+            input_log
+        }
+    }
+
+    #[test]
+    fn accepts_replayed_score_once() {
+        let (score, replay_hash) = finished_game();
+        let mut leaderboard_table = LeaderboardTable::default();
+
+        let rank = record_submission(submission(score, replay_hash, input_log(FRAME_COUNT, FRAME_DELTA)), &mut leaderboard_table);
+        assert_eq!(rank, Ok(Some(1)));
+        assert!(leaderboard_table.contains(replay_hash));
+
+        let error = record_submission(submission(score, replay_hash, input_log(FRAME_COUNT, FRAME_DELTA)), &mut leaderboard_table).unwrap_err();
+        assert!(error.contains("déjà envoyée"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_wrong_score() {
+        let (score, replay_hash) = finished_game();
+        let mut leaderboard_table = LeaderboardTable::default();
+
+        let error = record_submission(submission(score + 1, replay_hash, input_log(FRAME_COUNT, FRAME_DELTA)), &mut leaderboard_table).unwrap_err();
+        assert!(error.contains("score annoncé"), "{}", error);
+        assert!(leaderboard_table.modes.is_empty());
+    }
+
+    #[test]
+    fn rejects_wrong_hash() {
+        let (score, replay_hash) = finished_game();
+        let mut leaderboard_table = LeaderboardTable::default();
+
+        let error = record_submission(submission(score, replay_hash ^ 1, input_log(FRAME_COUNT, FRAME_DELTA)), &mut leaderboard_table).unwrap_err();
+        assert!(error.contains("empreinte"), "{}", error);
+        assert!(leaderboard_table.modes.is_empty());
+    }
+
+    /// Refusé sans être rejoué : une image de `u64::MAX` nanosecondes ferait paniquer la simulation.
+    #[test]
+    fn rejects_oversized_frame_before_simulating() {
+        let mut leaderboard_table = LeaderboardTable::default();
+
+        let error = record_submission(submission(0, 1, input_log(1, u64::MAX)), &mut leaderboard_table).unwrap_err();
+        assert!(error.contains("pas de déplacement"), "{}", error);
+    }
+
+    #[test]
+    fn rejects_malformed_log() {
+        let mut body = serde_json::to_value(submission(0, 1, input_log(2, FRAME_DELTA))).unwrap();
+        body["input_log"]["frames"][1]["delta"] = serde_json::json!(-1);
+
+        let error = parse_submission(&body.to_string()).err().unwrap();
+        assert!(error.contains("illisible"), "{}", error);
+        assert!(parse_submission("{}").is_err());
+    }
+}
@@ -0,0 +1,343 @@
+use std::str::FromStr;
+
+use bevy::prelude::{App, Resource, Res, ResMut, State, SystemSet};
+
+use crate::{AppState, ARENA_WIDTH, ARENA_HEIGHT, ARENA_MIN_SIZE, ARENA_MAX_SIZE, SPEED_MIN, SPEED_MAX, WINDOW_WIDTH, WINDOW_HEIGHT, arena_px_width, arena_px_height};
+use crate::main_menu::sub_menu::{GameType, Controller, MAX_ENEMIES, MODE_COUNT, mode_name, mode_multiplier};
+use crate::game::{replay::{RunRecording, ReplayFromFile}, settings::Settings};
+
+/// État à atteindre une fois sorti de `AppState::Launch`.
+#[derive(Resource)]
+struct LaunchTarget(AppState);
+
+/// Options de la ligne de commande : sans option, le jeu s'ouvre sur le choix du profil.
+pub struct LaunchOptions {
+    pub help: bool,
+    /// Partie lancée directement, sans passer par les menus.
+    pub game_type: Option<GameType>,
+    /// Replay ouvert depuis un fichier.
+    pub replay: Option<RunRecording>,
+    pub headless: bool,
+    /// Nombre de déplacements après lequel une partie sans fenêtre s'arrête, même si elle n'est pas finie.
+    pub max_ticks: Option<u64>,
+    pub scale: Option<f64>,
+    /// Replay à vérifier en rejouant sa partie, à la place du jeu.
+    pub verify: Option<RunRecording>,
+    /// Score annoncé pour le replay vérifié, celui de sa dernière image par défaut.
+    pub claimed_score: Option<u32>
+}
+
+impl LaunchOptions {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut launch_options = LaunchOptions {
+            help: false,
+            game_type: None,
+            replay: None,
+            headless: false,
+            max_ticks: None,
+            scale: None,
+            verify: None,
+            claimed_score: None
+        };
+        let mut game_type = GameType {
+            seed: rand::random(),
+            ..Default::default()
+        };
+        let mut game_requested = false;
+        let mut controller = None;
+
+        while let Some(arg) = args.next() {
+            let option = arg.as_str();
+            match option {
+                "-h" | "--help" => launch_options.help = true,
+                "--mode" => {
+                    let wall_type: usize = parse_value(option, args.next())?;
+                    if wall_type >= MODE_COUNT {
+                        return Err(format!("mode inconnu : {}", wall_type));
+                    }
+                    game_type.wall_type = wall_type;
+                    game_type.multiplier = mode_multiplier(wall_type);
+                    game_requested = true;
+                },
+                "--seed" => {
+                    game_type.seed = parse_value(option, args.next())?;
+                    game_requested = true;
+                },
+                "--arena" => {
+                    let value: String = parse_value(option, args.next())?;
+                    let (width, height) = value.split_once('x')
+                        .and_then(|(width, height)| Some((width.parse::<u32>().ok()?, height.parse::<u32>().ok()?)))
+                        .ok_or_else(|| format!("taille d'arène invalide : {} (attendu : <largeur>x<hauteur>)", value))?;
+                    let size_range = ARENA_MIN_SIZE..=ARENA_MAX_SIZE;
+                    if !size_range.contains(&width) || !size_range.contains(&height) {
+                        return Err(format!("taille d'arène hors limites : {} (de {} à {} cases)", value, ARENA_MIN_SIZE, ARENA_MAX_SIZE));
+                    }
+                    game_type.arena_width = width;
+                    game_type.arena_height = height;
+                    game_requested = true;
+                },
+                "--speed" => {
+                    let speed: f32 = parse_value(option, args.next())?;
+                    if !(SPEED_MIN..=SPEED_MAX).contains(&speed) {
+                        return Err(format!("vitesse hors limites : {} (de {} à {})", speed, SPEED_MIN, SPEED_MAX));
+                    }
+                    game_type.speed = speed;
+                    game_requested = true;
+                },
+                "--enemies" => {
+                    let enemies: u32 = parse_value(option, args.next())?;
+                    if enemies > MAX_ENEMIES {
+                        return Err(format!("trop d'adversaires : {} (au plus {})", enemies, MAX_ENEMIES));
+                    }
+                    game_type.enemies = enemies;
+                    game_requested = true;
+                },
+                "--controller" => {
+                    let value: String = parse_value(option, args.next())?;
+                    controller = Some(match value.as_str() {
+                        "human" => Controller::Human,
+                        "bot" => Controller::Bot,
+                        _ => return Err(format!("contrôleur inconnu : {} (human ou bot)", value))
+                    });
+                    game_requested = true;
+                },
+                "--replay" => {
+                    let path: String = parse_value(option, args.next())?;
+                    let run_recording = RunRecording::load(&path)
+                        .map_err(|error| format!("impossible de lire le replay {} : {}", path, error))?;
+                    launch_options.replay = Some(run_recording);
+                },
+                "--headless" => launch_options.headless = true,
+                "--max-ticks" => {
+                    let max_ticks: u64 = parse_value(option, args.next())?;
+                    if max_ticks == 0 {
+                        return Err("--max-ticks attend au moins 1 déplacement".to_owned());
+                    }
+                    launch_options.max_ticks = Some(max_ticks);
+                },
+                "--scale" => {
+                    let scale: f64 = parse_value(option, args.next())?;
+                    if scale <= 0. {
+                        return Err(format!("échelle invalide : {}", scale));
+                    }
+                    launch_options.scale = Some(scale);
+                },
+                "--verify" => {
+                    let path: String = parse_value(option, args.next())?;
+                    let run_recording = RunRecording::load(&path)
+                        .map_err(|error| format!("impossible de lire le replay {} : {}", path, error))?;
+                    launch_options.verify = Some(run_recording);
+                },
+                "--score" => launch_options.claimed_score = Some(parse_value(option, args.next())?),
+                _ => return Err(format!("option inconnue : {}", arg))
+            }
+        }
+
+        if launch_options.replay.is_some() && (game_requested || launch_options.headless) {
+            return Err("--replay ne se combine pas avec les options de partie ni avec --headless".to_owned());
+        }
+
+        if launch_options.max_ticks.is_some() && !launch_options.headless {
+            return Err("--max-ticks ne s'utilise qu'avec --headless".to_owned());
+        }
+
+        if launch_options.claimed_score.is_some() && launch_options.verify.is_none() {
+            return Err("--score ne s'utilise qu'avec --verify".to_owned());
+        }
+
+        if launch_options.verify.is_some() && (game_requested || launch_options.headless || launch_options.replay.is_some()) {
+            return Err("--verify ne se combine qu'avec --score".to_owned());
+        }
+
+        // sans fenêtre ni clavier, seul l'ordinateur peut jouer
+        if launch_options.headless {
+            if controller == Some(Controller::Human) {
+                return Err("--headless ne fonctionne qu'avec --controller bot".to_owned());
+            }
+            controller = Some(Controller::Bot);
+            game_requested = true;
+        }
+
+        if game_requested {
+            game_type.controller = controller.unwrap_or(Controller::Human);
+            launch_options.game_type = Some(game_type);
+        }
+
+        Ok(launch_options)
+    }
+
+    /// Taille de l'arène en pixels, pour dimensionner la fenêtre.
+    pub fn arena_px_size(&self) -> (f32, f32) {
+        match (&self.game_type, &self.replay) {
+            (Some(game_type), _) => (arena_px_width(game_type.arena_width), arena_px_height(game_type.arena_height)),
+            (None, Some(run_recording)) => (run_recording.px_width, run_recording.px_height),
+            (None, None) => (WINDOW_WIDTH, WINDOW_HEIGHT)
+        }
+    }
+
+    /// Ajoute l'état de départ et les ressources de la partie ou du replay demandés.
+    pub fn setup(self, app: &mut App) {
+        let launch_target = if let Some(game_type) = self.game_type {
+            app.insert_resource(game_type);
+            AppState::InGame
+        } else if let Some(run_recording) = self.replay {
+            app.insert_resource(run_recording).insert_resource(ReplayFromFile);
+            AppState::Replay
+        } else {
+            app.add_state(AppState::ProfileSelect);
+            return;
+        };
+
+        app
+            .insert_resource(LaunchTarget(launch_target))
+            .add_state(AppState::Launch)
+            .add_system_set(SystemSet::on_update(AppState::Launch).with_system(launch_system));
+    }
+}
+
+fn launch_system(launch_target: Res<LaunchTarget>, mut app_state: ResMut<State<AppState>>) {
+    let _ = app_state.set(launch_target.0.clone());
+}
+
+fn parse_value<T: FromStr>(option: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("valeur manquante pour {}", option))?;
+    value.parse().map_err(|_| format!("valeur invalide pour {} : {}", option, value))
+}
+
+pub fn usage() -> String {
+    let modes: Vec<String> = (0..MODE_COUNT)
+        .map(|wall_type| format!("                          {} : {} (x{})", wall_type, mode_name(wall_type), mode_multiplier(wall_type)))
+        .collect();
+
+    format!(
+"Utilisation : snake [options]
+
+Sans option, le jeu s'ouvre sur le choix du profil.
+
+Partie lancée directement :
+  --mode <n>              mode de jeu (0 par défaut) :
+{}
+  --seed <n>              graine du labyrinthe (tirée au hasard par défaut)
+  --arena <L>x<H>         taille de l'arène en cases, de {} à {} ({}x{} par défaut)
+  --speed <x>             vitesse des serpents, de {} à {} (1 par défaut)
+  --enemies <n>           nombre d'adversaires, de 0 à {}
+  --controller <c>        human (par défaut) ou bot : le serpent suit l'IA des adversaires
+
+Autres options :
+  --replay <fichier>      rejoue un replay enregistré (la dernière partie est dans {})
+  --headless              partie sans fenêtre jouée par l'ordinateur, résultat écrit à la fin
+  --max-ticks <n>         avec --headless, arrête la partie après <n> déplacements
+  --scale <x>             facteur d'échelle de la fenêtre
+  --verify <fichier>      rejoue sans fenêtre la partie d'un replay et la compare à ses images,
+                          au score annoncé, à la longueur du serpent et à l'issue
+  --score <n>             score annoncé pour --verify (celui du replay par défaut)
+  -h, --help              affiche cette aide
+
+Le serveur de classement est l'exécutable leaderboard-server ; le jeu envoie ses scores
+à l'adresse leaderboard_server de {}",
+        modes.join("\n"),
+        ARENA_MIN_SIZE, ARENA_MAX_SIZE, ARENA_WIDTH, ARENA_HEIGHT,
+        SPEED_MIN, SPEED_MAX,
+        MAX_ENEMIES,
+        RunRecording::path(),
+        Settings::path()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+    use crate::game::replay::ReplayFrame;
+
+    fn parse(args: &[&str]) -> Result<LaunchOptions, String> {
+        LaunchOptions::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    /// Replay d'une image écrit dans le dossier temporaire, un fichier par test.
+    fn replay_file(name: &str) -> String {
+        let run_recording = RunRecording {
+            frames: vec![ReplayFrame { cells: Vec::new(), score: 0 }],
+            ..Default::default()
+        };
+        let path = env::temp_dir().join(format!("snake_cli_{}_{}.ron", name, std::process::id()));
+        fs::write(&path, ron::to_string(&run_recording).unwrap()).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn replay_excludes_game_options() {
+        let path = replay_file("replay");
+
+        let launch_options = parse(&["--replay", &path]).unwrap();
+        assert!(launch_options.replay.is_some() && launch_options.game_type.is_none());
+        for option in [&["--mode", "1"][..], &["--seed", "7"], &["--enemies", "1"], &["--controller", "bot"], &["--headless"]] {
+            let args: Vec<&str> = ["--replay", path.as_str()].iter().chain(option).copied().collect();
+            assert!(parse(&args).err().unwrap().starts_with("--replay"), "{:?}", option);
+        }
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn max_ticks_needs_headless() {
+        assert!(parse(&["--max-ticks", "10"]).err().unwrap().starts_with("--max-ticks"));
+        assert!(parse(&["--headless", "--max-ticks", "0"]).is_err());
+
+        let launch_options = parse(&["--headless", "--max-ticks", "10"]).unwrap();
+        assert_eq!(launch_options.max_ticks, Some(10));
+    }
+
+    #[test]
+    fn score_needs_verify() {
+        assert!(parse(&["--score", "5"]).err().unwrap().starts_with("--score"));
+
+        let path = replay_file("verify");
+        let launch_options = parse(&["--verify", &path, "--score", "5"]).unwrap();
+        assert!(launch_options.verify.is_some());
+        assert_eq!(launch_options.claimed_score, Some(5));
+        assert!(parse(&["--verify", &path, "--mode", "1"]).err().unwrap().starts_with("--verify"));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn game_option_bounds() {
+        let arena = |width: u32, height: u32| parse(&["--arena", &format!("{}x{}", width, height)]);
+        let game_type = arena(ARENA_MIN_SIZE, ARENA_MAX_SIZE).unwrap().game_type.unwrap();
+        assert_eq!((game_type.arena_width, game_type.arena_height), (ARENA_MIN_SIZE, ARENA_MAX_SIZE));
+        assert!(arena(ARENA_MIN_SIZE - 1, ARENA_HEIGHT).is_err());
+        assert!(arena(ARENA_WIDTH, ARENA_MAX_SIZE + 1).is_err());
+        assert!(parse(&["--arena", "20"]).is_err());
+
+        let speed = |speed: f32| parse(&["--speed", &speed.to_string()]);
+        assert_eq!(speed(SPEED_MIN).unwrap().game_type.unwrap().speed, SPEED_MIN);
+        assert_eq!(speed(SPEED_MAX).unwrap().game_type.unwrap().speed, SPEED_MAX);
+        assert!(speed(SPEED_MIN / 2.).is_err());
+        assert!(speed(SPEED_MAX * 2.).is_err());
+
+        let enemies = |enemies: u32| parse(&["--enemies", &enemies.to_string()]);
+        assert_eq!(enemies(MAX_ENEMIES).unwrap().game_type.unwrap().enemies, MAX_ENEMIES);
+        assert!(enemies(MAX_ENEMIES + 1).is_err());
+
+        assert!(parse(&["--mode", &MODE_COUNT.to_string()]).is_err());
+        let game_type = parse(&["--mode", "5"]).unwrap().game_type.unwrap();
+        assert_eq!(game_type.multiplier, mode_multiplier(5));
+    }
+
+    #[test]
+    fn headless_plays_with_bot() {
+        assert!(parse(&["--headless", "--controller", "human"]).err().unwrap().starts_with("--headless"));
+
+        for args in [&["--headless"][..], &["--headless", "--controller", "bot"]] {
+            let launch_options = parse(args).unwrap();
+            assert!(launch_options.headless);
+            assert!(launch_options.game_type.unwrap().controller == Controller::Bot);
+        }
+
+        let game_type = parse(&["--seed", "7"]).unwrap().game_type.unwrap();
+        assert!(game_type.controller == Controller::Human);
+        assert_eq!(game_type.seed, 7);
+    }
+}
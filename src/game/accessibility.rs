@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+use super::settings::Settings;
+
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PostUpdate, text_size_system);
+    }
+}
+
+/// Agrandit tous les textes, du jeu comme des menus, selon la taille choisie dans les options.
+/// Les nouveaux textes sont mis à l'échelle à leur apparition, les autres lorsque l'option change.
+fn text_size_system(
+    settings: Res<Settings>,
+    mut applied_scale: Local<Option<f32>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let scale = settings.text_size.scale();
+    let previous_scale = applied_scale.replace(scale).unwrap_or(1.);
+
+    for mut text in text_query.iter_mut() {
+        let factor = if text.is_added() { scale } else { scale / previous_scale };
+        if factor != 1. {
+            for section in text.sections.iter_mut() {
+                section.style.font_size *= factor;
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use iyes_loopless::prelude::IntoConditionalSystem;
+use serde::{Serialize, Deserialize};
+
+use crate::main_menu::sub_menu::{GameType, Controller};
+
+use super::{profile::Profiles, debug::DebugConsole, simulation::Simulation, stats::{RunStats, GameOutcome}, ACHIEVEMENT_TOAST_DURATION, ACHIEVEMENT_GOLD_FOODS, ACHIEVEMENT_SURVIVAL_TIME, ACHIEVEMENT_WALLS_CROSSED};
+
+// region:    --- Achievement
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Achievement {
+    GoldDigger,
+    FillTheArena,
+    Survivor,
+    WallWalker
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 4] = [Achievement::GoldDigger, Achievement::FillTheArena, Achievement::Survivor, Achievement::WallWalker];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Achievement::GoldDigger => "Chercheur d'or",
+            Achievement::FillTheArena => "Plus de place",
+            Achievement::Survivor => "Survivant",
+            Achievement::WallWalker => "Passe-muraille"
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::GoldDigger => "Manger 3 nourritures dorées dans une partie",
+            Achievement::FillTheArena => "Remplir l'arène",
+            Achievement::Survivor => "Survivre 5 minutes avec les murs verticaux et horizontaux",
+            Achievement::WallWalker => "Traverser 20 murs en étant invincible"
+        }
+    }
+
+    fn is_reached(&self, run_stats: &RunStats) -> bool {
+        match self {
+            Achievement::GoldDigger => run_stats.foods_eaten("gold") >= ACHIEVEMENT_GOLD_FOODS,
+            Achievement::FillTheArena => run_stats.outcome == Some(GameOutcome::ArenaFilled),
+            // mode "Mur Vertical et Horizontal"
+            Achievement::Survivor => run_stats.wall_type == 4 && run_stats.play_time >= ACHIEVEMENT_SURVIVAL_TIME,
+            Achievement::WallWalker => run_stats.walls_crossed >= ACHIEVEMENT_WALLS_CROSSED
+        }
+    }
+}
+
+// endregion: --- Achievement
+
+// region:    --- Component
+
+#[derive(Component)]
+struct AchievementToast(Timer);
+
+// endregion: --- Component
+
+pub struct AchievementPlugin;
+
+impl Plugin for AchievementPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_system(achievement_unlock_system.run_unless_resource_exists::<Simulation>())
+        .add_system(achievement_toast_system);
+    }
+}
+
+/// Les succès sont propres au profil courant, et ne s'obtiennent ni avec le serpent dirigé par l'ordinateur,
+/// ni dans une partie modifiée avec la console de développement.
+fn achievement_unlock_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    run_stats: Res<RunStats>,
+    game_type: Option<Res<GameType>>,
+    debug_console: Option<Res<DebugConsole>>,
+    mut profiles: ResMut<Profiles>,
+) {
+    if !run_stats.is_changed()
+        || game_type.is_some_and(|game_type| game_type.controller == Controller::Bot)
+        || debug_console.is_some_and(|debug_console| debug_console.used)
+    {
+        return;
+    }
+
+    let mut unlocked = false;
+    if let Some(profile) = profiles.current_mut() {
+        for achievement in Achievement::ALL {
+            if !profile.achievements.contains(&achievement) && achievement.is_reached(&run_stats) {
+                profile.achievements.push(achievement);
+                toast_spawn(&mut commands, &asset_server, &achievement);
+                unlocked = true;
+            }
+        }
+    }
+
+    if unlocked {
+        profiles.save();
+    }
+}
+
+fn toast_spawn(commands: &mut Commands, asset_server: &AssetServer, achievement: &Achievement) {
+    commands.spawn(TextBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(10.),
+                bottom: Val::Px(10.),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        text: Text::from_section(
+            format!("Succès débloqué : {}", achievement.title()),
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 20.,
+                color: Color::rgb(1., 0.84, 0.),
+            }
+        ),
+        ..Default::default()
+    })
+    .insert(AchievementToast(Timer::from_seconds(ACHIEVEMENT_TOAST_DURATION, TimerMode::Once)));
+}
+
+fn achievement_toast_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut toast_query: Query<(Entity, &mut AchievementToast)>,
+) {
+    for (entity, mut toast) in toast_query.iter_mut() {
+        toast.0.tick(time.delta());
+        if toast.0.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use bevy::{prelude::{Plugin, App, Commands, Res, ResMut, Query, Entity, With, Or, SystemSet, Resource, Component, Transform, Vec3, AssetServer, Color, Text, TextStyle, Text2dBundle, CoreStage, default}, time::{Time, Timer, TimerMode}};
+use iyes_loopless::prelude::{IntoConditionalSystem, ConditionSet, ConditionHelpers, AppLooplessFixedTimestepExt};
+use serde::{Serialize, Deserialize};
+
+use super::{AppState, ArenaSize, GameTick, convert, is_same_position, components::{SnakeHead, Position, Food, Player}, theme::Palette, COMBO_WINDOW_TICKS, COMBO_MAX_MULTIPLIER, FLOATING_TEXT_DURATION, FLOATING_TEXT_SPEED, WINDOW_WIDTH, WINDOW_HEIGHT, UPPER_EDGE};
+
+// region:    --- Resource
+
+/// Série de nourritures mangées rapidement par le joueur.
+/// La série est cassée si le joueur met plus de `COMBO_WINDOW_TICKS` déplacements à manger
+/// la nourriture suivante, ou s'il frôle une nourriture sans la manger.
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+pub struct Combo {
+    pub count: u32,
+    pub last_tick: u64,
+    /// Nourritures voisines de la tête au déplacement précédent.
+    near_foods: Vec<Position>
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> u32 {
+        self.count.clamp(1, COMBO_MAX_MULTIPLIER)
+    }
+
+    /// Enregistre une nourriture mangée et renvoie le multiplicateur à appliquer à ses points.
+    pub fn register_food(&mut self, tick: u64) -> u32 {
+        if self.count > 0 && tick - self.last_tick > COMBO_WINDOW_TICKS {
+            self.count = 0;
+        }
+        self.count += 1;
+        self.last_tick = tick;
+        self.multiplier()
+    }
+
+    /// Applique les règles de rupture après un déplacement du joueur.
+    pub fn update(&mut self, tick: u64, head_position: &Position, foods: &[Position]) {
+        let missed_food = self.near_foods.iter().any(|near_food| {
+            foods.contains(near_food) && !is_same_position(head_position, near_food) && !is_adjacent(head_position, near_food)
+        });
+
+        if missed_food || tick - self.last_tick > COMBO_WINDOW_TICKS {
+            self.count = 0;
+        }
+
+        self.near_foods = foods.iter().filter(|food| is_adjacent(head_position, food)).copied().collect();
+    }
+}
+
+fn is_adjacent(position: &Position, other_position: &Position) -> bool {
+    (position.x - other_position.x).abs() + (position.y - other_position.y).abs() == 1
+}
+
+// endregion: --- Resource
+
+// region:    --- Component
+
+/// Texte "+N" qui monte et s'efface au-dessus d'une nourriture mangée.
+#[derive(Component)]
+struct FloatingText(Timer);
+
+// endregion: --- Component
+
+pub struct ComboPlugin;
+
+impl Plugin for ComboPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_system_set(
+            SystemSet::on_enter(AppState::InGame)
+            .with_system(combo_setup_system)
+        )
+        .add_fixed_timestep_system(
+            "snake_move_time",
+            2,
+            combo_update_system
+                .run_in_bevy_state(AppState::InGame)
+                .run_if_resource_exists::<Combo>()
+                // les ruptures de la série précèdent la nourriture mangée à ce déplacement
+                .before("snake_ate_food")
+        )
+        .add_system_set_to_stage(
+            CoreStage::PostUpdate,
+            ConditionSet::new()
+            .run_in_bevy_state(AppState::InGame)
+            .run_if_resource_exists::<Combo>()
+            .with_system(floating_text_system)
+            .into()
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::InGame)
+            .with_system(cleanup_combo_system)
+        );
+    }
+}
+
+fn combo_setup_system(mut commands: Commands) {
+    commands.insert_resource(Combo::default());
+}
+
+fn combo_update_system(
+    game_tick: Res<GameTick>,
+    mut combo: ResMut<Combo>,
+    snake_head_query: Query<&Position, (With<SnakeHead>, With<Player>)>,
+    food_query: Query<&Position, With<Food>>,
+) {
+    if let Ok(head_position) = snake_head_query.get_single() {
+        let foods: Vec<Position> = food_query.iter().copied().collect();
+        combo.update(game_tick.0, head_position, &foods);
+    }
+}
+
+/// Affiche les points gagnés au-dessus de la nourriture mangée.
+pub(super) fn floating_text_spawn(commands: &mut Commands, asset_server: &AssetServer, arena_size: &ArenaSize, palette: &Palette, position: &Position, points: u32) {
+    commands.spawn(Text2dBundle {
+        text: Text::from_section(
+            format!("+{}", points),
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 20.,
+                color: palette.combo_text,
+            }
+        ),
+        transform: Transform {
+            translation: Vec3::new(
+                convert(position.x as f32, arena_size.px_width, arena_size.tile_width as f32),
+                convert(position.y as f32, arena_size.px_height, arena_size.tile_height as f32),
+                20.,
+            ),
+            ..Default::default()
+        },
+        ..default()
+    })
+    .insert(FloatingText(Timer::from_seconds(FLOATING_TEXT_DURATION, TimerMode::Once)));
+}
+
+fn floating_text_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut floating_text_query: Query<(Entity, &mut FloatingText, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut floating_text, mut transform, mut text) in floating_text_query.iter_mut() {
+        floating_text.0.tick(time.delta());
+        if floating_text.0.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation.y += FLOATING_TEXT_SPEED * time.delta_seconds();
+        text.sections[0].style.color.set_a(floating_text.0.percent_left());
+    }
+}
+
+fn cleanup_combo_system(
+    mut commands: Commands,
+    text_query: Query<Entity, With<FloatingText>>,
+) {
+    for entity in text_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    commands.remove_resource::<Combo>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_food_window_and_multiplier() {
+        let mut combo = Combo::default();
+        assert_eq!(combo.register_food(10), 1);
+        // une nourriture mangée juste à la fin de la fenêtre prolonge encore la série
+        assert_eq!(combo.register_food(10 + COMBO_WINDOW_TICKS), 2);
+        let mut tick = 10 + COMBO_WINDOW_TICKS;
+        for multiplier in 3..=COMBO_MAX_MULTIPLIER {
+            tick += 1;
+            assert_eq!(combo.register_food(tick), multiplier);
+        }
+        tick += 1;
+        assert_eq!(combo.register_food(tick), COMBO_MAX_MULTIPLIER);
+        assert_eq!(combo.count, COMBO_MAX_MULTIPLIER + 1);
+
+        assert_eq!(combo.register_food(tick + COMBO_WINDOW_TICKS + 1), 1);
+        assert_eq!(combo.count, 1);
+    }
+
+    #[test]
+    fn update_breaks_combo() {
+        let food = Position { x: 5, y: 5 };
+        let mut combo = Combo::default();
+        combo.register_food(0);
+        combo.register_food(1);
+
+        combo.update(1 + COMBO_WINDOW_TICKS, &Position { x: 0, y: 0 }, &[]);
+        assert_eq!(combo.count, 2);
+        combo.update(2 + COMBO_WINDOW_TICKS, &Position { x: 0, y: 0 }, &[]);
+        assert_eq!(combo.count, 0);
+
+        // la tête passe à côté de la nourriture sans la manger
+        combo.register_food(30);
+        combo.update(31, &Position { x: 4, y: 5 }, &[food]);
+        assert_eq!(combo.count, 1);
+        combo.update(32, &Position { x: 4, y: 6 }, &[food]);
+        assert_eq!(combo.count, 0);
+
+        // la nourriture voisine mangée au déplacement suivant ne casse pas la série
+        combo.register_food(40);
+        combo.update(41, &Position { x: 5, y: 4 }, &[food]);
+        combo.update(42, &food, &[]);
+        assert_eq!(combo.register_food(42), 2);
+    }
+}
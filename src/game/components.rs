@@ -1,10 +1,11 @@
 use std::collections::VecDeque;
 
 use bevy::{prelude::{Component, Color}, time::{Timer, TimerMode}};
+use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
 // region:    --- Common Component
-#[derive(Component, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
@@ -64,9 +65,12 @@ impl Default for BonusTimer {
 #[derive(Component)]
 pub struct SnakeHead {
     pub moved: bool,
-    pub ate: bool,
+    /// Nombre de déplacements restants pendant lesquels la queue ne raccourcit pas.
+    pub growth: u32,
     pub invincible: bool,
-    pub body_parts: VecDeque<SnakeBody>
+    pub body_parts: VecDeque<SnakeBody>,
+    /// Case occupée avant le dernier déplacement, pour l'affichage fluide.
+    pub previous_position: Option<Position>
 }
 
 #[derive(Component, Clone)]
@@ -75,7 +79,24 @@ pub struct SnakeBody {
     pub id: Uuid
 }
 
-#[derive(Component, Debug)]
+/// Marque la tête du serpent contrôlé par le joueur.
+#[derive(Component)]
+pub struct Player;
+
+/// Marque la tête et le corps d'un serpent adverse contrôlé par l'ordinateur.
+#[derive(Component)]
+pub struct Enemy;
+
+/// Marque la tête du serpent du joueur lorsqu'il est dirigé par l'ordinateur.
+#[derive(Component)]
+pub struct Bot;
+
+/// Chemin prévu par l'ordinateur vers la nourriture la plus proche, vide s'il n'en trouve pas.
+#[derive(Component)]
+pub struct PlannedPath(pub Vec<Position>);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum Direction {
     UP,
     DOWN,
@@ -83,6 +104,17 @@ pub enum Direction {
     RIGHT
 }
 
+impl Direction {
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::UP => Direction::DOWN,
+            Direction::DOWN => Direction::UP,
+            Direction::LEFT => Direction::RIGHT,
+            Direction::RIGHT => Direction::LEFT
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct Velocity {
     pub x: f32,
@@ -93,14 +125,9 @@ pub struct Velocity {
 
 // region:    --- Food Component
 
-pub enum FoodType {
-    Simple,
-    Gold,
-    Bonus
-}
-
+/// Nourriture, repérée par son index dans le catalogue (`FoodCatalogue`).
 #[derive(Component)]
-pub struct Food(pub FoodType);
+pub struct Food(pub usize);
 
 #[derive(Component)]
 pub struct FoodTimer(pub Timer);
@@ -117,7 +144,35 @@ impl Default for FoodTimer {
 #[derive(Component)]
 pub struct Wall;
 
+/// Bord de l'arène, dessiné hors de la grille.
+#[derive(Component)]
+pub struct ExteriorWall;
+
 #[derive(Component)]
 pub struct Collision;
 
-// endregion: --- Wall Component
\ No newline at end of file
+/// Mur qui glisse d'avant en arrière le long de son chemin.
+#[derive(Component)]
+pub struct MovingObstacle {
+    pub path: Vec<Position>,
+    pub index: usize,
+    pub forward: bool
+}
+
+/// Obstacle qui apparaît et disparaît à intervalle régulier (pics).
+#[derive(Component)]
+pub struct TimedObstacle {
+    pub active: bool
+}
+
+// endregion: --- Wall Component
+
+// region:    --- Portal Component
+
+#[derive(Component)]
+pub struct Portal {
+    pub exit: Position,
+    pub hue: f32
+}
+
+// endregion: --- Portal Component
\ No newline at end of file
@@ -0,0 +1,239 @@
+use std::{fs, collections::BTreeMap, time::{SystemTime, UNIX_EPOCH}};
+
+use bevy::prelude::*;
+use iyes_loopless::prelude::IntoConditionalSystem;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Serialize, Deserialize};
+
+use crate::main_menu::sub_menu::{GameType, Controller, MAX_ENEMIES, MODE_COUNT, mode_name, mode_multiplier};
+
+use super::{AppState, Score, SAVE_DIRECTORY, DAILY_SPEEDS, DAILY_MOVE_LIMITS, save::SavedGame, debug::DebugConsole, simulation::Simulation, replay::RunRecording, settings::Settings, stats::RunStats};
+
+// region:    --- Daily Challenge
+
+/// Date du jour en temps universel, la même pour tous les joueurs quel que soit leur fuseau (AAAA-MM-JJ).
+pub fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() / 86_400) as i64;
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Date du calendrier grégorien à partir du nombre de jours écoulés depuis le 1er janvier 1970.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // les années commencent au 1er mars pour que le 29 février soit le dernier jour
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Graine tirée des caractères de la date (AAAA-MM-JJ).
+fn date_seed(date: &str) -> u64 {
+    date.bytes().fold(0u64, |seed, byte| seed.wrapping_mul(31).wrapping_add(byte as u64))
+}
+
+/// Partie du défi du jour : la graine, le mode et les modificateurs ne dépendent que de la date.
+pub fn daily_game_type(date: &str) -> GameType {
+    // ChaCha8 garde le même tirage d'une version de rand à l'autre, contrairement à StdRng
+    let mut rng = ChaCha8Rng::seed_from_u64(date_seed(date));
+    let wall_type = rng.gen_range(0..MODE_COUNT);
+
+    GameType {
+        wall_type,
+        multiplier: mode_multiplier(wall_type),
+        seed: rng.gen(),
+        enemies: rng.gen_range(0..=MAX_ENEMIES),
+        speed: DAILY_SPEEDS[rng.gen_range(0..DAILY_SPEEDS.len())],
+        move_limit: DAILY_MOVE_LIMITS[rng.gen_range(0..DAILY_MOVE_LIMITS.len())],
+        daily: Some(date.to_owned()),
+        ..Default::default()
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct DailyRecord {
+    /// Parties commencées, même abandonnées.
+    pub attempts: u32,
+    /// Meilleur score des parties qui comptent.
+    pub best_score: Option<u32>,
+    /// Résultat à partager du meilleur score.
+    pub share: Option<String>
+}
+
+/// Résultats du défi par date, tous profils confondus, sauvegardés dans `SAVE_DIRECTORY`.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct DailyHistory {
+    pub days: BTreeMap<String, DailyRecord>
+}
+
+impl DailyHistory {
+    fn path() -> String {
+        format!("{}/daily.ron", SAVE_DIRECTORY)
+    }
+
+    fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| ron::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let saved = fs::create_dir_all(SAVE_DIRECTORY)
+            .ok()
+            .and_then(|_| ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).ok())
+            .and_then(|content| fs::write(Self::path(), content).ok());
+
+        if saved.is_none() {
+            println!("impossible de sauvegarder l'historique du défi dans {}", Self::path());
+        }
+    }
+}
+
+/// Partie du défi en cours ou qui vient de finir.
+#[derive(Resource)]
+pub(super) struct DailyAttempt {
+    date: String,
+    /// Numéro de la tentative dans la journée, à partir de 1.
+    pub(super) attempt: u32,
+    /// Faux pour une nouvelle tentative quand seule la première partie compte.
+    pub(super) counted: bool,
+    /// Résultat à partager, connu une fois la partie finie.
+    pub(super) share: Option<String>
+}
+
+/// Dernier résultat du défi, à copier pour le partager.
+pub(super) fn share_path() -> String {
+    format!("{}/daily_share.txt", SAVE_DIRECTORY)
+}
+
+// endregion: --- Daily Challenge
+
+/// Défi du jour : une tentative est comptée dès le début de la partie, son résultat à la fin.
+pub struct DailyPlugin;
+
+impl Plugin for DailyPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(DailyHistory::load())
+        .add_system_set(
+            SystemSet::on_enter(AppState::InGame)
+            .with_system(daily_attempt_setup_system.run_unless_resource_exists::<Simulation>())
+        )
+        .add_system_set(SystemSet::on_update(AppState::GameOver(false)).with_system(daily_record_system.run_unless_resource_exists::<Simulation>()))
+        .add_system_set(SystemSet::on_update(AppState::GameOver(true)).with_system(daily_record_system.run_unless_resource_exists::<Simulation>()));
+    }
+}
+
+/// Une partie reprise depuis une sauvegarde reste la même tentative.
+fn daily_attempt_setup_system(
+    mut commands: Commands,
+    game_type: Res<GameType>,
+    settings: Res<Settings>,
+    saved_game: Option<Res<SavedGame>>,
+    mut daily_history: ResMut<DailyHistory>,
+) {
+    let date = match &game_type.daily {
+        Some(date) if game_type.controller == Controller::Human => date.clone(),
+        _ => {
+            commands.remove_resource::<DailyAttempt>();
+            return;
+        }
+    };
+
+    let daily_record = daily_history.days.entry(date.clone()).or_default();
+    if saved_game.is_none() || daily_record.attempts == 0 {
+        daily_record.attempts += 1;
+        daily_history.save();
+    }
+
+    let attempt = daily_history.days[&date].attempts;
+    commands.insert_resource(DailyAttempt {
+        date,
+        attempt,
+        counted: !settings.daily_first_attempt_only || attempt == 1,
+        share: None
+    });
+}
+
+/// L'issue de la partie est connue au plus tard à la première image de l'écran de fin.
+/// Une partie modifiée avec la console de développement n'a ni record ni résultat à partager.
+fn daily_record_system(
+    score: Res<Score>,
+    game_type: Res<GameType>,
+    run_stats: Res<RunStats>,
+    run_recording: Res<RunRecording>,
+    debug_console: Option<Res<DebugConsole>>,
+    daily_attempt: Option<ResMut<DailyAttempt>>,
+    mut daily_history: ResMut<DailyHistory>,
+) {
+    let Some(mut daily_attempt) = daily_attempt else {
+        return;
+    };
+    if daily_attempt.share.is_some() || run_stats.outcome.is_none() || debug_console.is_some_and(|debug_console| debug_console.used) {
+        return;
+    }
+
+    let share = format!(
+        "Snake - Défi du jour {} : {} points ({}, tentative {}), directions #{:016x}",
+        daily_attempt.date, score.0, mode_name(game_type.wall_type), daily_attempt.attempt, run_recording.direction_hash(game_type.seed)
+    );
+
+    if daily_attempt.counted {
+        let daily_record = daily_history.days.entry(daily_attempt.date.clone()).or_default();
+        if daily_record.best_score.is_none_or(|best_score| score.0 > best_score) {
+            daily_record.best_score = Some(score.0);
+            daily_record.share = Some(share.clone());
+            daily_history.save();
+        }
+    }
+
+    if fs::create_dir_all(SAVE_DIRECTORY).and_then(|_| fs::write(share_path(), &share)).is_err() {
+        println!("impossible d'écrire le résultat du défi dans {}", share_path());
+    }
+    daily_attempt.share = Some(share);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_from_days_known_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+        assert_eq!(civil_from_days(19_783), (2024, 3, 1));
+    }
+
+    #[test]
+    fn date_seed_known_dates() {
+        assert_eq!(date_seed("1970-01-01"), 1_345_713_403_105_153);
+        assert_eq!(date_seed("2000-02-29"), 1_364_284_417_659_939);
+        assert_eq!(date_seed("2000-03-01"), 1_364_284_417_689_660);
+        assert_eq!(date_seed("2024-02-29"), 1_364_342_992_902_885);
+    }
+
+    /// Tous les joueurs doivent avoir le même défi : un changement de tirage change les défis passés.
+    #[test]
+    fn daily_game_type_known_date() {
+        let game_type = daily_game_type("2000-02-29");
+        assert_eq!(
+            (game_type.wall_type, game_type.seed, game_type.enemies, game_type.speed, game_type.move_limit),
+            (4, 7_900_235_965_503_428_788, 1, 1.25, None)
+        );
+        assert_eq!(game_type.multiplier, mode_multiplier(4));
+        assert_eq!(game_type.daily.as_deref(), Some("2000-02-29"));
+    }
+}
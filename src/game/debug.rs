@@ -0,0 +1,355 @@
+use std::mem;
+
+use bevy::{prelude::*, diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin}};
+use iyes_loopless::prelude::IntoConditionalSystem;
+
+use crate::{SPEED_MIN, SPEED_MAX, main_menu::sub_menu::GameType};
+
+use super::{AppState, ArenaSize, GameTick, GameEvent, PositionsAvailable, start_invincibility, shrink_snake, food::{food_spawn, FoodCatalogue}, stats::DeathCause, theme::{ThemeTextures, Palette}, components::{SnakeHead, SnakeBody, Food, Wall, Player, Position, BonusTimer}, BONUS_LIFE_CYCLES, DEBUG_FONT_SIZE, DEBUG_CONSOLE_MAX_LENGTH};
+
+const CONSOLE_HELP: &str = "aide | nourriture <nom ou numéro> <x> <y> | invincible | vitesse <facteur> | grandir <n> | raccourcir <n> | teleporter <x> <y> | fin [victoire]";
+
+// region:    --- Resource
+
+/// Informations de développement, affichées ou masquées avec F3.
+#[derive(Resource, Default)]
+struct DebugOverlay(bool);
+
+/// Console de développement de la partie en cours, ouverte et fermée avec F4 (ou Échap pour la fermer).
+#[derive(Resource, Default)]
+pub struct DebugConsole {
+    open: bool,
+    input: String,
+    /// Commande validée, exécutée à l'image suivante.
+    pending: Option<String>,
+    /// Réponse à la dernière commande.
+    message: String,
+    /// Vitesse de la partie avant la commande `vitesse`, rétablie à la fin de la partie.
+    original_speed: Option<f32>,
+    /// Vrai dès qu'une commande a modifié la partie : elle ne compte ni dans le profil, ni pour les succès,
+    /// le fantôme, le défi du jour ou le classement. Gardé dans la partie sauvegardée.
+    pub used: bool
+}
+
+// endregion: --- Resource
+
+// region:    --- Component
+
+#[derive(Component)]
+struct DebugText;
+
+// endregion: --- Component
+
+enum ConsoleCommand {
+    Help,
+    SpawnFood { kind_index: usize, position: Position },
+    Invincible,
+    Speed(f32),
+    Grow(u32),
+    Shrink(u32),
+    Teleport(Position),
+    GameOver { won: bool }
+}
+
+impl ConsoleCommand {
+    fn parse(line: &str, food_catalogue: &FoodCatalogue, arena_size: &ArenaSize) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let number = |index: usize| -> Result<u32, String> {
+            let word = words.get(index).ok_or("argument manquant")?;
+            word.parse::<u32>().map_err(|_| format!("nombre invalide : {}", word))
+        };
+        let position = |index: usize| -> Result<Position, String> {
+            let (x, y) = (number(index)?, number(index + 1)?);
+            if x >= arena_size.tile_width || y >= arena_size.tile_height {
+                return Err(format!("case hors de l'arène : ({}, {})", x, y));
+            }
+            Ok(Position { x: x as i32, y: y as i32 })
+        };
+
+        match words.as_slice() {
+            ["aide"] => Ok(ConsoleCommand::Help),
+            ["nourriture", kind, ..] => {
+                let kind_index = kind.parse::<usize>().ok()
+                    .filter(|kind_index| *kind_index < food_catalogue.kinds.len())
+                    .or_else(|| food_catalogue.kinds.iter().position(|food_kind| food_kind.name == *kind))
+                    .ok_or_else(|| format!("nourriture inconnue : {}", kind))?;
+                Ok(ConsoleCommand::SpawnFood { kind_index, position: position(2)? })
+            },
+            ["invincible"] => Ok(ConsoleCommand::Invincible),
+            ["vitesse", speed] => {
+                let speed = speed.parse::<f32>().map_err(|_| format!("vitesse invalide : {}", speed))?;
+                if !(SPEED_MIN..=SPEED_MAX).contains(&speed) {
+                    return Err(format!("vitesse hors limites : {} (de {} à {})", speed, SPEED_MIN, SPEED_MAX));
+                }
+                Ok(ConsoleCommand::Speed(speed))
+            },
+            ["grandir", _] => Ok(ConsoleCommand::Grow(number(1)?)),
+            ["raccourcir", _] => Ok(ConsoleCommand::Shrink(number(1)?)),
+            ["teleporter", ..] => Ok(ConsoleCommand::Teleport(position(1)?)),
+            ["fin"] => Ok(ConsoleCommand::GameOver { won: false }),
+            ["fin", "victoire"] => Ok(ConsoleCommand::GameOver { won: true }),
+            _ => Err(format!("commande inconnue : {} (aide pour la liste)", line))
+        }
+    }
+}
+
+/// Ajouté seulement avec une fenêtre : la mesure des images par seconde a besoin des plugins par défaut.
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_plugin(FrameTimeDiagnosticsPlugin)
+        .init_resource::<DebugOverlay>()
+        .init_resource::<DebugConsole>()
+        .add_system(debug_overlay_system)
+        .add_system_set(
+            SystemSet::on_enter(AppState::InGame)
+            .with_system(debug_console_reset_system)
+        )
+        // la console passe avant les touches de la partie, qui ne reçoit pas ce qui y est tapé
+        .add_system(
+            debug_console_input_system
+                .run_in_bevy_state(AppState::InGame)
+                .label("debug_console")
+                .before("game_input")
+        )
+        .add_system(
+            debug_console_command_system
+                .run_in_bevy_state(AppState::InGame)
+                .after("debug_console")
+                .before("game_input")
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::InGame)
+            .with_system(debug_console_cleanup_system)
+        );
+    }
+}
+
+fn debug_console_reset_system(mut debug_console: ResMut<DebugConsole>) {
+    debug_console.used = false;
+    debug_console.message = String::new();
+}
+
+fn debug_console_input_system(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut received_characters: EventReader<ReceivedCharacter>,
+    mut debug_console: ResMut<DebugConsole>,
+) {
+    let typed: String = received_characters.iter().map(|received_character| received_character.char).collect();
+    if !debug_console.open {
+        if keys.just_pressed(KeyCode::F4) {
+            debug_console.open = true;
+        }
+        return;
+    }
+
+    for character in typed.chars().filter(|character| !character.is_control()) {
+        if debug_console.input.chars().count() < DEBUG_CONSOLE_MAX_LENGTH {
+            debug_console.input.push(character);
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        debug_console.input.pop();
+    }
+    if keys.just_pressed(KeyCode::Return) && !debug_console.input.trim().is_empty() {
+        debug_console.pending = Some(mem::take(&mut debug_console.input));
+    }
+    if keys.just_pressed(KeyCode::Escape) || keys.just_pressed(KeyCode::F4) {
+        debug_console.open = false;
+        debug_console.input.clear();
+    }
+
+    keys.reset_all();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn debug_console_command_system(
+    mut commands: Commands,
+    mut debug_console: ResMut<DebugConsole>,
+    mut app_state: ResMut<State<AppState>>,
+    mut game_type: ResMut<GameType>,
+    arena_size: Res<ArenaSize>,
+    mut assets: (ResMut<Assets<Mesh>>, ResMut<Assets<ColorMaterial>>),
+    food_catalogue: Res<FoodCatalogue>,
+    theme_textures: Res<ThemeTextures>,
+    palette: Res<Palette>,
+    mut snake_head_query: Query<(Entity, &mut Position, &mut SnakeHead, Option<&mut BonusTimer>), With<Player>>,
+    mut snake_body_query: Query<(Entity, &mut SnakeBody)>,
+    mut game_events: EventWriter<GameEvent>,
+) {
+    if debug_console.pending.is_none() {
+        return;
+    }
+    let line = debug_console.pending.take().unwrap_or_default();
+
+    let result = ConsoleCommand::parse(&line, &food_catalogue, &arena_size).and_then(|console_command| {
+        let modifies_game = !matches!(console_command, ConsoleCommand::Help);
+        let (snake_entity, mut head_position, mut snake_head, bonus_timer) = snake_head_query.get_single_mut()
+            .map_err(|_| "pas de serpent du joueur".to_owned())?;
+
+        let message = match console_command {
+            ConsoleCommand::Help => CONSOLE_HELP.to_owned(),
+            ConsoleCommand::SpawnFood { kind_index, position } => {
+                let (meshes, materials) = &mut assets;
+                food_spawn(&mut commands, meshes, materials, &arena_size, &food_catalogue, &theme_textures, &palette, kind_index, position);
+                format!("{} en ({}, {})", food_catalogue.kinds[kind_index].name, position.x, position.y)
+            },
+            ConsoleCommand::Invincible => match bonus_timer {
+                // la dernière étape se termine tout de suite : la fin habituelle rétablit les couleurs
+                Some(mut bonus_timer) => {
+                    bonus_timer.life_cycle = BONUS_LIFE_CYCLES.len() - 1;
+                    bonus_timer.life_timer = Some(Timer::from_seconds(0., TimerMode::Once));
+                    "fin de l'invincibilité".to_owned()
+                },
+                None => {
+                    start_invincibility(&mut commands, snake_entity, &mut snake_head, &mut game_events);
+                    "invincible".to_owned()
+                }
+            },
+            ConsoleCommand::Speed(speed) => {
+                debug_console.original_speed.get_or_insert(game_type.speed);
+                game_type.speed = speed;
+                format!("vitesse x{}", speed)
+            },
+            ConsoleCommand::Grow(length) => {
+                snake_head.growth += length;
+                format!("+{} parties de corps", length)
+            },
+            ConsoleCommand::Shrink(length) => {
+                shrink_snake(&mut snake_head, &mut snake_body_query, &mut commands, length);
+                format!("longueur {}", 1 + snake_head.body_parts.len())
+            },
+            ConsoleCommand::Teleport(position) => {
+                *head_position = position;
+                format!("tête en ({}, {})", position.x, position.y)
+            },
+            ConsoleCommand::GameOver { won } => {
+                if won {
+                    game_events.send(GameEvent::ArenaFilled);
+                } else {
+                    game_events.send(GameEvent::Died { cause: DeathCause::Console, position: *head_position });
+                }
+                app_state.set(AppState::GameOver(won)).map_err(|error| error.to_string())?;
+                "fin de partie".to_owned()
+            }
+        };
+        debug_console.used |= modifies_game;
+        Ok(message)
+    });
+
+    debug_console.message = match result {
+        Ok(message) => message,
+        Err(error) => format!("erreur : {}", error)
+    };
+}
+
+/// La vitesse modifiée ne doit pas être reprise par la partie suivante.
+fn debug_console_cleanup_system(mut debug_console: ResMut<DebugConsole>, mut game_type: ResMut<GameType>) {
+    if let Some(speed) = debug_console.original_speed.take() {
+        game_type.speed = speed;
+    }
+    debug_console.open = false;
+    debug_console.input.clear();
+    debug_console.pending = None;
+}
+
+/// Les informations restent affichées dans les menus ; le texte est recréé s'il a été effacé avec l'écran précédent.
+#[allow(clippy::too_many_arguments)]
+fn debug_overlay_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    keys: Res<Input<KeyCode>>,
+    mut debug_overlay: ResMut<DebugOverlay>,
+    debug_console: Res<DebugConsole>,
+    diagnostics: Res<Diagnostics>,
+    app_state: Res<State<AppState>>,
+    food_catalogue: Res<FoodCatalogue>,
+    game_tick: Option<Res<GameTick>>,
+    positions_available: Option<Res<PositionsAvailable>>,
+    entity_query: Query<Entity>,
+    snake_head_query: Query<&SnakeHead>,
+    snake_body_query: Query<(), With<SnakeBody>>,
+    food_query: Query<&Food>,
+    wall_query: Query<(), With<Wall>>,
+    mut debug_text_query: Query<(Entity, &mut Text), With<DebugText>>,
+) {
+    if keys.just_pressed(KeyCode::F3) {
+        debug_overlay.0 = !debug_overlay.0;
+    }
+
+    let shown = debug_overlay.0 || debug_console.open;
+    if !shown {
+        for (entity, _) in debug_text_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    let mut lines = Vec::new();
+    if debug_overlay.0 {
+        let fps = diagnostics.get(FrameTimeDiagnosticsPlugin::FPS).and_then(|fps| fps.smoothed()).unwrap_or_default();
+        // la nourriture présente par genre, avec le maximum du catalogue quand il y en a un
+        let foods = food_catalogue.kinds.iter().enumerate()
+            .filter_map(|(kind_index, food_kind)| {
+                let count = food_query.iter().filter(|food| food.0 == kind_index).count();
+                food_kind.max_count.map(|max_count| format!("{} {}/{}", food_kind.name, count, max_count))
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        let body_parts: usize = snake_head_query.iter().map(|snake_head| snake_head.body_parts.len()).sum();
+        let states = app_state.inactives().iter()
+            .chain([app_state.current()])
+            .map(|state| format!("{:?}", state))
+            .collect::<Vec<String>>()
+            .join(" > ");
+
+        lines.push(format!("images/s : {:.0}", fps));
+        lines.push(format!("déplacements : {}", game_tick.map_or("-".to_owned(), |game_tick| game_tick.0.to_string())));
+        lines.push(format!("entités : {}", entity_query.iter().count()));
+        lines.push(format!("corps : {} entités pour {} parties", snake_body_query.iter().count(), body_parts));
+        lines.push(format!("nourritures : {} ({})", food_query.iter().count(), foods));
+        lines.push(format!("murs : {}", wall_query.iter().count()));
+        lines.push(format!("cases disponibles : {}", positions_available.map_or("-".to_owned(), |positions_available| positions_available.0.len().to_string())));
+        lines.push(format!("états : {}", states));
+    }
+    if debug_console.open {
+        lines.push(String::new());
+        lines.push(debug_console.message.clone());
+        lines.push(format!("> {}_", debug_console.input));
+    }
+    let value = lines.join("\n");
+
+    match debug_text_query.get_single_mut() {
+        Ok((_, mut text)) => {
+            if text.sections[0].value != value {
+                text.sections[0].value = value;
+            }
+        },
+        Err(_) => {
+            commands.spawn(TextBundle {
+                text: Text::from_section(
+                    value,
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: DEBUG_FONT_SIZE,
+                        color: Color::WHITE,
+                    }
+                ),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(5.),
+                        bottom: Val::Px(5.),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                z_index: ZIndex::Global(i32::MAX),
+                ..Default::default()
+            })
+            .insert(DebugText);
+        }
+    }
+}
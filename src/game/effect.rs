@@ -0,0 +1,136 @@
+use std::{mem, time::Duration};
+
+use bevy::{prelude::{Plugin, App, Commands, Res, ResMut, Query, With, SystemSet, Resource, CoreStage}, sprite::Sprite, time::{Time, Timer, TimerMode}};
+use iyes_loopless::{prelude::{ConditionSet, IntoConditionalSystem, ConditionHelpers}, fixedtimestep::FixedTimesteps};
+
+use crate::main_menu::sub_menu::GameType;
+
+use super::{AppState, SNAKE_MOVE_TIME, GHOST_ALPHA, food::FoodEffect, components::{SnakeHead, Player}};
+
+// region:    --- Resource
+
+/// Effets temporaires des nourritures actuellement actifs sur le joueur, avec le temps restant.
+#[derive(Resource, Default)]
+pub struct ActiveEffects(pub Vec<(FoodEffect, Timer)>);
+
+impl ActiveEffects {
+    /// Active un effet temporaire ; un effet du même genre déjà actif est remplacé.
+    pub fn add(&mut self, effect: FoodEffect) {
+        let duration = match effect {
+            FoodEffect::SpeedUp { duration, .. }
+            | FoodEffect::SlowDown { duration, .. }
+            | FoodEffect::ReverseControls { duration }
+            | FoodEffect::ScoreMultiplier { duration, .. }
+            | FoodEffect::Ghost { duration } => duration,
+            FoodEffect::Invincibility | FoodEffect::Shrink(_) => return
+        };
+
+        self.0.retain(|(active_effect, _)| mem::discriminant(active_effect) != mem::discriminant(&effect));
+        self.0.push((effect, Timer::from_seconds(duration, TimerMode::Once)));
+    }
+
+    pub fn speed_factor(&self) -> f32 {
+        self.0.iter().fold(1., |factor, (effect, _)| match effect {
+            FoodEffect::SpeedUp { factor: speed_up, .. } => factor * speed_up,
+            FoodEffect::SlowDown { factor: slow_down, .. } => factor / slow_down,
+            _ => factor
+        })
+    }
+
+    pub fn score_multiplier(&self) -> u32 {
+        self.0.iter().fold(1, |multiplier, (effect, _)| match effect {
+            FoodEffect::ScoreMultiplier { factor, .. } => multiplier * factor,
+            _ => multiplier
+        })
+    }
+
+    pub fn reverse_controls(&self) -> bool {
+        self.0.iter().any(|(effect, _)| matches!(effect, FoodEffect::ReverseControls { .. }))
+    }
+
+    pub fn ghost(&self) -> bool {
+        self.0.iter().any(|(effect, _)| matches!(effect, FoodEffect::Ghost { .. }))
+    }
+}
+
+// endregion: --- Resource
+
+pub struct EffectPlugin;
+
+impl Plugin for EffectPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_system_set(
+            SystemSet::on_enter(AppState::InGame)
+            .with_system(effects_setup_system)
+        )
+        .add_system(
+            effects_timer_system
+                .run_in_bevy_state(AppState::InGame)
+                .run_if_resource_exists::<ActiveEffects>()
+                .label("game_effects")
+                .after("game_walls")
+        )
+        .add_system_set_to_stage(
+            CoreStage::PostUpdate,
+            ConditionSet::new()
+            .run_in_bevy_state(AppState::InGame)
+            .run_if_resource_exists::<ActiveEffects>()
+            .with_system(ghost_transparency_system)
+            .into()
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::InGame)
+            .with_system(cleanup_effects_system)
+        );
+    }
+}
+
+fn effects_setup_system(mut commands: Commands) {
+    commands.insert_resource(ActiveEffects::default());
+}
+
+/// Les effets durent un temps réel : comptés en déplacements, une accélération durerait moins longtemps
+/// et un ralentissement plus longtemps que la durée du catalogue.
+fn effects_timer_system(
+    time: Res<Time>,
+    game_type: Res<GameType>,
+    mut active_effects: ResMut<ActiveEffects>,
+    fixed_timesteps: Option<ResMut<FixedTimesteps>>,
+) {
+    for (_, timer) in active_effects.0.iter_mut() {
+        timer.tick(time.delta());
+    }
+    active_effects.0.retain(|(_, timer)| !timer.finished());
+
+    // la vitesse de tous les serpents suit celle de la partie et les effets d'accélération et de ralentissement
+    set_snake_move_step(fixed_timesteps, game_type.speed * active_effects.speed_factor());
+}
+
+/// La ressource `FixedTimesteps` n'existe qu'après le premier pas de temps fixe.
+fn set_snake_move_step(fixed_timesteps: Option<ResMut<FixedTimesteps>>, speed_factor: f32) {
+    if let Some(snake_move_time) = fixed_timesteps.and_then(|fixed_timesteps| fixed_timesteps.into_inner().get_mut("snake_move_time")) {
+        let step = Duration::from_secs_f32(Duration::from_millis(SNAKE_MOVE_TIME).as_secs_f32() / speed_factor);
+        if snake_move_time.step != step {
+            snake_move_time.step = step;
+        }
+    }
+}
+
+fn ghost_transparency_system(
+    active_effects: Res<ActiveEffects>,
+    mut snake_head_query: Query<&mut Sprite, (With<SnakeHead>, With<Player>)>,
+) {
+    let alpha = if active_effects.ghost() { GHOST_ALPHA } else { 1. };
+
+    for mut sprite in snake_head_query.iter_mut() {
+        if sprite.color.a() != alpha {
+            sprite.color.set_a(alpha);
+        }
+    }
+}
+
+fn cleanup_effects_system(mut commands: Commands, fixed_timesteps: Option<ResMut<FixedTimesteps>>) {
+    set_snake_move_step(fixed_timesteps, 1.);
+    commands.remove_resource::<ActiveEffects>();
+}
@@ -0,0 +1,352 @@
+use std::collections::{HashSet, HashMap, VecDeque};
+
+use bevy::{prelude::{Plugin, App, Commands, Res, ResMut, Query, Entity, With, Or, SystemSet, Resource, Transform, Vec3, Assets, Mesh}, sprite::{SpriteBundle, Sprite, ColorMaterial}, time::{Time, Timer, TimerMode}};
+use iyes_loopless::prelude::{IntoConditionalSystem, ConditionHelpers, AppLooplessFixedTimestepExt};
+use rand::{SeedableRng, seq::SliceRandom};
+use rand_chacha::ChaCha8Rng;
+use uuid::Uuid;
+
+use crate::main_menu::sub_menu::GameType;
+
+use super::{AppState, ArenaSize, PositionsAvailable, next_head_position, is_same_position, food::{food_spawn, random_food_kind, is_kind_allowed, FoodCatalogue, FoodRng}, theme::{Palette, ThemeTextures}, ENEMY_SPAWN_DELAY, ENEMY_SPAWN_MIN_DISTANCE, SNAKE_HEAD_SIZE, SNAKE_BODY_SIZE};
+use super::components::{SnakeHead, SnakeBody, Direction, Position, Size, Food, Wall, Collision, Portal, Player, Enemy, Bot, PlannedPath};
+
+const DIRECTIONS: [Direction; 4] = [Direction::UP, Direction::DOWN, Direction::LEFT, Direction::RIGHT];
+
+// region:    --- Resource
+
+/// Nombre d'adversaires à (ré)introduire dans l'arène et délai entre deux apparitions.
+#[derive(Resource)]
+pub(super) struct EnemySpawner {
+    pub(super) timer: Timer,
+    pub(super) missing: u32,
+    /// Hasard des apparitions, tiré de `GameType::seed` pour pouvoir rejouer la partie.
+    pub(super) rng: ChaCha8Rng
+}
+
+impl EnemySpawner {
+    /// Graine distincte de celle des nourritures.
+    pub(super) fn rng_from_seed(seed: u64) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(!seed)
+    }
+}
+
+// endregion: --- Resource
+
+pub struct EnemyPlugin;
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_system_set(
+            SystemSet::on_enter(AppState::InGame)
+            .with_system(enemy_spawner_setup_system)
+        )
+        .add_fixed_timestep_system(
+            "snake_move_time",
+            0,
+            enemy_direction_system
+                .run_in_bevy_state(AppState::InGame)
+        )
+        .add_fixed_timestep_system(
+            "snake_move_time",
+            2,
+            enemy_collision_system
+                .run_in_bevy_state(AppState::InGame)
+                .run_if_resource_exists::<EnemySpawner>()
+                .label("enemy_collision")
+                .after("snake_ate_food")
+        )
+        .add_system(
+            enemy_spawn_system
+                .run_in_bevy_state(AppState::InGame)
+                .run_if_resource_exists::<EnemySpawner>()
+                .label("game_spawn")
+                .after("food_spawn")
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::InGame)
+            .with_system(cleanup_enemy_system)
+        );
+    }
+}
+
+fn enemy_spawner_setup_system(mut commands: Commands, game_type: Res<GameType>) {
+    commands.insert_resource(EnemySpawner {
+        timer: Timer::from_seconds(ENEMY_SPAWN_DELAY, TimerMode::Repeating),
+        missing: game_type.enemies,
+        rng: EnemySpawner::rng_from_seed(game_type.seed)
+    });
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn enemy_spawn_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    arena_size: Res<ArenaSize>,
+    game_type: Res<GameType>,
+    palette: Res<Palette>,
+    positions_available: Res<PositionsAvailable>,
+    mut enemy_spawner: ResMut<EnemySpawner>,
+    occupied_query: Query<&Position, Or<(With<SnakeHead>, With<SnakeBody>, With<Food>)>>,
+    player_query: Query<&Position, With<Player>>,
+) {
+    if enemy_spawner.missing == 0 {
+        return;
+    }
+
+    enemy_spawner.timer.tick(time.delta());
+    if !enemy_spawner.timer.just_finished() {
+        return;
+    }
+
+    if let Ok(player_position) = player_query.get_single() {
+        let mut free_positions = positions_available.0.clone();
+        for position in occupied_query.iter() {
+            free_positions.remove(position);
+        }
+
+        if let Some((head_position, body_position, direction)) = find_spawn_place(&mut enemy_spawner.rng, &free_positions, player_position, &arena_size, game_type.wall_type) {
+            enemy_snake_spawn(&mut commands, &palette, head_position, body_position, direction);
+            enemy_spawner.missing -= 1;
+        }
+    }
+}
+
+/// Cherche une case libre loin du joueur avec de la place devant et derrière pour y faire apparaître un adversaire.
+fn find_spawn_place(rng: &mut ChaCha8Rng, free_positions: &HashSet<Position>, player_position: &Position, arena_size: &ArenaSize, wall_type: usize) -> Option<(Position, Position, Direction)> {
+    let mut candidates: Vec<&Position> = free_positions.iter()
+        .filter(|position| (position.x - player_position.x).abs() + (position.y - player_position.y).abs() >= ENEMY_SPAWN_MIN_DISTANCE)
+        .collect();
+    // l'ordre d'un HashSet change d'une exécution à l'autre : le mélange part d'un ordre fixe
+    candidates.sort_by_key(|position| (position.y, position.x));
+    candidates.shuffle(rng);
+
+    for head_position in candidates {
+        let mut directions = DIRECTIONS;
+        directions.shuffle(rng);
+
+        for direction in directions {
+            let body_position = next_head_position(head_position, &direction.opposite(), arena_size, wall_type);
+            let ahead = next_head_position(head_position, &direction, arena_size, wall_type);
+            let further_ahead = next_head_position(&ahead, &direction, arena_size, wall_type);
+
+            if [body_position, ahead, further_ahead].iter().all(|position| free_positions.contains(position)) {
+                return Some((*head_position, body_position, direction));
+            }
+        }
+    }
+
+    None
+}
+
+fn enemy_snake_spawn(commands: &mut Commands, palette: &Palette, head_position: Position, body_position: Position, direction: Direction) {
+    let snake_body = SnakeBody {
+        is_tail: true,
+        id: Uuid::new_v4()
+    };
+
+    commands.spawn(SpriteBundle {
+        sprite: Sprite {
+            color: palette.enemy_body,
+            ..Default::default()
+        },
+        transform: Transform {
+            translation: Vec3::new(body_position.x as f32, body_position.y as f32, 10.),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .insert(snake_body.clone())
+    .insert(body_position)
+    .insert(Size::square(SNAKE_BODY_SIZE))
+    .insert(Enemy);
+
+    commands.spawn(SpriteBundle {
+        sprite: Sprite {
+            color: palette.enemy_head,
+            ..Default::default()
+        },
+        transform: Transform {
+            translation: Vec3::new(head_position.x as f32, head_position.y as f32, 10.),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .insert(SnakeHead {
+        moved: true,
+        growth: 0,
+        invincible: false,
+        body_parts: VecDeque::from([snake_body]),
+        previous_position: None
+    })
+    .insert(direction)
+    .insert(head_position)
+    .insert(Size::square(SNAKE_HEAD_SIZE))
+    .insert(Enemy);
+}
+
+/// Dirige aussi le serpent du joueur lorsqu'il est confié à l'ordinateur.
+/// Le chemin prévu est gardé sur la tête pour le mode spectateur.
+#[allow(clippy::type_complexity)]
+fn enemy_direction_system(
+    mut commands: Commands,
+    arena_size: Res<ArenaSize>,
+    game_type: Res<GameType>,
+    mut enemy_query: Query<(Entity, &Position, &mut Direction), (With<SnakeHead>, Or<(With<Enemy>, With<Bot>)>)>,
+    obstacle_query: Query<&Position, Or<(With<SnakeHead>, With<SnakeBody>, With<Collision>)>>,
+    food_query: Query<&Position, With<Food>>,
+    portal_query: Query<(&Position, &Portal)>,
+) {
+    let grid = Grid {
+        arena_size: &arena_size,
+        wall_type: game_type.wall_type,
+        blocked: obstacle_query.iter().copied().collect(),
+        portals: portal_query.iter().map(|(position, portal)| (*position, portal.exit)).collect()
+    };
+    let foods: HashSet<Position> = food_query.iter().copied().collect();
+
+    for (entity, head_position, mut direction) in enemy_query.iter_mut() {
+        if let Some((new_direction, planned_path)) = grid.choose_direction(head_position, *direction, &foods) {
+            *direction = new_direction;
+            commands.entity(entity).insert(PlannedPath(planned_path));
+        }
+    }
+}
+
+/// Vue simplifiée de l'arène utilisée par l'IA des adversaires.
+struct Grid<'a> {
+    arena_size: &'a ArenaSize,
+    wall_type: usize,
+    blocked: HashSet<Position>,
+    portals: HashMap<Position, Position>
+}
+
+impl Grid<'_> {
+    fn step(&self, position: &Position, direction: &Direction) -> Position {
+        let next_position = next_head_position(position, direction, self.arena_size, self.wall_type);
+        *self.portals.get(&next_position).unwrap_or(&next_position)
+    }
+
+    fn is_free(&self, position: &Position) -> bool {
+        let is_inside = (0..self.arena_size.tile_width as i32).contains(&position.x) && (0..self.arena_size.tile_height as i32).contains(&position.y);
+        is_inside && !self.blocked.contains(position)
+    }
+
+    /// Direction et cases du plus court chemin vers une nourriture, ou à défaut direction qui laisse le plus de place.
+    fn choose_direction(&self, head_position: &Position, direction: Direction, foods: &HashSet<Position>) -> Option<(Direction, Vec<Position>)> {
+        let options: Vec<(Direction, Position)> = DIRECTIONS.iter()
+            .filter(|option| **option != direction.opposite())
+            .map(|option| (*option, self.step(head_position, option)))
+            .filter(|(_, position)| self.is_free(position))
+            .collect();
+
+        // case précédente de chaque case atteinte, pour retrouver le chemin
+        let mut previous_positions = HashMap::from([(*head_position, *head_position)]);
+        let mut queue = VecDeque::new();
+        for (first_direction, position) in options.iter() {
+            if !previous_positions.contains_key(position) {
+                previous_positions.insert(*position, *head_position);
+                queue.push_back((*position, *first_direction));
+            }
+        }
+
+        while let Some((position, first_direction)) = queue.pop_front() {
+            if foods.contains(&position) {
+                let mut path = vec![position];
+                while let Some(previous_position) = previous_positions.get(path.last().unwrap()).filter(|previous_position| *previous_position != head_position) {
+                    path.push(*previous_position);
+                }
+                path.reverse();
+                return Some((first_direction, path));
+            }
+
+            for next_direction in DIRECTIONS.iter() {
+                let next_position = self.step(&position, next_direction);
+                if self.is_free(&next_position) && !previous_positions.contains_key(&next_position) {
+                    previous_positions.insert(next_position, position);
+                    queue.push_back((next_position, first_direction));
+                }
+            }
+        }
+
+        options.iter()
+            .max_by_key(|(_, position)| self.free_area(position))
+            .map(|(first_direction, _)| (*first_direction, Vec::new()))
+    }
+
+    fn free_area(&self, start: &Position) -> usize {
+        let mut visited = HashSet::from([*start]);
+        let mut queue = VecDeque::from([*start]);
+
+        while let Some(position) = queue.pop_front() {
+            for direction in DIRECTIONS.iter() {
+                let next_position = self.step(&position, direction);
+                if self.is_free(&next_position) && visited.insert(next_position) {
+                    queue.push_back(next_position);
+                }
+            }
+        }
+
+        visited.len()
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn enemy_collision_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    arena_size: Res<ArenaSize>,
+    game_type: Res<GameType>,
+    positions_available: Res<PositionsAvailable>,
+    food_catalogue: Res<FoodCatalogue>,
+    mut food_rng: ResMut<FoodRng>,
+    theme_textures: Res<ThemeTextures>,
+    palette: Res<Palette>,
+    mut enemy_spawner: ResMut<EnemySpawner>,
+    enemy_query: Query<(Entity, &Position, &SnakeHead), With<Enemy>>,
+    snake_head_query: Query<(Entity, &Position, &SnakeHead, Option<&Player>)>,
+    snake_body_query: Query<(Entity, &Position, &SnakeBody)>,
+    food_query: Query<&Food>,
+    wall_query: Query<&Position, (With<Wall>, With<Collision>)>,
+) {
+    let player_snake = snake_head_query.iter().find_map(|(_, _, snake_head, player)| player.map(|_| snake_head));
+    let mut food_counts = vec![0; food_catalogue.kinds.len()];
+    for food in food_query.iter() {
+        food_counts[food.0] += 1;
+    }
+
+    for (enemy_entity, enemy_position, enemy_head) in enemy_query.iter() {
+        let crashed = wall_query.iter().any(|wall_position| is_same_position(enemy_position, wall_position))
+            || snake_body_query.iter().any(|(_, body_position, _)| is_same_position(enemy_position, body_position))
+            || snake_head_query.iter().any(|(entity, head_position, _, _)| entity != enemy_entity && is_same_position(enemy_position, head_position));
+
+        if !crashed {
+            continue;
+        }
+
+        // le corps de l'adversaire se transforme en nourritures tirées comme les apparitions normales,
+        // de la queue vers la tête pour que le tirage ne dépende pas de l'ordre des entités
+        let body_parts: HashMap<Uuid, (Entity, Position)> = snake_body_query.iter()
+            .map(|(body_entity, body_position, snake_body)| (snake_body.id, (body_entity, *body_position)))
+            .collect();
+        for (body_entity, body_position) in enemy_head.body_parts.iter().filter_map(|body_part| body_parts.get(&body_part.id)) {
+            commands.entity(*body_entity).despawn();
+            let kind_index = random_food_kind(&mut food_rng.0, &food_catalogue, |kind_index, kind| {
+                is_kind_allowed(kind, &game_type, player_snake, &positions_available, food_counts[kind_index])
+            });
+            if let Some(kind_index) = kind_index {
+                food_spawn(&mut commands, &mut meshes, &mut materials, &arena_size, &food_catalogue, &theme_textures, &palette, kind_index, *body_position);
+                food_counts[kind_index] += 1;
+            }
+        }
+
+        commands.entity(enemy_entity).despawn();
+        enemy_spawner.missing += 1;
+    }
+}
+
+fn cleanup_enemy_system(mut commands: Commands) {
+    commands.remove_resource::<EnemySpawner>();
+}
@@ -1,51 +1,157 @@
-use std::{time::Duration, collections::HashSet};
+use std::{time::Duration, collections::HashSet, fs};
 
-use bevy::{prelude::{Plugin, App, SystemSet, Commands, Query, Transform, Res, ResMut, Vec3, With, Entity, State, IntoSystemDescriptor, CoreStage, Or, Vec2, Color, Mesh, Assets, shape, Handle, Without}, time::{FixedTimestep, Time, Timer, TimerMode}, sprite::{SpriteBundle, Sprite, MaterialMesh2dBundle, ColorMaterial, Material2d}, ecs::schedule::ShouldRun};
+use bevy::{prelude::{Plugin, App, SystemSet, Commands, Query, Transform, Res, ResMut, Vec3, With, Entity, State, IntoSystemDescriptor, CoreStage, Or, Vec2, Color, Mesh, Assets, shape, Handle, Without, Resource}, time::{FixedTimestep, Time, Timer, TimerMode}, sprite::{SpriteBundle, Sprite, MaterialMesh2dBundle, ColorMaterial, Material2d}, ecs::schedule::ShouldRun};
 use iyes_loopless::prelude::{IntoConditionalSystem, ConditionHelpers, AppLooplessFixedTimestepExt, ConditionSet};
-use rand::Rng;
+use rand::{Rng, SeedableRng, seq::SliceRandom};
+use rand_chacha::ChaCha8Rng;
+use serde::{Serialize, Deserialize};
 use crate::{main_menu::sub_menu::GameType, game::components::BonusTimer};
 
-use super::{AppState, setup_system, components::{SnakeHead, SnakeBody, FoodType}, PositionsAvailable, GOLD_FOOD_COLOR, snake, GameTextures};
+use super::{AppState, setup_system, components::{SnakeHead, SnakeBody, Player}, PositionsAvailable, snake, settings::Settings, theme::{ThemeTextures, Palette}, get_points, FOOD_CATALOGUE_PATH, BONUS_FOOD_RADIUS, FLASHING_MIN_PERIOD};
 
-use super::{components::{Position, Size, Food, FoodTimer}, ArenaSize, FoodCount, FOOD_MAX, ARENA_WIDTH, ARENA_HEIGHT, FOOD_COLOR, FOOD_SIZE, UPPER_EDGE};
+use super::{components::{Position, Size, Food, FoodTimer}, ArenaSize, ARENA_WIDTH, ARENA_HEIGHT, FOOD_SIZE, UPPER_EDGE};
+
+// region:    --- Food Catalogue
+
+/// Liste des nourritures du jeu, chargée depuis `assets/foods.ron`.
+#[derive(Resource, Deserialize)]
+pub struct FoodCatalogue {
+    pub kinds: Vec<FoodKind>
+}
+
+#[derive(Deserialize)]
+pub struct FoodKind {
+    pub name: String,
+    pub shape: FoodShape,
+    pub color: FoodColor,
+    /// Intervalle en secondes entre deux tentatives d'apparition.
+    pub spawn_interval: f32,
+    /// Une chance sur `luck` d'apparaître à chaque tentative.
+    pub luck: f64,
+    /// Durée de vie en secondes.
+    pub lifetime: f32,
+    #[serde(default)]
+    pub max_count: Option<u32>,
+    pub points: FoodPoints,
+    pub growth: u32,
+    #[serde(default)]
+    pub effect: Option<FoodEffect>,
+    /// N'apparaît que dans les arènes avec des murs.
+    #[serde(default)]
+    pub requires_walls: bool,
+    /// N'apparaît plus lorsque le serpent occupe une part de l'arène supérieure à ce ratio.
+    #[serde(default)]
+    pub max_snake_ratio: Option<f32>
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum FoodShape {
+    Square,
+    Circle
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum FoodColor {
+    Fixed(f32, f32, f32),
+    /// Couleur qui défile comme l'ancien bonus d'invincibilité.
+    Rainbow
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub enum FoodPoints {
+    /// De 4 à 1 points selon le temps mis à manger la nourriture.
+    TimeBased,
+    Fixed(u32)
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum FoodEffect {
+    Invincibility,
+    Shrink(u32),
+    SpeedUp { factor: f32, duration: f32 },
+    SlowDown { factor: f32, duration: f32 },
+    ReverseControls { duration: f32 },
+    ScoreMultiplier { factor: u32, duration: f32 },
+    Ghost { duration: f32 }
+}
+
+impl FoodEffect {
+    /// Nom court, affiché dans la barre d'informations.
+    pub fn label(&self) -> &'static str {
+        match self {
+            FoodEffect::Invincibility => "Invincible",
+            FoodEffect::Shrink(_) => "Rétréci",
+            FoodEffect::SpeedUp { .. } => "Rapide",
+            FoodEffect::SlowDown { .. } => "Lent",
+            FoodEffect::ReverseControls { .. } => "Inversé",
+            FoodEffect::ScoreMultiplier { .. } => "Points",
+            FoodEffect::Ghost { .. } => "Fantôme"
+        }
+    }
+}
+
+/// Timers d'apparition, un par nourriture du catalogue.
+#[derive(Resource)]
+pub(super) struct FoodSpawnTimers(pub(super) Vec<Timer>);
+
+/// Hasard des apparitions, tiré de `GameType::seed` : deux parties de même graine voient
+/// les mêmes tirages tant que le serpent occupe les mêmes cases. Son état est sauvegardé avec la partie.
+#[derive(Resource)]
+pub(super) struct FoodRng(pub(super) ChaCha8Rng);
+
+impl FoodRng {
+    pub(super) fn from_seed(seed: u64) -> Self {
+        FoodRng(ChaCha8Rng::seed_from_u64(seed))
+    }
+}
+
+/// Charge le catalogue depuis le dossier des assets, ou à défaut la version embarquée dans l'exécutable.
+fn load_food_catalogue() -> FoodCatalogue {
+    let embedded = include_str!("../../assets/foods.ron");
+    let content = fs::read_to_string(FOOD_CATALOGUE_PATH).unwrap_or_else(|_| embedded.to_owned());
+
+    ron::from_str(&content).unwrap_or_else(|error| {
+        println!("catalogue de nourritures invalide ({}), utilisation du catalogue par défaut", error);
+        ron::from_str(embedded).expect("catalogue de nourritures par défaut invalide")
+    })
+}
+
+impl FoodKind {
+    /// Couleur d'apparition ; les nourritures arc-en-ciel changent ensuite de couleur.
+    pub fn base_color(&self) -> Color {
+        match self.color {
+            FoodColor::Fixed(r, g, b) => Color::rgb(r, g, b),
+            FoodColor::Rainbow => BonusTimer::default().colors[0]
+        }
+    }
+
+    /// Points de base, avant les multiplicateurs de la partie, des effets et des combos.
+    pub fn points(&self, food_timer: &FoodTimer) -> u32 {
+        match self.points {
+            FoodPoints::TimeBased => get_points(food_timer.0.duration().as_secs(), food_timer.0.elapsed().as_secs()),
+            FoodPoints::Fixed(points) => points
+        }
+    }
+}
+
+// endregion: --- Food Catalogue
 
 pub struct FoodPlugin;
 
 impl Plugin for FoodPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_fixed_timestep(
-                Duration::from_millis(5000),
-                // give it a label
-                "food_spawn_time",
-            )
-            .add_fixed_timestep(
-                Duration::from_millis(30000),
-                // give it a label
-                "gold_food_spawn_time",
-            )
-            .add_fixed_timestep(
-                Duration::from_millis(40000),
-                // give it a label
-                "bonus_food_spawn_time",
+            .insert_resource(load_food_catalogue())
+            .add_system_set(
+                SystemSet::on_enter(AppState::InGame)
+                .with_system(food_spawn_timers_setup_system)
             )
-            .add_fixed_timestep_system(
-                "food_spawn_time",
-                0,
+            .add_system(
                 food_spawn_system
                 .run_in_bevy_state(AppState::InGame)
-            )
-            .add_fixed_timestep_system(
-                "gold_food_spawn_time",
-                0,
-                gold_food_spawn_system
-                .run_in_bevy_state(AppState::InGame)
-            )
-            .add_fixed_timestep_system(
-                "bonus_food_spawn_time",
-                0,
-                bonus_food_spawn_system
-                .run_in_bevy_state(AppState::InGame).run_if(is_game_with_wall).run_if(snake_is_not_too_big)
+                .run_if_resource_exists::<FoodSpawnTimers>()
+                .label("food_spawn")
+                .label("game_spawn")
             )
             .add_system_set_to_stage(
                 CoreStage::PostUpdate,
@@ -76,172 +182,187 @@ impl Plugin for FoodPlugin {
 //     ShouldRun::No
 // }
 
-fn is_lucky(luck: f64) -> bool {
-    let mut rng = rand::thread_rng();
+fn food_spawn_timers_setup_system(mut commands: Commands, game_type: Res<GameType>, food_catalogue: Res<FoodCatalogue>) {
+    let timers = food_catalogue.kinds.iter()
+        .map(|kind| Timer::from_seconds(kind.spawn_interval, TimerMode::Repeating))
+        .collect();
+
+    commands.insert_resource(FoodSpawnTimers(timers));
+    commands.insert_resource(FoodRng::from_seed(game_type.seed));
+}
+
+fn is_lucky(rng: &mut ChaCha8Rng, luck: f64) -> bool {
     rng.gen_bool(1. / luck)
 }
 
-fn is_game_with_wall(game_type: Res<GameType>) -> bool {
-    if game_type.wall_type == 0 as usize {
+fn is_game_with_wall(game_type: &GameType) -> bool {
+    if game_type.wall_type == 0 {
         return false;
     }    
     true
 }
 
-fn snake_is_not_too_big(query: Query<(&SnakeHead), With<SnakeHead>>, game_type: Res<GameType>) -> bool {
-    if let Ok(snake) = query.get_single() {
+fn snake_is_not_too_big(snake: Option<&SnakeHead>, positions_available: &PositionsAvailable, max_snake_ratio: f32) -> bool {
+    if let Some(snake) = snake {
         let snake_size = 1 + snake.body_parts.len() as u32;
-        let maximun_snake_size;
-        match game_type.wall_type {
-            4 => maximun_snake_size = (ARENA_WIDTH - 1) * (ARENA_HEIGHT - 1),
-            2 | 3 => maximun_snake_size = (ARENA_WIDTH - 1) * ARENA_HEIGHT,
-            _ => maximun_snake_size = ARENA_WIDTH * ARENA_HEIGHT,
-        }
+        let maximun_snake_size = positions_available.0.len() as u32;
         
-        // let mut x = 1;
-        // x = (maximun_snake_size * 75) / 100 - 1;
-        // let snake_size = x + snake.body_parts.len() as u32;
-        // println!("");
-        // println!("");
-        // println!("===================================================");
-        // println!("===================================================");
-        // println!("===================================================");
-        // println!("taille du serpent {}", snake_size);
-        // println!("taille maximal du serpent {}", maximun_snake_size);
-        // println!("rario taille du serpent / taille max {}", snake_size as f32 / maximun_snake_size as f32);
-        
-        if snake_size as f32 / maximun_snake_size as f32 > 0.75 {
+        if snake_size as f32 / maximun_snake_size as f32 > max_snake_ratio {
             return false;
         }
     }  
     true
 }
 
-fn food_spawn_system(
-    mut commands: Commands,
-    arena_size: Res<ArenaSize>,
-    mut food_count: ResMut<FoodCount>,
-    positions_available: Res<PositionsAvailable>,
-    query: Query<(&Position), Or<(With<SnakeHead>, With<SnakeBody>, With<Food>)>>,
-) {
-    if food_count.0 >= FOOD_MAX {
-        return;
+/// Règles d'apparition d'une nourriture du catalogue, hors tirage au sort ; `count` est le nombre déjà présent dans l'arène.
+pub(super) fn is_kind_allowed(kind: &FoodKind, game_type: &GameType, snake: Option<&SnakeHead>, positions_available: &PositionsAvailable, count: u32) -> bool {
+    if kind.requires_walls && !is_game_with_wall(game_type) {
+        return false;
     }
-
-    // let mut occupied_positions: HashSet<i32> = get_occupied_positions(query);
-    // if occupied_positions.len() as u32 == ARENA_WIDTH * ARENA_HEIGHT {
-    //     // Scénario qui n'est pas censé arrivé, la fin de partie doit être prononcée avant
-    //     return;
-    // }
-
-    // let (x, y) = get_new_food_position(occupied_positions);
-
-    let mut positions_available_depending_snake_and_food: Vec<Position> = get_available_positions_depending_snake_and_food(positions_available.0.clone(), query);
-    
-    if positions_available_depending_snake_and_food.is_empty() {
-        return;
+    if kind.max_snake_ratio.is_some_and(|ratio| !snake_is_not_too_big(snake, positions_available, ratio)) {
+        return false;
     }
+    kind.max_count.is_none_or(|max_count| count < max_count)
+}
 
-    let new_position = get_new_food_position(positions_available_depending_snake_and_food);
-
-    commands.spawn(SpriteBundle {
-        sprite: Sprite {
-            color: FOOD_COLOR,
-            ..Default::default()
-        },
-        transform: Transform {
-            translation: Vec3::new(
-                convert(new_position.x as f32, arena_size.px_width, arena_size.tile_width as f32),
-                convert(new_position.y as f32, arena_size.px_height, arena_size.tile_height as f32),
-                0.0,
-            ),
-            ..Default::default()
-        },
-        ..Default::default()
-    })
-    .insert(Food(FoodType::Simple)) 
-    .insert(new_position)
-    .insert(Size::square(FOOD_SIZE))
-    .insert(FoodTimer::default());
+/// Tire une nourriture parmi celles autorisées, chacune avec sa chance d'apparition (une sur `luck`).
+pub(super) fn random_food_kind(rng: &mut impl Rng, food_catalogue: &FoodCatalogue, is_allowed: impl Fn(usize, &FoodKind) -> bool) -> Option<usize> {
+    let allowed_kinds: Vec<usize> = (0..food_catalogue.kinds.len())
+        .filter(|&kind_index| is_allowed(kind_index, &food_catalogue.kinds[kind_index]))
+        .collect();
 
-    food_count.0 += 1; 
+    allowed_kinds.choose_weighted(rng, |&kind_index| 1. / food_catalogue.kinds[kind_index].luck).ok().copied()
 }
 
-fn gold_food_spawn_system(
+/// Les apparitions suivent le temps réel, comme les intervalles du catalogue, et non les déplacements :
+/// le journal des touches garde la durée de chaque image, une partie rejouée retrouve donc les mêmes apparitions.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn food_spawn_system(
     mut commands: Commands,
+    time: Res<Time>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
     arena_size: Res<ArenaSize>,
+    game_type: Res<GameType>,
+    food_catalogue: Res<FoodCatalogue>,
+    theme_textures: Res<ThemeTextures>,
+    palette: Res<Palette>,
     positions_available: Res<PositionsAvailable>,
+    mut food_spawn_timers: ResMut<FoodSpawnTimers>,
+    mut food_rng: ResMut<FoodRng>,
+    snake_query: Query<&SnakeHead, With<Player>>,
+    food_query: Query<&Food>,
     query: Query<(&Position), Or<(With<SnakeHead>, With<SnakeBody>, With<Food>)>>,
 ) {
-    if !is_lucky(3.) {
-        return;
-    }
+    let mut occupied_positions: HashSet<Position> = query.iter().copied().collect();
 
-    let mut positions_available_depending_snake_and_food: Vec<Position> = get_available_positions_depending_snake_and_food(positions_available.0.clone(), query);
-    
-    if positions_available_depending_snake_and_food.is_empty() {
-        return;
-    }
+    for (kind_index, timer) in food_spawn_timers.0.iter_mut().enumerate() {
+        timer.tick(time.delta());
+        if !timer.just_finished() {
+            continue;
+        }
+
+        let kind = &food_catalogue.kinds[kind_index];
+        let count = food_query.iter().filter(|food| food.0 == kind_index).count() as u32;
+        if !is_kind_allowed(kind, &game_type, snake_query.get_single().ok(), &positions_available, count) {
+            continue;
+        }
+        if !is_lucky(&mut food_rng.0, kind.luck) {
+            continue;
+        }
 
-    let new_position = get_new_food_position(positions_available_depending_snake_and_food);
+        let mut positions_available_depending_snake_and_food: Vec<Position> = positions_available.0.iter()
+            .filter(|position| !occupied_positions.contains(position))
+            .copied()
+            .collect();
+        
+        if positions_available_depending_snake_and_food.is_empty() {
+            continue;
+        }
 
-    commands.spawn(SpriteBundle {
-        sprite: Sprite {
-            color: GOLD_FOOD_COLOR,
-            ..Default::default()
+        // l'ordre d'un HashSet change d'une exécution à l'autre : le tirage se fait dans un ordre fixe
+        positions_available_depending_snake_and_food.sort_unstable_by_key(|position| (position.y, position.x));
+        let new_position = get_new_food_position(&mut food_rng.0, positions_available_depending_snake_and_food);
+        food_spawn(&mut commands, &mut meshes, &mut materials, &arena_size, &food_catalogue, &theme_textures, &palette, kind_index, new_position);
+        occupied_positions.insert(new_position);
+    }
+}
+
+/// Fait apparaître une nourriture du catalogue à la position donnée et renvoie son entité.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn food_spawn(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    arena_size: &ArenaSize,
+    food_catalogue: &FoodCatalogue,
+    theme_textures: &ThemeTextures,
+    palette: &Palette,
+    kind_index: usize,
+    position: Position
+) -> Entity {
+    let kind = &food_catalogue.kinds[kind_index];
+    let color = palette.food_color(kind);
+    let translation = Vec3::new(
+        convert(position.x as f32, arena_size.px_width, arena_size.tile_width as f32),
+        convert(position.y as f32, arena_size.px_height, arena_size.tile_height as f32),
+        0.0,
+    );
+
+    // avec l'icône du thème, la forme du catalogue est ignorée
+    let mut food = match (theme_textures.food(&kind.name), kind.shape) {
+        (Some((texture, rect)), _) => {
+            let mut food = commands.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    rect: Some(rect),
+                    custom_size: Some(Vec2::ONE),
+                    ..Default::default()
+                },
+                texture,
+                transform: Transform {
+                    translation,
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+            food.insert(Size::square(FOOD_SIZE));
+            food
         },
-        transform: Transform {
-            translation: Vec3::new(
-                convert(new_position.x as f32, arena_size.px_width, arena_size.tile_width as f32),
-                convert(new_position.y as f32, arena_size.px_height, arena_size.tile_height as f32),
-                0.0,
-            ),
-            ..Default::default()
+        (None, FoodShape::Square) => {
+            let mut food = commands.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    ..Default::default()
+                },
+                transform: Transform {
+                    translation,
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+            food.insert(Size::square(FOOD_SIZE));
+            food
         },
-        ..Default::default()
-    })
-    .insert(Food(FoodType::Gold)) 
-    .insert(new_position)
-    .insert(Size::square(FOOD_SIZE))
-    .insert(FoodTimer(Timer::from_seconds(6., TimerMode::Once)));
-}
+        // systeme de changement de couleur à utiliser https://github.com/bevyengine/bevy/discussions/2869
+        (None, FoodShape::Circle) => commands.spawn(MaterialMesh2dBundle {
+            mesh: meshes.add(shape::Circle::new(BONUS_FOOD_RADIUS).into()).into(),
+            material: materials.add(ColorMaterial::from(color)),
+            transform: Transform::from_translation(translation),
+            ..Default::default()
+        })
+    };
 
-fn bonus_food_spawn_system(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    arena_size: Res<ArenaSize>,
-    positions_available: Res<PositionsAvailable>,
-    // game_texture: Res<GameTextures>, Pas utilisé
-    query: Query<(&Position), Or<(With<SnakeHead>, With<SnakeBody>, With<Food>)>>,
-) {
-    if !is_lucky(20.) {
-        return;
-    }
+    food
+    .insert(Food(kind_index))
+    .insert(position)
+    .insert(FoodTimer(Timer::from_seconds(kind.lifetime, TimerMode::Once)));
 
-    let mut positions_available_depending_snake_and_food: Vec<Position> = get_available_positions_depending_snake_and_food(positions_available.0.clone(), query);
-    
-    if positions_available_depending_snake_and_food.is_empty() {
-        return;
+    if let FoodColor::Rainbow = kind.color {
+        food.insert(BonusTimer::default());
     }
-    let new_position = get_new_food_position(positions_available_depending_snake_and_food);
-    // systeme de changement de couleur à utiliser https://github.com/bevyengine/bevy/discussions/2869
-
-    commands.spawn(MaterialMesh2dBundle {
-        mesh: meshes.add(shape::Circle::new(10.).into()).into(),
-        material: materials.add(ColorMaterial::from(Color::RED)),
-        transform: Transform::from_translation(Vec3::new(
-            convert(new_position.x as f32, arena_size.px_width, arena_size.tile_width as f32),
-            convert(new_position.y as f32, arena_size.px_height, arena_size.tile_height as f32),
-            0.0,
-        )),
-        ..Default::default()
-    })
-    .insert(Food(FoodType::Bonus)) 
-    .insert(new_position)
-    // .insert(Size::square(FOOD_SIZE))
-    .insert(FoodTimer(Timer::from_seconds(6., TimerMode::Once)))
-    .insert(BonusTimer::default());
+
+    let food_entity = food.id();
 
     /* Spawn a sprite with img
     Je conserve pour potentiel réutilisation dans d'autres codes 
@@ -269,6 +390,8 @@ fn bonus_food_spawn_system(
     // .insert(Size::square(FOOD_SIZE))
     .insert(FoodTimer(Timer::from_seconds(6., TimerMode::Once)))
     .insert(BonusTimer::default()); */
+
+    food_entity
 }
 // fn get_occupied_positions(query: Query<(&Position)>) -> HashSet<i32> {
 //     let mut occupied_positions: HashSet<i32>= HashSet::new();
@@ -284,14 +407,6 @@ fn bonus_food_spawn_system(
 //     occupied_positions
 // }
 
-fn get_available_positions_depending_snake_and_food(mut positions_available: HashSet<Position>, query: Query<(&Position), Or<(With<SnakeHead>, With<SnakeBody>, With<Food>)>>) -> Vec<Position> {
-    for position in query.iter() {
-        positions_available.remove(position);
-    }
-
-    positions_available.into_iter().collect()
-}
-
 fn get_couple_x_y(x: i32, y: i32) -> i32 {
     let mut x_y = x * y;
     if x == 0 || y == 0 {
@@ -322,9 +437,7 @@ fn get_couple_x_y(x: i32, y: i32) -> i32 {
 //     (x, y)
 // }
 
-fn get_new_food_position(positions_available_depending_snake_and_food: Vec<Position>) -> Position {
-    let mut rng = rand::thread_rng();
-
+fn get_new_food_position(rng: &mut ChaCha8Rng, positions_available_depending_snake_and_food: Vec<Position>) -> Position {
     let index = rng.gen_range(0..positions_available_depending_snake_and_food.len());
     *positions_available_depending_snake_and_food.get(index).expect("index non trouvé dans la liste")
 }
@@ -334,51 +447,61 @@ fn convert(pos: f32, bound_window: f32, bound_game: f32) -> f32 {
 	pos / bound_game * bound_window - (bound_window / 2.) + (tile_size / 2.)
 }
 
+/// Durée de vie en secondes, comme dans le catalogue : elle ne change pas avec la vitesse de la partie.
 fn food_timer_system(
     mut commands: Commands,
     time: Res<Time>,
-    mut food_count: ResMut<FoodCount>,
-    mut query: Query<(Entity, &mut FoodTimer, &Food), With<Food>>,
+    mut query: Query<(Entity, &mut FoodTimer), With<Food>>,
 ) {
-    for (entity, mut timer, food) in query.iter_mut() {
+    for (entity, mut timer) in query.iter_mut() {
         timer.0.tick(time.delta());
         if timer.0.finished() {
             commands.entity(entity).despawn();
-
-            match food.0 {
-                FoodType::Simple => food_count.0 -= 1,
-                _ => ()
-            }
         }
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn bonus_color_timer_system(
     time: Res<Time>,
-    mut snake_head_bonus_timer_query: Query<(&mut Sprite, &mut BonusTimer), (With<BonusTimer>, With<SnakeHead>)>,
+    settings: Res<Settings>,
+    mut sprite_bonus_timer_query: Query<(&mut Sprite, &mut BonusTimer), (With<BonusTimer>, Without<Handle<ColorMaterial>>)>,
     mut color_materials_bonus_timer_query: Query<(&Handle<ColorMaterial>, &mut BonusTimer), (With<BonusTimer>, With<Food>, Without<SnakeHead>)>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    for (mut sprite, mut bonus_timer) in snake_head_bonus_timer_query.iter_mut() {
+    for (mut sprite, mut bonus_timer) in sprite_bonus_timer_query.iter_mut() {
+        limit_flashing(&settings, &mut bonus_timer);
         bonus_timer.color_timer.tick(time.delta());
         if bonus_timer.color_timer.finished() {
-            bonus_timer.index_color += 1 as usize;
+            bonus_timer.index_color += 1;
             if bonus_timer.index_color >= bonus_timer.colors.len() { bonus_timer.index_color = 0; }
-            sprite.color = bonus_timer.colors.get(bonus_timer.index_color).unwrap().clone();
+            // la transparence est conservée (mode fantôme)
+            let alpha = sprite.color.a();
+            sprite.color = *bonus_timer.colors.get(bonus_timer.index_color).unwrap();
+            sprite.color.set_a(alpha);
         }
     }
 
-    if let Ok((color_material, mut bonus_timer)) = color_materials_bonus_timer_query.get_single_mut() {
+    for (color_material, mut bonus_timer) in color_materials_bonus_timer_query.iter_mut() {
+        limit_flashing(&settings, &mut bonus_timer);
         bonus_timer.color_timer.tick(time.delta());
         if bonus_timer.color_timer.finished() {
-            bonus_timer.index_color += 1 as usize;
+            bonus_timer.index_color += 1;
             if bonus_timer.index_color >= bonus_timer.colors.len() { bonus_timer.index_color = 0; }
             let mut material = materials.get_mut(color_material).unwrap();
-            material.color = bonus_timer.colors.get(bonus_timer.index_color).unwrap().clone();
+            material.color = *bonus_timer.colors.get(bonus_timer.index_color).unwrap();
         }
     }
 }
 
+/// Ralentit le défilement des couleurs lorsque les clignotements sont réduits.
+fn limit_flashing(settings: &Settings, bonus_timer: &mut BonusTimer) {
+    let min_period = Duration::from_secs_f32(FLASHING_MIN_PERIOD);
+    if settings.reduce_flashing && bonus_timer.color_timer.duration() < min_period {
+        bonus_timer.color_timer.set_duration(min_period);
+    }
+}
+
 /* // Utiliser avec le sprite
 fn bonus_color_timer_system(time: Res<Time>, mut query: Query<(&mut Sprite, &mut BonusTimer), With<BonusTimer>>,) {
     for (mut sprite, mut bonus_timer) in query.iter_mut() {
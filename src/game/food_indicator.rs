@@ -0,0 +1,177 @@
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use iyes_loopless::prelude::ConditionSet;
+
+use crate::main_menu::sub_menu::GameType;
+
+use super::{AppState, ArenaSize, settings::{Settings, FoodIndicatorStyle}, food::{FoodCatalogue, FoodPoints}, effect::ActiveEffects, theme::Palette, components::{Food, FoodTimer}, FOOD_SIZE, FOOD_INDICATOR_FONT_SIZE, FOOD_INDICATOR_BAR_HEIGHT, FOOD_INDICATOR_HALO_RADIUS, FOOD_INDICATOR_HALO_ALPHA};
+
+// region:    --- Components
+
+/// Élément d'affichage qui suit une nourriture et disparaît avec elle.
+#[derive(Component)]
+struct FoodIndicator {
+    food: Entity,
+    kind: FoodIndicatorKind
+}
+
+#[derive(Clone, Copy)]
+enum FoodIndicatorKind {
+    Points,
+    Bar,
+    Halo,
+    Countdown
+}
+
+impl FoodIndicatorKind {
+    /// Décalage par rapport au centre de la nourriture, en cases.
+    fn offset(&self) -> Vec3 {
+        match self {
+            FoodIndicatorKind::Points => Vec3::new(0.6, 0.6, 6.),
+            FoodIndicatorKind::Bar => Vec3::new(0., -0.55, 6.),
+            FoodIndicatorKind::Halo => Vec3::new(0., 0., -1.),
+            FoodIndicatorKind::Countdown => Vec3::new(-0.6, 0.6, 6.)
+        }
+    }
+}
+
+// endregion: --- Components
+
+pub struct FoodIndicatorPlugin;
+
+impl Plugin for FoodIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set_to_stage(
+                CoreStage::PostUpdate,
+                ConditionSet::new()
+                    .run_in_bevy_state(AppState::InGame)
+                    .with_system(food_indicator_spawn_system)
+                    .with_system(food_indicator_update_system)
+                    .into()
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::InGame)
+                    .with_system(cleanup_food_indicator_system)
+            );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn food_indicator_spawn_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    arena_size: Res<ArenaSize>,
+    food_catalogue: Res<FoodCatalogue>,
+    palette: Res<Palette>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    food_query: Query<(Entity, &Food, &Transform), Added<Food>>,
+) {
+    let tile_size = arena_size.px_width / arena_size.tile_width as f32;
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        font_size: FOOD_INDICATOR_FONT_SIZE,
+        color: Color::WHITE,
+    };
+
+    for (food_entity, food, transform) in food_query.iter() {
+        let food_kind = &food_catalogue.kinds[food.0];
+        let mut kinds = Vec::new();
+        // les nourritures qui ne rapportent rien n'affichent pas de points
+        if settings.food_indicator != FoodIndicatorStyle::Hidden && !matches!(food_kind.points, FoodPoints::Fixed(0)) {
+            kinds.push(FoodIndicatorKind::Points);
+        }
+        match settings.food_indicator {
+            FoodIndicatorStyle::Hidden => (),
+            FoodIndicatorStyle::Bar => kinds.push(FoodIndicatorKind::Bar),
+            FoodIndicatorStyle::Halo => kinds.push(FoodIndicatorKind::Halo),
+            FoodIndicatorStyle::Countdown => kinds.push(FoodIndicatorKind::Countdown)
+        }
+
+        for kind in kinds {
+            let translation = transform.translation.truncate().extend(0.) + kind.offset() * Vec3::new(tile_size, tile_size, 1.);
+            let mut indicator = match kind {
+                FoodIndicatorKind::Points | FoodIndicatorKind::Countdown => commands.spawn(Text2dBundle {
+                    text: Text::from_section("", text_style.clone()).with_alignment(TextAlignment::CENTER),
+                    transform: Transform::from_translation(translation),
+                    ..Default::default()
+                }),
+                FoodIndicatorKind::Bar => commands.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::WHITE,
+                        custom_size: Some(Vec2::new(tile_size * FOOD_SIZE, FOOD_INDICATOR_BAR_HEIGHT)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(translation),
+                    ..Default::default()
+                }),
+                FoodIndicatorKind::Halo => {
+                    let mut color = palette.food_color(food_kind);
+                    color.set_a(FOOD_INDICATOR_HALO_ALPHA);
+                    commands.spawn(MaterialMesh2dBundle {
+                        mesh: meshes.add(shape::Circle::new(tile_size * FOOD_INDICATOR_HALO_RADIUS).into()).into(),
+                        material: materials.add(ColorMaterial::from(color)),
+                        transform: Transform::from_translation(translation),
+                        ..Default::default()
+                    })
+                }
+            };
+            indicator.insert(FoodIndicator { food: food_entity, kind });
+        }
+    }
+}
+
+/// Met à jour les indicateurs depuis le `FoodTimer` de leur nourriture, à chaque image.
+fn food_indicator_update_system(
+    mut commands: Commands,
+    game_type: Res<GameType>,
+    food_catalogue: Res<FoodCatalogue>,
+    active_effects: Option<Res<ActiveEffects>>,
+    food_query: Query<(&Food, &FoodTimer, &Visibility), Without<FoodIndicator>>,
+    mut indicator_query: Query<(Entity, &FoodIndicator, &mut Transform, &mut Visibility, Option<&mut Text>)>,
+) {
+    let score_multiplier = game_type.multiplier * active_effects.map_or(1, |active_effects| active_effects.score_multiplier());
+
+    for (entity, indicator, mut transform, mut visibility, text) in indicator_query.iter_mut() {
+        let (food, food_timer, food_visibility) = match food_query.get(indicator.food) {
+            Ok(food) => food,
+            Err(_) => {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        };
+        let remaining = 1. - food_timer.0.percent();
+        // l'indicateur clignote avec sa nourriture
+        visibility.is_visible = food_visibility.is_visible;
+
+        let label = match indicator.kind {
+            FoodIndicatorKind::Points => format!("+{}", food_catalogue.kinds[food.0].points(food_timer) * score_multiplier),
+            FoodIndicatorKind::Countdown => format!("{}", food_timer.0.remaining_secs().ceil()),
+            _ => String::new()
+        };
+        if let Some(mut text) = text {
+            // le texte n'est recalculé que lorsque sa valeur change
+            if text.sections[0].value != label {
+                text.sections[0].value = label;
+            }
+        }
+
+        match indicator.kind {
+            FoodIndicatorKind::Points | FoodIndicatorKind::Countdown => (),
+            FoodIndicatorKind::Bar => transform.scale.x = remaining,
+            FoodIndicatorKind::Halo => {
+                // le halo se resserre jusqu'au bord de la nourriture
+                let min_scale = FOOD_SIZE / 2. / FOOD_INDICATOR_HALO_RADIUS;
+                let scale = min_scale + (1. - min_scale) * remaining;
+                transform.scale = Vec3::new(scale, scale, 1.);
+            }
+        }
+    }
+}
+
+fn cleanup_food_indicator_system(mut commands: Commands, indicator_query: Query<Entity, With<FoodIndicator>>) {
+    for entity in indicator_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -1,7 +1,8 @@
-use super::{AppState, Score};
+use super::{AppState, Score, daily::{DailyAttempt, share_path}, leaderboard::LeaderboardStatus, replay::RunRecording, stats::{RunStats, GameOutcome}};
 
-use bevy::{prelude::*, ui::Interaction, app::AppExit};
+use bevy::{prelude::*, ui::Interaction};
 
+const SELECTED_BUTTON_COLOR: Color = Color::rgb(0.35, 0.75, 0.35);
 
 // region:    --- Resource
 
@@ -11,38 +12,125 @@ struct MenuData {
     ui_root: Entity,
 }
 
+/// Bouton choisi au clavier ou survolé à la souris.
+#[derive(Resource, Default)]
+struct SelectedButton(usize);
+
 // endregion: --- Resource
+
+// region:    --- Component
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum GameOverButton {
+    Retry,
+    Replay,
+    Menu
+}
+
+impl GameOverButton {
+    const ALL: [GameOverButton; 3] = [GameOverButton::Retry, GameOverButton::Replay, GameOverButton::Menu];
+
+    fn label(&self) -> &'static str {
+        match self {
+            GameOverButton::Retry => "Rejouer",
+            GameOverButton::Replay => "Voir le replay",
+            GameOverButton::Menu => "Menu"
+        }
+    }
+}
+
+#[derive(Component)]
+struct SummaryText;
+
+// endregion: --- Component
+
 pub struct GameOverPlugin;
 
 impl Plugin for GameOverPlugin {
     fn build(&self, app: &mut App) {
         app
+        .init_resource::<SelectedButton>()
         .add_system_set(SystemSet::on_update(AppState::GameOver(true)).with_system(button_press_system))
             .add_system_set(SystemSet::on_update(AppState::GameOver(true)).with_system(keyboard_event_system))
-            .add_system_set(SystemSet::on_enter(AppState::GameOver(true)).with_system(setup_system).with_system(score_system))
+            .add_system_set(SystemSet::on_update(AppState::GameOver(true)).with_system(button_highlight_system).with_system(summary_system))
+            .add_system_set(SystemSet::on_enter(AppState::GameOver(true)).with_system(setup_system))
             .add_system_set(SystemSet::on_exit(AppState::GameOver(true)).with_system(cleanup_system))
             .add_system_set(SystemSet::on_update(AppState::GameOver(false)).with_system(button_press_system))
             .add_system_set(SystemSet::on_update(AppState::GameOver(false)).with_system(keyboard_event_system))
-            .add_system_set(SystemSet::on_enter(AppState::GameOver(false)).with_system(setup_system).with_system(score_system))
+            .add_system_set(SystemSet::on_update(AppState::GameOver(false)).with_system(button_highlight_system).with_system(summary_system))
+            .add_system_set(SystemSet::on_enter(AppState::GameOver(false)).with_system(setup_system))
             .add_system_set(SystemSet::on_exit(AppState::GameOver(false)).with_system(cleanup_system));
     }
 }
 
+fn select_button(button: GameOverButton, run_recording: &RunRecording, state: &mut State<AppState>) {
+    match button {
+        // GameType est conservé : la nouvelle partie reprend le même mode
+        GameOverButton::Retry => state.set(AppState::InGame).expect("Couldn't switch state to InGame"),
+        GameOverButton::Replay => {
+            if !run_recording.frames.is_empty() {
+                state.set(AppState::Replay).expect("Couldn't switch state to Replay");
+            }
+        },
+        GameOverButton::Menu => state.set(AppState::MainMenu).expect("Couldn't switch state to MainMenu")
+    }
+}
+
 fn button_press_system(
-    buttons: Query<(&Interaction), (Changed<Interaction>, With<Button>)>,
+    buttons: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
+    run_recording: Res<RunRecording>,
+    mut selected_button: ResMut<SelectedButton>,
     mut state: ResMut<State<AppState>>,
-    mut exit: EventWriter<AppExit>
 ) {
-    for (interaction) in buttons.iter() {
-        if *interaction == Interaction::Clicked {
-            state.set(AppState::MainMenu).expect("Couldn't switch state to MainMenu");
+    for (interaction, button) in buttons.iter() {
+        let index = GameOverButton::ALL.iter().position(|other_button| other_button == button).unwrap();
+        match *interaction {
+            Interaction::Clicked => {
+                select_button(*button, &run_recording, &mut state);
+                return;
+            },
+            Interaction::Hovered => selected_button.0 = index,
+            Interaction::None => ()
         }
     }
 }
 
-fn keyboard_event_system(mut keys: ResMut<Input<KeyCode>>, mut exit: EventWriter<AppExit>) {
-    if keys.just_pressed(KeyCode::Escape) {
-        exit.send(AppExit);
+/// Haut/Bas pour choisir un bouton, Entrée pour valider, Échap pour revenir au menu.
+fn keyboard_event_system(
+    mut keys: ResMut<Input<KeyCode>>,
+    run_recording: Res<RunRecording>,
+    mut selected_button: ResMut<SelectedButton>,
+    mut state: ResMut<State<AppState>>,
+) {
+    let button_count = GameOverButton::ALL.len();
+
+    if keys.just_pressed(KeyCode::Up) {
+        selected_button.0 = (selected_button.0 + button_count - 1) % button_count;
+    } else if keys.just_pressed(KeyCode::Down) {
+        selected_button.0 = (selected_button.0 + 1) % button_count;
+    } else if keys.just_pressed(KeyCode::Return) {
+        keys.reset(KeyCode::Return);
+        select_button(GameOverButton::ALL[selected_button.0], &run_recording, &mut state);
+    } else if keys.just_pressed(KeyCode::Escape) {
+        keys.reset(KeyCode::Escape);
+        select_button(GameOverButton::Menu, &run_recording, &mut state);
+    }
+}
+
+fn button_highlight_system(
+    selected_button: Res<SelectedButton>,
+    mut buttons: Query<(&GameOverButton, &mut BackgroundColor)>,
+) {
+    if !selected_button.is_changed() {
+        return;
+    }
+
+    for (button, mut background_color) in buttons.iter_mut() {
+        *background_color = if GameOverButton::ALL[selected_button.0] == *button {
+            SELECTED_BUTTON_COLOR.into()
+        } else {
+            Color::WHITE.into()
+        };
     }
 }
 
@@ -86,7 +174,8 @@ fn menu_background() -> NodeBundle {
 fn button() -> ButtonBundle {
     ButtonBundle {
         style: Style {
-            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            size: Size::new(Val::Percent(100.0), Val::Px(50.0)),
+            margin: UiRect::all(Val::Px(5.0)),
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
             ..Default::default()
@@ -97,7 +186,7 @@ fn button() -> ButtonBundle {
 }
 
 fn button_text(asset_server: &Res<AssetServer>, label: &str) -> TextBundle {
-    return TextBundle {
+    TextBundle {
         style: Style {
             margin: UiRect::all(Val::Px(10.0)),
             ..Default::default()
@@ -111,12 +200,105 @@ fn button_text(asset_server: &Res<AssetServer>, label: &str) -> TextBundle {
             },
         ),
         ..Default::default()
+    }
+}
+
+fn text(asset_server: &Res<AssetServer>, label: &str, font_size: f32) -> TextBundle {
+    TextBundle {
+        style: Style {
+            margin: UiRect::all(Val::Px(5.0)),
+            ..Default::default()
+        },
+        text: Text::from_section(
+            label,
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size,
+                color: Color::WHITE,
+            },
+        ),
+        ..Default::default()
+    }
+}
+
+fn duration_label(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    format!("{}m{:02}s", seconds / 60, seconds % 60)
+}
+
+fn rank_label(rank: Option<usize>) -> String {
+    match rank {
+        Some(1) => "1er".to_owned(),
+        Some(rank) => format!("{}e", rank),
+        None => "hors classement".to_owned()
+    }
+}
+
+/// Résumé de la partie ; le classement n'est connu qu'une fois la partie enregistrée dans le profil,
+/// celui du réseau local une fois la réponse du serveur reçue.
+fn summary(run_stats: &RunStats, score: u32, daily_attempt: Option<&DailyAttempt>, leaderboard_status: &LeaderboardStatus) -> String {
+    let death = match (run_stats.outcome, run_stats.death_position) {
+        (Some(GameOutcome::Died(death_cause)), Some(position)) => format!("Mort : {} en ({}, {})", death_cause.label(), position.x, position.y),
+        (Some(GameOutcome::Died(death_cause)), None) => format!("Mort : {}", death_cause.label()),
+        (Some(GameOutcome::ArenaFilled), _) => "Arène remplie".to_owned(),
+        (None, _) => "Partie interrompue".to_owned()
+    };
+
+    let points_by_food = if run_stats.points_by_food.is_empty() {
+        "aucun".to_owned()
+    } else {
+        run_stats.points_by_food.iter()
+            .map(|(name, points)| format!("{} {}", name, points))
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+
+    let rank = match run_stats.recorded {
+        false => "...".to_owned(),
+        true => rank_label(run_stats.high_score_rank)
+    };
+
+    let leaderboard = match leaderboard_status {
+        LeaderboardStatus::Unsent | LeaderboardStatus::Skipped => String::new(),
+        LeaderboardStatus::Pending => "\nClassement réseau : ...".to_owned(),
+        LeaderboardStatus::Ranked(rank) => format!("\nClassement réseau : {}", rank_label(*rank)),
+        LeaderboardStatus::Failed(error) => format!("\nClassement réseau : échec, {}", error)
     };
+
+    let daily = match daily_attempt {
+        Some(daily_attempt) if daily_attempt.share.is_some() => format!(
+            "\nDéfi du jour : tentative {}{}, résultat dans {}",
+            daily_attempt.attempt, if daily_attempt.counted { "" } else { " (ne compte pas)" }, share_path()
+        ),
+        _ => String::new()
+    };
+
+    format!(
+        "Score : {}\nPoints : {}\nDurée : {} - Longueur max : {}\n{}\nBonus ramassés : {}\nClassement : {}{}{}",
+        score, points_by_food, duration_label(run_stats.play_time), run_stats.longest_snake, death, run_stats.bonus_pickups, rank, leaderboard, daily
+    )
 }
 
-fn setup_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+#[allow(clippy::too_many_arguments)]
+fn setup_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    score: Res<Score>,
+    run_stats: Res<RunStats>,
+    daily_attempt: Option<Res<DailyAttempt>>,
+    leaderboard_status: Res<LeaderboardStatus>,
+    app_state: Res<State<AppState>>,
+    mut selected_button: ResMut<SelectedButton>,
+) {
+    selected_button.0 = 0;
+
     let camera_entity = commands.spawn(Camera2dBundle::default()).id();
 
+    let title = match app_state.current() {
+        AppState::GameOver(true) => "Gagné !",
+        _ => "Perdu !"
+    };
+
     let ui_root = 
     commands
         .spawn(root())
@@ -129,10 +311,17 @@ fn setup_system(mut commands: Commands, asset_server: Res<AssetServer>) {
                         parent
                             .spawn(menu_background())
                             .with_children(|parent| {
-                                parent.spawn(button())
-                                    .with_children(|parent| {
-                                        parent.spawn(button_text(&asset_server, "Menu"));
-                                    });                                
+                                parent.spawn(text(&asset_server, title, 40.));
+                                parent.spawn(text(&asset_server, &summary(&run_stats, score.0, daily_attempt.as_deref(), &leaderboard_status), 18.))
+                                    .insert(SummaryText);
+
+                                for game_over_button in GameOverButton::ALL {
+                                    parent.spawn(button())
+                                        .with_children(|parent| {
+                                            parent.spawn(button_text(&asset_server, game_over_button.label()));
+                                        })
+                                        .insert(game_over_button);
+                                }
                             });
                     });
             })
@@ -144,52 +333,31 @@ fn setup_system(mut commands: Commands, asset_server: Res<AssetServer>) {
     });
 }
 
-fn score_system(
-	mut commands: Commands,
-	asset_server: Res<AssetServer>,
-	score: Res<Score>,
-    app_state: Res<State<AppState>>,
+fn summary_system(
+    score: Res<Score>,
+    run_stats: Res<RunStats>,
+    daily_attempt: Option<Res<DailyAttempt>>,
+    leaderboard_status: Res<LeaderboardStatus>,
+    mut summary_text_query: Query<&mut Text, With<SummaryText>>,
 ) {
-	let font = asset_server.load("fonts/FiraSans-Bold.ttf");
-	let text_style = TextStyle {
-        font,
-        font_size: 40.,
-        color: Color::WHITE,
-    };
+    if !run_stats.is_changed()
+        && !leaderboard_status.is_changed()
+        && !daily_attempt.as_ref().is_some_and(|daily_attempt| daily_attempt.is_changed())
+    {
+        return;
+    }
 
-    let text;
-
-    match app_state.current() {
-        AppState::GameOver(true) => text = "Gagné !\n".to_owned() + &score.0.to_string(),
-        _ => text = "Perdu !\n".to_owned() + &score.0.to_string()
-    }
-	
-	commands.spawn(Text2dBundle {
-		text: Text::from_section(
-			text,
-			text_style.clone()
-		)
-        .with_alignment(TextAlignment::TOP_CENTER),
-        transform: Transform {
-            translation: Vec3::new(0., 100., 1.),
-            ..Default::default()
-        },
-		..default()
-	});
+    for mut text in summary_text_query.iter_mut() {
+        text.sections[0].value = summary(&run_stats, score.0, daily_attempt.as_deref(), &leaderboard_status);
+    }
 }
 
-fn cleanup_system(mut commands: Commands, menu_data: Res<MenuData>, mut query: Query<Entity, (With<Text>, Without<Style>)>) {
-    // "Without<Style>" car cela comprenait le texte présent dans le bouton, ce qui posait pb pour mon précédent code... 
-    if let Ok(entity) = query.get_single() {
-		commands.entity(entity).despawn_recursive();
-	}
-
+fn cleanup_system(mut commands: Commands, menu_data: Res<MenuData>) {
     commands.entity(menu_data.ui_root).despawn_recursive();
     // println!("ui_root");
     commands.entity(menu_data.camera_entity).despawn_recursive();
     // println!("camera");
-    commands.remove_resource::<Score>();
-    // println!("score");
+    // Score est conservé pour revenir sur cet écran après le replay
     commands.remove_resource::<MenuData>();
     // println!("menudata");
-}
\ No newline at end of file
+}
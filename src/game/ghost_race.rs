@@ -0,0 +1,255 @@
+use std::fs;
+
+use bevy::prelude::*;
+use iyes_loopless::prelude::{IntoConditionalSystem, ConditionHelpers, AppLooplessFixedTimestepExt};
+use serde::{Serialize, Deserialize};
+
+use crate::main_menu::sub_menu::{GameType, Controller};
+
+use super::{AppState, Score, GameTick, debug::DebugConsole, simulation::Simulation, settings::Settings, theme::Palette, replay::{RunRecording, ReplayCell}, components::{Position, Size}, SAVE_DIRECTORY, GHOST_RUN_MAX_FILES, SNAKE_HEAD_SIZE, SNAKE_BODY_SIZE, GHOST_RACE_ALPHA};
+
+// region:    --- Ghost Run
+
+#[derive(Serialize, Deserialize)]
+struct GhostFrame {
+    head: Position,
+    body: Vec<Position>,
+    score: u32
+}
+
+/// Meilleure partie en solo d'un mode, d'une graine et d'une taille d'arène : le serpent après chaque déplacement.
+#[derive(Serialize, Deserialize)]
+struct GhostRun {
+    score: u32,
+    frames: Vec<GhostFrame>
+}
+
+impl GhostRun {
+    fn path(game_type: &GameType) -> String {
+        format!("{}/ghost_{}_{}_{}x{}.ron", SAVE_DIRECTORY, game_type.wall_type, game_type.seed, game_type.arena_width, game_type.arena_height)
+    }
+
+    fn load(game_type: &GameType) -> Option<Self> {
+        let content = fs::read_to_string(Self::path(game_type)).ok()?;
+        ron::from_str::<GhostRun>(&content).ok().filter(|ghost_run| !ghost_run.frames.is_empty())
+    }
+
+    /// Format compact : le fantôme garde tout le serpent à chaque déplacement.
+    fn save(&self, game_type: &GameType) {
+        let saved = fs::create_dir_all(SAVE_DIRECTORY)
+            .ok()
+            .and_then(|_| ron::to_string(self).ok())
+            .and_then(|content| fs::write(Self::path(game_type), content).ok());
+
+        if saved.is_none() {
+            println!("impossible de sauvegarder le fantôme dans {}", Self::path(game_type));
+        }
+        Self::prune();
+    }
+
+    /// Ne garde que les `GHOST_RUN_MAX_FILES` fantômes enregistrés le plus récemment.
+    fn prune() {
+        let Ok(entries) = fs::read_dir(SAVE_DIRECTORY) else {
+            return;
+        };
+        let mut ghost_files: Vec<_> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_str().is_some_and(|name| name.starts_with("ghost_") && name.ends_with(".ron")))
+            .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+            .collect();
+        if ghost_files.len() <= GHOST_RUN_MAX_FILES {
+            return;
+        }
+
+        ghost_files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+        for (_, path) in ghost_files.drain(GHOST_RUN_MAX_FILES..) {
+            if fs::remove_file(&path).is_err() {
+                println!("impossible de supprimer l'ancien fantôme {}", path.display());
+            }
+        }
+    }
+
+    fn from_recording(run_recording: &RunRecording, score: u32) -> Self {
+        let frames = run_recording.frames.iter()
+            .filter_map(|frame| {
+                let head = frame.cells.iter().find(|(_, cell)| matches!(cell, ReplayCell::PlayerHead))?.0;
+                let body = frame.cells.iter()
+                    .filter(|(_, cell)| matches!(cell, ReplayCell::PlayerBody))
+                    .map(|(position, _)| *position)
+                    .collect();
+                Some(GhostFrame { head, body, score: frame.score })
+            })
+            .collect();
+
+        GhostRun { score, frames }
+    }
+
+    /// Image du fantôme après `tick` déplacements ; le fantôme disparaît une fois sa partie finie.
+    fn frame(&self, tick: u64) -> Option<&GhostFrame> {
+        self.frames.get((tick as usize).checked_sub(1)?)
+    }
+
+    /// Score du fantôme après `tick` déplacements, son score final une fois sa partie finie.
+    /// Une image est enregistrée avant que le serpent ne mange : la suivante a le score du déplacement.
+    fn score_at(&self, tick: u64) -> u32 {
+        self.frames.get(tick as usize).map_or(self.score, |frame| frame.score)
+    }
+}
+
+/// Fantôme de la partie en cours, avec ses morceaux de corps affichés.
+#[derive(Resource)]
+pub(super) struct GhostRace {
+    run: GhostRun,
+    head_entity: Entity,
+    body_entities: Vec<Entity>
+}
+
+impl GhostRace {
+    /// Écart de score du joueur sur le fantôme, au même nombre de déplacements.
+    pub(super) fn score_delta(&self, score: u32, tick: u64) -> i64 {
+        score as i64 - self.run.score_at(tick) as i64
+    }
+}
+
+// endregion: --- Ghost Run
+
+// region:    --- Component
+
+/// Tête ou corps du fantôme, traversé par tout le reste du jeu.
+#[derive(Component)]
+pub struct GhostRacer;
+
+// endregion: --- Component
+
+/// Course contre la meilleure partie en solo de même mode et de même graine.
+pub struct GhostRacePlugin;
+
+impl Plugin for GhostRacePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_system_set(
+            SystemSet::on_enter(AppState::InGame)
+            .with_system(ghost_race_setup_system)
+        )
+        .add_fixed_timestep_system(
+            "snake_move_time",
+            2,
+            ghost_race_system
+                .run_in_bevy_state(AppState::InGame)
+                .run_if_resource_exists::<GhostRace>()
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::InGame)
+            .with_system(ghost_run_save_system.run_unless_resource_exists::<Simulation>())
+            .with_system(cleanup_ghost_race_system)
+        );
+    }
+}
+
+/// Seules les parties en solo jouées au clavier ont un fantôme.
+fn is_single_player(game_type: &GameType) -> bool {
+    game_type.enemies == 0 && game_type.controller == Controller::Human
+}
+
+fn ghost_race_setup_system(mut commands: Commands, settings: Res<Settings>, game_type: Res<GameType>, palette: Res<Palette>) {
+    if !settings.ghost_race || !is_single_player(&game_type) {
+        return;
+    }
+
+    if let Some(run) = GhostRun::load(&game_type) {
+        let mut color = palette.snake_head;
+        color.set_a(GHOST_RACE_ALPHA);
+        // la tête reste cachée jusqu'au premier déplacement
+        let head_entity = ghost_spawn(&mut commands, color, run.frames[0].head, SNAKE_HEAD_SIZE)
+            .insert(Visibility::INVISIBLE)
+            .id();
+
+        commands.insert_resource(GhostRace { run, head_entity, body_entities: Vec::new() });
+    }
+}
+
+fn ghost_spawn<'w, 's, 'a>(commands: &'a mut Commands<'w, 's>, color: Color, position: Position, size: f32) -> bevy::ecs::system::EntityCommands<'w, 's, 'a> {
+    let mut ghost = commands.spawn(SpriteBundle {
+        sprite: Sprite {
+            color,
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    ghost
+        .insert(GhostRacer)
+        .insert(position)
+        .insert(Size::square(size));
+    ghost
+}
+
+/// Le fantôme suit sa partie au même rythme que le joueur, déplacement par déplacement.
+fn ghost_race_system(
+    mut commands: Commands,
+    game_tick: Res<GameTick>,
+    palette: Res<Palette>,
+    mut ghost_race: ResMut<GhostRace>,
+    mut ghost_query: Query<(&mut Position, &mut Visibility), With<GhostRacer>>,
+) {
+    let ghost_race = ghost_race.as_mut();
+    let frame = ghost_race.run.frame(game_tick.0);
+
+    // le corps grandit ou raccourcit avec celui de la partie enregistrée
+    let body: &[Position] = frame.map_or(&[], |frame| &frame.body);
+    let mut body_color = palette.snake_body;
+    body_color.set_a(GHOST_RACE_ALPHA);
+    while ghost_race.body_entities.len() < body.len() {
+        let position = body[ghost_race.body_entities.len()];
+        let entity = ghost_spawn(&mut commands, body_color, position, SNAKE_BODY_SIZE).id();
+        ghost_race.body_entities.push(entity);
+    }
+    for entity in ghost_race.body_entities.drain(body.len()..) {
+        commands.entity(entity).despawn();
+    }
+
+    if let Ok((mut position, mut visibility)) = ghost_query.get_mut(ghost_race.head_entity) {
+        visibility.is_visible = frame.is_some();
+        if let Some(frame) = frame {
+            *position = frame.head;
+        }
+    }
+
+    // les morceaux tout juste ajoutés sont déjà à leur place
+    for (entity, body_position) in ghost_race.body_entities.iter().zip(body) {
+        if let Ok((mut position, _)) = ghost_query.get_mut(*entity) {
+            *position = *body_position;
+        }
+    }
+}
+
+/// Une partie reprise depuis une sauvegarde n'est pas enregistrée en entier : elle ne devient pas un fantôme.
+/// Une partie modifiée avec la console de développement ne remplace pas le fantôme.
+fn ghost_run_save_system(
+    game_type: Res<GameType>,
+    score: Option<Res<Score>>,
+    game_tick: Option<Res<GameTick>>,
+    run_recording: Res<RunRecording>,
+    debug_console: Option<Res<DebugConsole>>,
+) {
+    let (Some(score), Some(game_tick)) = (score, game_tick) else {
+        return;
+    };
+    if debug_console.is_some_and(|debug_console| debug_console.used) {
+        return;
+    }
+    if !is_single_player(&game_type) || run_recording.frames.is_empty() || run_recording.frames.len() as u64 != game_tick.0 {
+        return;
+    }
+
+    let best_score = GhostRun::load(&game_type).map_or(0, |ghost_run| ghost_run.score);
+    if score.0 > best_score {
+        GhostRun::from_recording(&run_recording, score.0).save(&game_type);
+    }
+}
+
+fn cleanup_ghost_race_system(mut commands: Commands, ghost_query: Query<Entity, With<GhostRacer>>) {
+    for entity in ghost_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.remove_resource::<GhostRace>();
+}
@@ -0,0 +1,56 @@
+use bevy::{prelude::*, app::AppExit};
+
+use super::{AppState, Score, GameTick, stats::RunStats};
+
+/// Sans fenêtre, la partie s'arrête dès qu'elle est finie et son résultat est écrit sur la sortie standard.
+/// Le bot peut survivre indéfiniment : `max_ticks` arrête la partie après ce nombre de déplacements.
+pub struct HeadlessPlugin {
+    pub max_ticks: Option<u64>
+}
+
+#[derive(Resource)]
+struct MaxTicks(u64);
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(max_ticks) = self.max_ticks {
+            app
+                .insert_resource(MaxTicks(max_ticks))
+                .add_system(headless_max_ticks_system);
+        }
+        app.add_system(headless_exit_system);
+    }
+}
+
+fn headless_max_ticks_system(
+    app_state: Res<State<AppState>>,
+    max_ticks: Res<MaxTicks>,
+    game_tick: Option<Res<GameTick>>,
+    score: Option<Res<Score>>,
+    run_stats: Res<RunStats>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if *app_state.current() != AppState::InGame || game_tick.is_none_or(|game_tick| game_tick.0 < max_ticks.0) {
+        return;
+    }
+
+    println!("partie interrompue après {} déplacements : score {}, {:.1} s", max_ticks.0, score.map_or(0, |score| score.0), run_stats.play_time);
+    exit.send(AppExit);
+}
+
+/// L'issue de la partie est connue au plus tard une image après la sortie de `InGame`.
+fn headless_exit_system(
+    app_state: Res<State<AppState>>,
+    score: Option<Res<Score>>,
+    run_stats: Res<RunStats>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if !matches!(app_state.current(), AppState::DeathReplay | AppState::GameOver(_)) {
+        return;
+    }
+
+    if let Some(outcome) = run_stats.outcome {
+        println!("partie terminée : {}, score {}, {:.1} s", outcome.label(), score.map_or(0, |score| score.0), run_stats.play_time);
+        exit.send(AppExit);
+    }
+}
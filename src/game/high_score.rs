@@ -0,0 +1,57 @@
+use std::{fs, collections::BTreeMap};
+
+use bevy::prelude::Resource;
+use serde::{Serialize, Deserialize};
+
+use super::{SAVE_DIRECTORY, HIGH_SCORE_COUNT};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u32
+}
+
+/// Meilleurs scores locaux par mode de jeu (type de mur), tous profils confondus.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub modes: BTreeMap<usize, Vec<HighScore>>
+}
+
+impl HighScores {
+    fn path() -> String {
+        format!("{}/high_scores.ron", SAVE_DIRECTORY)
+    }
+
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| ron::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let saved = fs::create_dir_all(SAVE_DIRECTORY)
+            .ok()
+            .and_then(|_| ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).ok())
+            .and_then(|content| fs::write(Self::path(), content).ok());
+
+        if saved.is_none() {
+            println!("impossible de sauvegarder les meilleurs scores dans {}", Self::path());
+        }
+    }
+
+    /// Ajoute un score au tableau du mode et renvoie sa place (à partir de 1),
+    /// ou `None` s'il ne fait pas partie des `HIGH_SCORE_COUNT` meilleurs.
+    pub fn insert(&mut self, wall_type: usize, name: &str, score: u32) -> Option<usize> {
+        let high_scores = self.modes.entry(wall_type).or_default();
+        // à score égal, le plus ancien reste devant
+        let index = high_scores.iter().position(|high_score| high_score.score < score).unwrap_or(high_scores.len());
+        if index >= HIGH_SCORE_COUNT {
+            return None;
+        }
+
+        high_scores.insert(index, HighScore { name: name.to_owned(), score });
+        high_scores.truncate(HIGH_SCORE_COUNT);
+        Some(index + 1)
+    }
+}
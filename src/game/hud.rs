@@ -0,0 +1,223 @@
+use bevy::prelude::*;
+use iyes_loopless::prelude::IntoConditionalSystem;
+
+use crate::main_menu::sub_menu::GameType;
+
+use super::{AppState, Score, GameTick, combo::Combo, ghost_race::GhostRace, effect::ActiveEffects, high_score::HighScores, stats::RunStats, theme::Palette, components::{SnakeHead, BonusTimer, Player}, BONUS_LIFE_CYCLES, HUD_LABEL_FONT_SIZE, HUD_VALUE_FONT_SIZE, WINDOW_HEIGHT, UPPER_EDGE};
+
+// region:    --- Component
+
+/// Racine de la barre d'informations, dans la bande au-dessus de l'arène.
+#[derive(Component)]
+struct HudRoot;
+
+/// Chaque texte de la barre affiche un intitulé et une valeur.
+#[derive(Component, Clone, Copy)]
+enum HudItem {
+    Score,
+    HighScore,
+    Length,
+    Time,
+    Bonus,
+    Speed,
+    Multiplier,
+    /// Écart de score avec le fantôme.
+    Ghost
+}
+
+impl HudItem {
+    const ALL: [HudItem; 8] = [
+        HudItem::Score,
+        HudItem::HighScore,
+        HudItem::Length,
+        HudItem::Time,
+        HudItem::Bonus,
+        HudItem::Speed,
+        HudItem::Multiplier,
+        HudItem::Ghost
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            HudItem::Score => "Score",
+            HudItem::HighScore => "Record",
+            HudItem::Length => "Longueur",
+            HudItem::Time => "Temps",
+            HudItem::Bonus => "Bonus",
+            HudItem::Speed => "Vitesse",
+            HudItem::Multiplier => "Multiplicateur",
+            HudItem::Ghost => "Écart"
+        }
+    }
+}
+
+// endregion: --- Component
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_enter(AppState::InGame)
+                    .with_system(hud_setup_system)
+            )
+            .add_system(hud_system.run_in_bevy_state(AppState::InGame))
+            .add_system_set(
+                SystemSet::on_exit(AppState::InGame)
+                    .with_system(cleanup_hud_system)
+            );
+    }
+}
+
+fn hud_setup_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands.spawn(NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(0.),
+                top: Val::Px(0.),
+                ..Default::default()
+            },
+            size: Size::new(Val::Percent(100.), Val::Px(WINDOW_HEIGHT * UPPER_EDGE)),
+            justify_content: JustifyContent::SpaceAround,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        background_color: Color::NONE.into(),
+        ..Default::default()
+    })
+    .with_children(|parent| {
+        for hud_item in HudItem::ALL {
+            parent.spawn(TextBundle {
+                text: Text::from_sections([
+                    TextSection::new(
+                        format!("{}\n", hud_item.label()),
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: HUD_LABEL_FONT_SIZE,
+                            color: Color::GRAY,
+                        }
+                    ),
+                    TextSection::new(
+                        "",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: HUD_VALUE_FONT_SIZE,
+                            color: Color::WHITE,
+                        }
+                    )
+                ])
+                .with_alignment(TextAlignment::CENTER),
+                ..Default::default()
+            })
+            .insert(hud_item);
+        }
+    })
+    .insert(HudRoot);
+}
+
+/// Les textes ne sont modifiés que lorsque leur contenu change,
+/// pour ne pas recalculer leur mise en page à chaque image.
+#[allow(clippy::too_many_arguments)]
+fn hud_system(
+    score: Res<Score>,
+    high_scores: Res<HighScores>,
+    game_type: Res<GameType>,
+    run_stats: Res<RunStats>,
+    palette: Res<Palette>,
+    combo: Option<Res<Combo>>,
+    active_effects: Option<Res<ActiveEffects>>,
+    game_tick: Option<Res<GameTick>>,
+    ghost_race: Option<Res<GhostRace>>,
+    snake_head_query: Query<(&SnakeHead, Option<&BonusTimer>), With<Player>>,
+    mut hud_query: Query<(&HudItem, &mut Text)>,
+) {
+    let snake_head = snake_head_query.get_single().ok();
+    let combo_multiplier = combo.map_or(1, |combo| combo.multiplier());
+    let active_effects = active_effects.as_deref();
+
+    for (hud_item, mut text) in hud_query.iter_mut() {
+        let mut label = hud_item.label().to_owned();
+        let mut color = Color::WHITE;
+
+        let value = match hud_item {
+            HudItem::Score => score.0.to_string(),
+            HudItem::HighScore => {
+                let high_score = high_scores.modes.get(&game_type.wall_type)
+                    .and_then(|high_scores| high_scores.first())
+                    .map_or(0, |high_score| high_score.score);
+                high_score.max(score.0).to_string()
+            },
+            HudItem::Length => snake_head.map_or(0, |(snake_head, _)| 1 + snake_head.body_parts.len()).to_string(),
+            HudItem::Time => match (game_type.move_limit, &game_tick) {
+                // partie limitée : déplacements restants plutôt que temps écoulé
+                (Some(move_limit), Some(game_tick)) => {
+                    label = "Restants".to_owned();
+                    move_limit.saturating_sub(game_tick.0).to_string()
+                },
+                _ => {
+                    let seconds = run_stats.play_time as u32;
+                    format!("{}:{:02}", seconds / 60, seconds % 60)
+                }
+            },
+            HudItem::Bonus => match active_bonus(snake_head.and_then(|(_, bonus_timer)| bonus_timer), active_effects) {
+                Some((bonus_label, remaining)) => {
+                    label = bonus_label.to_owned();
+                    format!("{} s", remaining.ceil())
+                },
+                None => "-".to_owned()
+            },
+            HudItem::Speed => format!("x{:.1}", game_type.speed * active_effects.map_or(1., |active_effects| active_effects.speed_factor())),
+            HudItem::Multiplier => {
+                if combo_multiplier > 1 {
+                    color = palette.combo_text;
+                }
+                let score_multiplier = active_effects.map_or(1, |active_effects| active_effects.score_multiplier());
+                format!("x{}", game_type.multiplier * score_multiplier * combo_multiplier)
+            },
+            HudItem::Ghost => match (&ghost_race, &game_tick) {
+                (Some(ghost_race), Some(game_tick)) => {
+                    let score_delta = ghost_race.score_delta(score.0, game_tick.0);
+                    if score_delta < 0 {
+                        color = palette.collision;
+                    }
+                    format!("{:+}", score_delta)
+                },
+                _ => "-".to_owned()
+            }
+        };
+
+        let label = format!("{}\n", label);
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+        if text.sections[1].value != value || text.sections[1].style.color != color {
+            text.sections[1].value = value;
+            text.sections[1].style.color = color;
+        }
+    }
+}
+
+/// Bonus affiché : l'invincibilité en priorité, sinon l'effet qui dure le plus longtemps,
+/// avec son temps restant en secondes.
+fn active_bonus(bonus_timer: Option<&BonusTimer>, active_effects: Option<&ActiveEffects>) -> Option<(&'static str, f32)> {
+    if let Some(bonus_timer) = bonus_timer {
+        if let Some(life_timer) = &bonus_timer.life_timer {
+            let next_cycles: f32 = BONUS_LIFE_CYCLES[bonus_timer.life_cycle + 1..].iter().sum();
+            return Some(("Invincible", life_timer.remaining_secs() + next_cycles));
+        }
+    }
+
+    active_effects?.0.iter()
+        .map(|(effect, timer)| (effect.label(), timer.remaining_secs()))
+        .max_by(|(_, remaining), (_, other_remaining)| remaining.total_cmp(other_remaining))
+}
+
+fn cleanup_hud_system(mut commands: Commands, hud_query: Query<Entity, With<HudRoot>>) {
+    for entity in hud_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use iyes_loopless::{prelude::{IntoConditionalSystem, ConditionHelpers}, fixedtimestep::FixedTimesteps};
+
+use super::{AppState, ArenaSize, convert, settings::Settings, components::{SnakeHead, SnakeBody, Position}};
+
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            interpolation_system
+                .run_in_bevy_state(AppState::InGame)
+                .run_if(smooth_movement_enabled)
+                .after("position_translation")
+        );
+    }
+}
+
+fn smooth_movement_enabled(settings: Res<Settings>) -> bool {
+    settings.smooth_movement
+}
+
+/// La grille logique ne change pas : seuls la tête et la queue sont dessinées entre deux cases,
+/// selon l'avancement du pas de temps fixe des déplacements.
+fn interpolation_system(
+    arena_size: Res<ArenaSize>,
+    fixed_timesteps: Option<Res<FixedTimesteps>>,
+    mut snake_head_query: Query<(&Position, &SnakeHead, &mut Transform)>,
+    mut snake_body_query: Query<(&SnakeBody, &Position, &mut Transform), Without<SnakeHead>>,
+) {
+    let progress = fixed_timesteps
+        .and_then(|fixed_timesteps| fixed_timesteps.get("snake_move_time").map(|info| info.overstep() as f32))
+        .unwrap_or(0.)
+        .min(1.);
+
+    let body_positions: HashMap<_, Position> = snake_body_query.iter().map(|(snake_body, position, _)| (snake_body.id, *position)).collect();
+    // queue qui avancera au prochain déplacement -> case vers laquelle elle glisse
+    let mut tail_targets = HashMap::new();
+
+    for (position, snake_head, mut transform) in snake_head_query.iter_mut() {
+        if let Some(previous_position) = snake_head.previous_position {
+            // la tête arrive par le bord opposé lorsqu'elle traverse un bord
+            let from = match unwrapped_offset(&previous_position, position, &arena_size) {
+                Some((dx, dy)) => Vec2::new((position.x - dx) as f32, (position.y - dy) as f32),
+                None => Vec2::new(position.x as f32, position.y as f32)
+            };
+            transform.translation = translation(from.lerp(Vec2::new(position.x as f32, position.y as f32), progress), &arena_size, transform.translation.z);
+        }
+
+        if snake_head.growth == 0 {
+            if let Some(tail) = snake_head.body_parts.front() {
+                let next_position = snake_head.body_parts.get(1)
+                    .and_then(|next_body| body_positions.get(&next_body.id))
+                    .unwrap_or(position);
+                tail_targets.insert(tail.id, *next_position);
+            }
+        }
+    }
+
+    for (snake_body, position, mut transform) in snake_body_query.iter_mut() {
+        if let Some(target) = tail_targets.get(&snake_body.id) {
+            // la queue sort par le bord qu'elle traverse
+            if let Some((dx, dy)) = unwrapped_offset(position, target, &arena_size) {
+                let from = Vec2::new(position.x as f32, position.y as f32);
+                transform.translation = translation(from.lerp(from + Vec2::new(dx as f32, dy as f32), progress), &arena_size, transform.translation.z);
+            }
+        }
+    }
+}
+
+/// Déplacement d'une case entre deux positions voisines, en tenant compte de la traversée des bords ;
+/// `None` pour un saut plus grand (portail).
+pub(super) fn unwrapped_offset(from: &Position, to: &Position, arena_size: &ArenaSize) -> Option<(i32, i32)> {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+
+    match (dx.abs(), dy.abs()) {
+        (1, 0) | (0, 1) => Some((dx, dy)),
+        (x, 0) if x == arena_size.tile_width as i32 - 1 => Some((-dx.signum(), 0)),
+        (0, y) if y == arena_size.tile_height as i32 - 1 => Some((0, -dy.signum())),
+        _ => None
+    }
+}
+
+fn translation(position: Vec2, arena_size: &ArenaSize, z: f32) -> Vec3 {
+    Vec3::new(
+        convert(position.x, arena_size.px_width, arena_size.tile_width as f32),
+        convert(position.y, arena_size.px_height, arena_size.tile_height as f32),
+        z,
+    )
+}
@@ -0,0 +1,355 @@
+use std::{fs, thread, collections::BTreeMap, sync::{Mutex, mpsc::{self, Receiver}}, time::Duration};
+
+use bevy::prelude::*;
+use iyes_loopless::prelude::IntoConditionalSystem;
+use serde::{Serialize, Deserialize};
+
+use crate::{ARENA_WIDTH, ARENA_HEIGHT, main_menu::sub_menu::{GameType, Controller, MAX_ENEMIES, MODE_COUNT, mode_multiplier}};
+
+use super::{AppState, Score, SAVE_DIRECTORY, SNAKE_MOVE_TIME, LEADERBOARD_COUNT, LEADERBOARD_CONNECT_TIMEOUT, LEADERBOARD_READ_TIMEOUT, LEADERBOARD_MAX_FRAME_STEPS, LEADERBOARD_MAX_FRAMES, LEADERBOARD_MAX_TICKS, debug::DebugConsole, profile::Profiles, replay::{RunRecording, InputLog}, settings::Settings, simulation::Simulation, stats::RunStats};
+
+// region:    --- Resource
+
+/// Score envoyé au serveur de classement, avec de quoi rejouer la partie pour le vérifier.
+#[derive(Serialize, Deserialize)]
+pub struct ScoreSubmission {
+    pub name: String,
+    pub game_type: GameType,
+    pub score: u32,
+    /// Empreinte des changements de direction de la partie (`RunRecording::direction_hash`), avec la graine de `game_type`.
+    pub replay_hash: u64,
+    pub input_log: InputLog
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub score: u32,
+    pub seed: u64,
+    pub replay_hash: u64
+}
+
+/// Réponse du serveur à un score accepté.
+#[derive(Serialize, Deserialize)]
+pub struct SubmissionReply {
+    /// Place dans le classement du mode (à partir de 1), `None` hors classement.
+    pub rank: Option<usize>
+}
+
+/// Réponse du serveur à une requête refusée.
+#[derive(Serialize, Deserialize)]
+pub struct ErrorReply {
+    pub error: String
+}
+
+/// Classement du serveur par mode de jeu (type de mur), écrit dans `SAVE_DIRECTORY` à chaque score accepté.
+#[derive(Default, Serialize, Deserialize)]
+pub struct LeaderboardTable {
+    pub modes: BTreeMap<usize, Vec<LeaderboardEntry>>
+}
+
+impl LeaderboardTable {
+    fn path() -> String {
+        format!("{}/leaderboard_server.ron", SAVE_DIRECTORY)
+    }
+
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| ron::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let saved = fs::create_dir_all(SAVE_DIRECTORY)
+            .ok()
+            .and_then(|_| ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).ok())
+            .and_then(|content| fs::write(Self::path(), content).ok());
+
+        if saved.is_none() {
+            println!("impossible de sauvegarder le classement dans {}", Self::path());
+        }
+    }
+
+    pub fn contains(&self, replay_hash: u64) -> bool {
+        self.modes.values().flatten().any(|entry| entry.replay_hash == replay_hash)
+    }
+
+    /// Ajoute un score au classement du mode et renvoie sa place (à partir de 1),
+    /// ou `None` s'il ne fait pas partie des `LEADERBOARD_COUNT` meilleurs.
+    pub fn insert(&mut self, wall_type: usize, entry: LeaderboardEntry) -> Option<usize> {
+        let entries = self.modes.entry(wall_type).or_default();
+        // à score égal, le plus ancien reste devant
+        let index = entries.iter().position(|other_entry| other_entry.score < entry.score).unwrap_or(entries.len());
+        if index >= LEADERBOARD_COUNT {
+            return None;
+        }
+
+        entries.insert(index, entry);
+        entries.truncate(LEADERBOARD_COUNT);
+        Some(index + 1)
+    }
+}
+
+/// Seules les parties d'un mode dans ses réglages d'origine sont classées : défi du jour, arène, vitesse
+/// ou multiplicateur modifiés n'ont pas leur place dans le classement du mode.
+pub fn check_ranked(game_type: &GameType) -> Result<(), String> {
+    if game_type.wall_type >= MODE_COUNT {
+        return Err(format!("mode inconnu : {}", game_type.wall_type));
+    }
+    if game_type.controller != Controller::Human {
+        return Err("partie jouée par l'ordinateur".to_owned());
+    }
+    if game_type.daily.is_some() || game_type.move_limit.is_some() {
+        return Err("partie du défi du jour".to_owned());
+    }
+    if game_type.multiplier != mode_multiplier(game_type.wall_type)
+        || game_type.speed != 1.
+        || (game_type.arena_width, game_type.arena_height) != (ARENA_WIDTH, ARENA_HEIGHT)
+        || game_type.enemies > MAX_ENEMIES
+    {
+        return Err("réglages de partie modifiés".to_owned());
+    }
+    Ok(())
+}
+
+/// Un journal démesuré ferait tourner le serveur pendant des heures : il est refusé avant d'être rejoué.
+pub fn check_input_log(input_log: &InputLog) -> Result<(), String> {
+    if input_log.frames.len() > LEADERBOARD_MAX_FRAMES {
+        return Err(format!("journal de {} images, au plus {}", input_log.frames.len(), LEADERBOARD_MAX_FRAMES));
+    }
+
+    let step = Duration::from_millis(SNAKE_MOVE_TIME).as_nanos() as u64;
+    if input_log.frames.iter().any(|logged_frame| logged_frame.delta > LEADERBOARD_MAX_FRAME_STEPS * step) {
+        return Err(format!("image de plus de {} pas de déplacement", LEADERBOARD_MAX_FRAME_STEPS));
+    }
+
+    // chaque image est bornée : la somme ne peut pas déborder
+    let ticks = input_log.frames.iter().map(|logged_frame| logged_frame.delta).sum::<u64>() / step;
+    if ticks > LEADERBOARD_MAX_TICKS {
+        return Err(format!("partie de {} pas de déplacement, au plus {}", ticks, LEADERBOARD_MAX_TICKS));
+    }
+    Ok(())
+}
+
+/// Envoi du score de la dernière partie au serveur de classement.
+#[derive(Resource, Default, Clone, PartialEq, Eq)]
+pub enum LeaderboardStatus {
+    #[default]
+    Unsent,
+    /// Pas de serveur, ou partie hors classement.
+    Skipped,
+    Pending,
+    Ranked(Option<usize>),
+    Failed(String)
+}
+
+/// Meilleurs scores du serveur par mode, demandés à l'ouverture des statistiques.
+#[derive(Resource, Default)]
+pub enum LeaderboardTop {
+    #[default]
+    Unavailable,
+    Loading,
+    Loaded(BTreeMap<usize, Vec<LeaderboardEntry>>),
+    Failed(String)
+}
+
+/// Réponse attendue d'une requête faite hors du thread principal.
+#[derive(Resource)]
+struct PendingRequest<T>(Mutex<Receiver<T>>);
+
+// endregion: --- Resource
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<LeaderboardStatus>()
+        .init_resource::<LeaderboardTop>()
+        .add_system_set(
+            SystemSet::on_enter(AppState::InGame)
+            .with_system(leaderboard_reset_system)
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::GameOver(true))
+            .with_system(leaderboard_submit_system.run_unless_resource_exists::<Simulation>())
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::GameOver(false))
+            .with_system(leaderboard_submit_system.run_unless_resource_exists::<Simulation>())
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::Statistics)
+            .with_system(leaderboard_fetch_system)
+        )
+        .add_system(leaderboard_status_system.run_if_resource_exists::<PendingRequest<LeaderboardStatus>>())
+        .add_system(leaderboard_top_system.run_if_resource_exists::<PendingRequest<LeaderboardTop>>());
+    }
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(LEADERBOARD_CONNECT_TIMEOUT))
+        .timeout_read(Duration::from_secs(LEADERBOARD_READ_TIMEOUT))
+        .build()
+}
+
+/// Message d'erreur d'une requête : celui du serveur s'il a refusé, sinon celui de la connexion.
+fn error_message(error: ureq::Error) -> String {
+    match error {
+        ureq::Error::Status(_, response) => response.into_json::<ErrorReply>()
+            .map_or_else(|error| error.to_string(), |error_reply| error_reply.error),
+        ureq::Error::Transport(transport) => format!("serveur injoignable ({})", transport.kind())
+    }
+}
+
+fn submit(server: &str, submission: &ScoreSubmission) -> LeaderboardStatus {
+    let reply = agent().post(&format!("http://{}/scores", server))
+        .send_json(submission)
+        .map_err(error_message)
+        .and_then(|response| response.into_json::<SubmissionReply>().map_err(|error| error.to_string()));
+
+    match reply {
+        Ok(reply) => LeaderboardStatus::Ranked(reply.rank),
+        Err(error) => LeaderboardStatus::Failed(error)
+    }
+}
+
+fn fetch_top(server: &str) -> LeaderboardTop {
+    let agent = agent();
+    let modes = (0..MODE_COUNT)
+        .map(|wall_type| {
+            agent.get(&format!("http://{}/scores/{}", server, wall_type))
+                .call()
+                .map_err(error_message)
+                .and_then(|response| response.into_json::<Vec<LeaderboardEntry>>().map_err(|error| error.to_string()))
+                .map(|entries| (wall_type, entries))
+        })
+        .collect::<Result<BTreeMap<usize, Vec<LeaderboardEntry>>, String>>();
+
+    match modes {
+        Ok(modes) => LeaderboardTop::Loaded(modes),
+        Err(error) => LeaderboardTop::Failed(error)
+    }
+}
+
+/// Lance la requête dans un thread : le jeu continue pendant l'attente de la réponse.
+fn spawn_request<T: Send + 'static>(commands: &mut Commands, request: impl FnOnce() -> T + Send + 'static) {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(request());
+    });
+    commands.insert_resource(PendingRequest(Mutex::new(receiver)));
+}
+
+/// La réponse d'un envoi encore en attente concerne la partie précédente.
+fn leaderboard_reset_system(mut commands: Commands, mut leaderboard_status: ResMut<LeaderboardStatus>) {
+    commands.remove_resource::<PendingRequest<LeaderboardStatus>>();
+    *leaderboard_status = LeaderboardStatus::Unsent;
+}
+
+/// Envoie le score une seule fois par partie, dès que son issue est connue.
+/// Une partie reprise d'une sauvegarde n'a pas le journal de ses premières touches : elle n'est pas envoyée,
+/// pas plus qu'une partie modifiée avec la console de développement.
+#[allow(clippy::too_many_arguments)]
+fn leaderboard_submit_system(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    score: Res<Score>,
+    run_stats: Res<RunStats>,
+    run_recording: Res<RunRecording>,
+    profiles: Res<Profiles>,
+    debug_console: Option<Res<DebugConsole>>,
+    mut leaderboard_status: ResMut<LeaderboardStatus>,
+) {
+    if *leaderboard_status != LeaderboardStatus::Unsent || run_stats.outcome.is_none() {
+        return;
+    }
+
+    let modified = debug_console.is_some_and(|debug_console| debug_console.used);
+    let game_type = match (&settings.leaderboard_server, &run_recording.game_type) {
+        (Some(_), Some(game_type)) if !run_recording.resumed && !modified && check_ranked(game_type).is_ok() => game_type.clone(),
+        _ => {
+            *leaderboard_status = LeaderboardStatus::Skipped;
+            return;
+        }
+    };
+
+    let server = settings.leaderboard_server.clone().unwrap_or_default();
+    let submission = ScoreSubmission {
+        name: profiles.current().map_or("Anonyme".to_owned(), |profile| profile.name.clone()),
+        score: score.0,
+        replay_hash: run_recording.direction_hash(game_type.seed),
+        input_log: run_recording.input_log.clone(),
+        game_type
+    };
+    spawn_request(&mut commands, move || submit(&server, &submission));
+    *leaderboard_status = LeaderboardStatus::Pending;
+}
+
+fn leaderboard_fetch_system(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut leaderboard_top: ResMut<LeaderboardTop>,
+) {
+    match settings.leaderboard_server.clone() {
+        Some(server) => {
+            spawn_request(&mut commands, move || fetch_top(&server));
+            *leaderboard_top = LeaderboardTop::Loading;
+        },
+        None => *leaderboard_top = LeaderboardTop::Unavailable
+    }
+}
+
+fn leaderboard_status_system(
+    mut commands: Commands,
+    pending_request: Res<PendingRequest<LeaderboardStatus>>,
+    mut leaderboard_status: ResMut<LeaderboardStatus>,
+) {
+    if let Ok(status) = pending_request.0.lock().unwrap().try_recv() {
+        *leaderboard_status = status;
+        commands.remove_resource::<PendingRequest<LeaderboardStatus>>();
+    }
+}
+
+fn leaderboard_top_system(
+    mut commands: Commands,
+    pending_request: Res<PendingRequest<LeaderboardTop>>,
+    mut leaderboard_top: ResMut<LeaderboardTop>,
+) {
+    if let Ok(top) = pending_request.0.lock().unwrap().try_recv() {
+        *leaderboard_top = top;
+        commands.remove_resource::<PendingRequest<LeaderboardTop>>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::replay::LoggedFrame;
+
+    fn input_log(frame_count: usize, delta: u64) -> InputLog {
+        InputLog {
+            start_accumulator: 0,
+            frames: vec![LoggedFrame { delta, pressed: Vec::new(), released: Vec::new() }; frame_count]
+        }
+    }
+
+    #[test]
+    fn input_log_limits() {
+        let step = Duration::from_millis(SNAKE_MOVE_TIME).as_nanos() as u64;
+        let max_delta = LEADERBOARD_MAX_FRAME_STEPS * step;
+
+        assert!(check_input_log(&input_log(100, max_delta)).is_ok());
+        assert!(check_input_log(&input_log(1, max_delta + 1)).is_err());
+        assert!(check_input_log(&input_log(1, u64::MAX)).is_err());
+        assert!(check_input_log(&input_log(LEADERBOARD_MAX_FRAMES, 1)).is_ok());
+        assert!(check_input_log(&input_log(LEADERBOARD_MAX_FRAMES + 1, 1)).is_err());
+
+        let steps_per_frame = LEADERBOARD_MAX_FRAME_STEPS as usize;
+        let ticks = LEADERBOARD_MAX_TICKS as usize;
+        assert!(check_input_log(&input_log(ticks / steps_per_frame, max_delta)).is_ok());
+        assert!(check_input_log(&input_log(ticks / steps_per_frame + 1, max_delta)).is_err());
+    }
+}
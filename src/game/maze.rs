@@ -0,0 +1,301 @@
+use std::collections::{HashSet, VecDeque};
+
+use rand::{Rng, SeedableRng, seq::SliceRandom};
+use rand_chacha::ChaCha8Rng;
+
+use super::{ArenaSize, components::Position};
+
+// region:    --- Maze Constants
+
+const CORRIDOR_STRIDE: i32 = 4;
+const CORRIDOR_EXTRA_OPENING_LUCK: f64 = 0.35;
+
+const BLOCKS_DENSITY: f32 = 0.12;
+const BLOCK_MAX_SIZE: i32 = 3;
+
+const ROOMS_MIN_SIZE: i32 = 5;
+const DOOR_WIDTH: i32 = 2;
+
+// zone laissée libre autour du point d'apparition du serpent (3,3) qui part vers la droite
+const SPAWN_ZONE_MIN: Position = Position {x: 1, y: 2};
+const SPAWN_ZONE_MAX: Position = Position {x: 8, y: 4};
+
+// endregion: --- Maze Constants
+
+#[derive(Debug, Clone, Copy)]
+pub enum MazeStyle {
+    Corridors,
+    Blocks,
+    Rooms
+}
+
+/// Génère les positions des murs intérieurs d'un labyrinthe pour une arène et une graine données.
+/// Toutes les cases libres restantes sont reliées entre elles et la zone de départ du serpent est dégagée.
+pub fn generate_maze(arena_size: &ArenaSize, seed: u64) -> HashSet<Position> {
+    // une graine partagée (défi du jour, --seed) doit redonner ce labyrinthe après une mise à jour de rand
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let (width, height) = (arena_size.tile_width as i32, arena_size.tile_height as i32);
+
+    let style = match rng.gen_range(0..3) {
+        0 => MazeStyle::Corridors,
+        1 => MazeStyle::Blocks,
+        _ => MazeStyle::Rooms
+    };
+
+    let mut walls = match style {
+        MazeStyle::Corridors => corridors(&mut rng, width, height),
+        MazeStyle::Blocks => blocks(&mut rng, width, height),
+        MazeStyle::Rooms => rooms(&mut rng, width, height),
+    };
+
+    clear_spawn_zone(&mut walls);
+    fill_unreachable_cells(&mut walls, width, height);
+
+    walls
+}
+
+fn corridors(rng: &mut ChaCha8Rng, width: i32, height: i32) -> HashSet<Position> {
+    let wall_columns: Vec<i32> = (1..).map(|k| k * CORRIDOR_STRIDE).take_while(|x| *x < width - 1).collect();
+    let wall_rows: Vec<i32> = (1..).map(|k| k * CORRIDOR_STRIDE).take_while(|y| *y < height - 1).collect();
+
+    let mut walls = HashSet::new();
+    for y in 0..height {
+        for x in 0..width {
+            if wall_columns.contains(&x) || wall_rows.contains(&y) {
+                walls.insert(Position {x, y});
+            }
+        }
+    }
+
+    // chaque cellule est délimitée par deux colonnes (ou lignes) de mur consécutives
+    let cell_bounds = |lines: &[i32], size: i32| -> Vec<(i32, i32)> {
+        let mut bounds = Vec::new();
+        let mut start = 0;
+        for line in lines.iter() {
+            bounds.push((start, *line - 1));
+            start = *line + 1;
+        }
+        bounds.push((start, size - 1));
+        bounds
+    };
+    let columns = cell_bounds(&wall_columns, width);
+    let rows = cell_bounds(&wall_rows, height);
+
+    // arbre couvrant aléatoire (parcours en profondeur) sur les cellules
+    let mut visited = vec![vec![false; columns.len()]; rows.len()];
+    let mut stack = vec![(0_usize, 0_usize)];
+    visited[0][0] = true;
+
+    while let Some(&(row, column)) = stack.last() {
+        let mut neighbours = Vec::new();
+        if row > 0 && !visited[row - 1][column] { neighbours.push((row - 1, column)); }
+        if row + 1 < rows.len() && !visited[row + 1][column] { neighbours.push((row + 1, column)); }
+        if column > 0 && !visited[row][column - 1] { neighbours.push((row, column - 1)); }
+        if column + 1 < columns.len() && !visited[row][column + 1] { neighbours.push((row, column + 1)); }
+
+        match neighbours.choose(rng) {
+            Some(&next) => {
+                open_between(&mut walls, &columns, &rows, (row, column), next);
+                visited[next.0][next.1] = true;
+                stack.push(next);
+            },
+            None => {
+                stack.pop();
+            }
+        }
+    }
+
+    // ouvertures supplémentaires pour créer des boucles et éviter les impasses
+    for row in 0..rows.len() {
+        for column in 0..columns.len() {
+            if column + 1 < columns.len() && rng.gen_bool(CORRIDOR_EXTRA_OPENING_LUCK) {
+                open_between(&mut walls, &columns, &rows, (row, column), (row, column + 1));
+            }
+            if row + 1 < rows.len() && rng.gen_bool(CORRIDOR_EXTRA_OPENING_LUCK) {
+                open_between(&mut walls, &columns, &rows, (row, column), (row + 1, column));
+            }
+        }
+    }
+
+    walls
+}
+
+fn open_between(walls: &mut HashSet<Position>, columns: &[(i32, i32)], rows: &[(i32, i32)], cell: (usize, usize), other_cell: (usize, usize)) {
+    if cell.0 == other_cell.0 {
+        let x = columns[cell.1.max(other_cell.1)].0 - 1;
+        let (y_min, y_max) = rows[cell.0];
+        for y in y_min..=y_max {
+            walls.remove(&Position {x, y});
+        }
+    } else {
+        let y = rows[cell.0.max(other_cell.0)].0 - 1;
+        let (x_min, x_max) = columns[cell.1];
+        for x in x_min..=x_max {
+            walls.remove(&Position {x, y});
+        }
+    }
+}
+
+fn blocks(rng: &mut ChaCha8Rng, width: i32, height: i32) -> HashSet<Position> {
+    let mut walls = HashSet::new();
+    let target = ((width * height) as f32 * BLOCKS_DENSITY) as usize;
+    let mut attempts = 0;
+
+    while walls.len() < target && attempts < 1000 {
+        attempts += 1;
+
+        let block_width = rng.gen_range(1..=BLOCK_MAX_SIZE);
+        let block_height = rng.gen_range(1..=BLOCK_MAX_SIZE);
+        let x = rng.gen_range(1..(width - block_width));
+        let y = rng.gen_range(1..(height - block_height));
+
+        // on garde au moins une case libre autour de chaque bloc pour ne pas créer de cul-de-sac
+        let is_isolated = (x - 1..=x + block_width).all(|block_x| {
+            (y - 1..=y + block_height).all(|block_y| !walls.contains(&Position {x: block_x, y: block_y}))
+        });
+
+        if is_isolated {
+            for block_x in x..x + block_width {
+                for block_y in y..y + block_height {
+                    walls.insert(Position {x: block_x, y: block_y});
+                }
+            }
+        }
+    }
+
+    walls
+}
+
+fn rooms(rng: &mut ChaCha8Rng, width: i32, height: i32) -> HashSet<Position> {
+    let mut walls = HashSet::new();
+    split_room(rng, &mut walls, (0, 0), (width - 1, height - 1));
+    walls
+}
+
+/// Découpe récursivement la pièce en deux par un mur percé d'une porte.
+fn split_room(rng: &mut ChaCha8Rng, walls: &mut HashSet<Position>, min: (i32, i32), max: (i32, i32)) {
+    let (room_width, room_height) = (max.0 - min.0 + 1, max.1 - min.1 + 1);
+    let can_split_vertically = room_width > 2 * ROOMS_MIN_SIZE;
+    let can_split_horizontally = room_height > 2 * ROOMS_MIN_SIZE;
+
+    let vertical = match (can_split_vertically, can_split_horizontally) {
+        (false, false) => return,
+        (true, false) => true,
+        (false, true) => false,
+        (true, true) => rng.gen_bool(0.5)
+    };
+
+    if vertical {
+        let x = rng.gen_range(min.0 + ROOMS_MIN_SIZE..=max.0 - ROOMS_MIN_SIZE);
+        let door = rng.gen_range(min.1..=max.1 - DOOR_WIDTH + 1);
+        for y in min.1..=max.1 {
+            if !(door..door + DOOR_WIDTH).contains(&y) {
+                walls.insert(Position {x, y});
+            }
+        }
+        split_room(rng, walls, min, (x - 1, max.1));
+        split_room(rng, walls, (x + 1, min.1), max);
+    } else {
+        let y = rng.gen_range(min.1 + ROOMS_MIN_SIZE..=max.1 - ROOMS_MIN_SIZE);
+        let door = rng.gen_range(min.0..=max.0 - DOOR_WIDTH + 1);
+        for x in min.0..=max.0 {
+            if !(door..door + DOOR_WIDTH).contains(&x) {
+                walls.insert(Position {x, y});
+            }
+        }
+        split_room(rng, walls, min, (max.0, y - 1));
+        split_room(rng, walls, (min.0, y + 1), max);
+    }
+}
+
+fn clear_spawn_zone(walls: &mut HashSet<Position>) {
+    walls.retain(|wall| {
+        !(SPAWN_ZONE_MIN.x..=SPAWN_ZONE_MAX.x).contains(&wall.x) || !(SPAWN_ZONE_MIN.y..=SPAWN_ZONE_MAX.y).contains(&wall.y)
+    });
+}
+
+/// Bouche toute case libre qui n'est pas atteignable depuis le point d'apparition du serpent.
+fn fill_unreachable_cells(walls: &mut HashSet<Position>, width: i32, height: i32) {
+    let start = Position {x: 3, y: 3};
+    let mut reachable = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(position) = queue.pop_front() {
+        let neighbours = [
+            Position {x: position.x + 1, y: position.y},
+            Position {x: position.x - 1, y: position.y},
+            Position {x: position.x, y: position.y + 1},
+            Position {x: position.x, y: position.y - 1},
+        ];
+
+        for neighbour in neighbours {
+            let is_inside = (0..width).contains(&neighbour.x) && (0..height).contains(&neighbour.y);
+            if is_inside && !walls.contains(&neighbour) && reachable.insert(neighbour) {
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    for y in 0..height {
+        for x in 0..width {
+            let position = Position {x, y};
+            if !reachable.contains(&position) {
+                walls.insert(position);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arena_size(tile_width: u32, tile_height: u32) -> ArenaSize {
+        ArenaSize { px_width: 0., tile_width, px_height: 0., tile_height }
+    }
+
+    /// Cases libres atteintes depuis le point d'apparition, sans traverser les bords.
+    fn reachable_cells(walls: &HashSet<Position>, width: i32, height: i32) -> HashSet<Position> {
+        let start = Position {x: 3, y: 3};
+        let mut reachable = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(position) = queue.pop_front() {
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let neighbour = Position {x: position.x + dx, y: position.y + dy};
+                let is_inside = (0..width).contains(&neighbour.x) && (0..height).contains(&neighbour.y);
+                if is_inside && !walls.contains(&neighbour) && reachable.insert(neighbour) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        reachable
+    }
+
+    #[test]
+    fn every_free_cell_is_reachable() {
+        for (width, height) in [(20, 20), (16, 16), (21, 20), (40, 33)] {
+            for seed in 0..200 {
+                let walls = generate_maze(&arena_size(width, height), seed);
+                let (width, height) = (width as i32, height as i32);
+                let reachable = reachable_cells(&walls, width, height);
+
+                for y in 0..height {
+                    for x in 0..width {
+                        let position = Position {x, y};
+                        assert!(
+                            walls.contains(&position) || reachable.contains(&position),
+                            "case ({}, {}) inaccessible, arène {}x{}, graine {}", x, y, width, height, seed
+                        );
+                    }
+                }
+                assert!(!walls.contains(&Position {x: 3, y: 3}), "départ bouché, graine {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_maze() {
+        let arena_size = arena_size(20, 20);
+        assert!(generate_maze(&arena_size, 7) == generate_maze(&arena_size, 7));
+    }
+}
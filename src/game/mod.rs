@@ -1,50 +1,193 @@
 mod components;
 use std::{time::Duration, collections::HashSet};
 
-use components::{SnakeHead, Direction, Size, Position, SnakeBody, Food, FoodTimer, Wall, Collision};
+use components::{SnakeHead, Direction, Size, Position, SnakeBody, Food, FoodTimer, Wall, Collision, Portal, Player, Enemy};
 mod snake;
-use iyes_loopless::prelude::{IntoConditionalSystem, ConditionSet, AppLooplessFixedTimestepExt};
+use iyes_loopless::{prelude::{IntoConditionalSystem, ConditionSet, ConditionHelpers, AppLooplessFixedTimestepExt}, fixedtimestep::FixedTimesteps};
 use snake::SnakePlugin;
 mod food;
-use food::FoodPlugin;
+use food::{FoodPlugin, FoodCatalogue, FoodEffect};
+mod effect;
+use effect::{EffectPlugin, ActiveEffects};
+mod combo;
+use combo::{ComboPlugin, Combo, floating_text_spawn};
+pub(crate) mod stats;
+use stats::{StatsPlugin, DeathCause};
+pub(crate) mod profile;
+use profile::ProfilePlugin;
+mod high_score;
+pub(crate) mod save;
+use save::SavePlugin;
+pub mod replay;
+use replay::ReplayPlugin;
+mod ghost_race;
+use ghost_race::{GhostRacePlugin, GhostRacer};
+pub(crate) mod daily;
+use daily::DailyPlugin;
+pub mod simulation;
+use simulation::Simulation;
+pub mod verification;
+pub mod leaderboard;
+use leaderboard::LeaderboardPlugin;
+pub(crate) mod settings;
+use settings::SettingsPlugin;
+pub(crate) mod theme;
+use theme::{ThemePlugin, Palette};
+mod accessibility;
+use accessibility::AccessibilityPlugin;
+mod sound;
+use sound::SoundPlugin;
+mod vfx;
+use vfx::VfxPlugin;
+mod food_indicator;
+use food_indicator::FoodIndicatorPlugin;
+mod hud;
+use hud::HudPlugin;
+mod interpolation;
+use interpolation::InterpolationPlugin;
+mod spectator;
+use spectator::SpectatorPlugin;
+pub mod headless;
+pub mod debug;
+pub(crate) mod achievement;
+use achievement::AchievementPlugin;
 mod wall;
 use wall::WallPlugin;
+mod maze;
+mod enemy;
+use enemy::EnemyPlugin;
 mod game_over;
 use game_over::GameOverPlugin;
 
 use bevy::{prelude::*, time::FixedTimestep, text::Text2dBounds, ecs::query, sprite};
 use uuid::Uuid;
-use crate::{WINDOW_WIDTH, ARENA_WIDTH, WINDOW_HEIGHT, UPPER_EDGE, ARENA_HEIGHT, main_menu::sub_menu::GameType};
+use crate::{WINDOW_WIDTH, ARENA_WIDTH, WINDOW_HEIGHT, UPPER_EDGE, ARENA_HEIGHT, arena_px_width, arena_px_height, main_menu::sub_menu::GameType};
 
-use self::components::{FoodType, BonusTimer};
+use self::components::BonusTimer;
 
 use super::AppState;
 
 // region:    --- Game Constants
 
-const SNAKE_HEAD_COLOR: Color = Color::rgb(0.7, 0.7, 0.7);
 const SNAKE_HEAD_SIZE: f32 = 0.8;
 
-const SNAKE_BODY_COLOR: Color = Color::rgb(0.3, 0.3, 0.3);
 const SNAKE_BODY_SIZE: f32 = 0.6;
 
-const FOOD_COLOR: Color = Color::rgb(1.0, 0.0, 1.0);
-const GOLD_FOOD_COLOR: Color = Color::rgb(1.0, 0.84, 0.);
+const ENEMY_SPAWN_DELAY: f32 = 3.;
+const ENEMY_SPAWN_MIN_DISTANCE: i32 = 8;
+
 const FOOD_SIZE: f32 = 0.8;
+const BONUS_FOOD_RADIUS: f32 = 10.;
+const FOOD_CATALOGUE_PATH: &str = "assets/foods.ron";
+const FOOD_INDICATOR_FONT_SIZE: f32 = 12.;
+const FOOD_INDICATOR_BAR_HEIGHT: f32 = 3.;
+/// Rayon du halo en cases, avant qu'il ne se resserre.
+const FOOD_INDICATOR_HALO_RADIUS: f32 = 0.8;
+const FOOD_INDICATOR_HALO_ALPHA: f32 = 0.35;
+const THEME_CATALOGUE_PATH: &str = "assets/themes.ron";
+const SOUND_CATALOGUE_PATH: &str = "assets/sounds.ron";
+
+const SNAKE_MOVE_TIME: u64 = 150;
+/// Durées successives de l'invincibilité : la première sans clignotement, puis les étapes d'avertissement.
+const BONUS_LIFE_CYCLES: [f32; 4] = [15., 5., 6., 4.];
+const GHOST_ALPHA: f32 = 0.4;
+const GHOST_RACE_ALPHA: f32 = 0.3;
+/// Nombre de fantômes gardés sur le disque : les parties du menu ont une graine au hasard, les moins récents sont supprimés.
+pub const GHOST_RUN_MAX_FILES: usize = 20;
+
+const HUD_LABEL_FONT_SIZE: f32 = 12.;
+const HUD_VALUE_FONT_SIZE: f32 = 20.;
+
+const COMBO_WINDOW_TICKS: u64 = 20;
+const COMBO_MAX_MULTIPLIER: u32 = 5;
+const FLOATING_TEXT_DURATION: f32 = 1.;
+const FLOATING_TEXT_SPEED: f32 = 40.;
+
+const SAVE_DIRECTORY: &str = "saves";
+/// Version du format de la partie sauvegardée : une sauvegarde d'une autre version est ignorée.
+const SAVED_GAME_VERSION: u32 = 6;
+const ACHIEVEMENT_TOAST_DURATION: f32 = 3.;
+const ACHIEVEMENT_GOLD_FOODS: u32 = 3;
+const ACHIEVEMENT_SURVIVAL_TIME: f32 = 300.;
+const ACHIEVEMENT_WALLS_CROSSED: u32 = 20;
+pub const PROFILE_NAME_MAX_LENGTH: usize = 12;
+const HIGH_SCORE_COUNT: usize = 10;
+/// Classement du serveur du réseau local : scores gardés par mode, attentes maximales en secondes.
+/// La réponse à un score n'arrive qu'une fois la partie rejouée par le serveur.
+const LEADERBOARD_COUNT: usize = 10;
+const LEADERBOARD_CONNECT_TIMEOUT: u64 = 5;
+const LEADERBOARD_READ_TIMEOUT: u64 = 300;
+/// Meilleurs scores du serveur affichés par mode dans les statistiques.
+pub const LEADERBOARD_SHOWN: usize = 3;
+/// Taille maximale d'un score envoyé au serveur, journal des touches compris.
+pub const LEADERBOARD_MAX_BODY: u64 = 16 * 1024 * 1024;
+/// Limites du journal des touches d'un score, vérifiées avant de rejouer la partie :
+/// durée d'une image en pas de déplacement, nombre d'images, durée de la partie en pas de déplacement.
+const LEADERBOARD_MAX_FRAME_STEPS: u64 = 4;
+const LEADERBOARD_MAX_FRAMES: usize = 400_000;
+const LEADERBOARD_MAX_TICKS: u64 = 40_000;
+
+/// Modificateurs tirés pour le défi du jour.
+const DAILY_SPEEDS: [f32; 4] = [0.75, 1., 1.25, 1.5];
+const DAILY_MOVE_LIMITS: [Option<u64>; 4] = [None, None, Some(400), Some(800)];
+/// Jours du défi affichés dans les statistiques.
+pub const DAILY_HISTORY_SHOWN: usize = 7;
+
+const REPLAY_STEP: f32 = 0.1;
+const DEATH_FREEZE_DURATION: f32 = 1.;
+const DEATH_REPLAY_FRAMES: usize = 20;
+const DEATH_REPLAY_STEP: f32 = 0.3;
+const DEATH_HOLD_DURATION: f32 = 1.;
+const COLLISION_HIGHLIGHT_ALPHA: f32 = 0.5;
+const COLLISION_HIGHLIGHT_SIZE: f32 = 1.4;
 
 const TIME_STEP: f32 = 1./60.;
 const BASE_SPEED: f32 = 60.;
 
-const FOOD_MAX: u32 = 3;
-
-const WALL_COLOR: Color = Color::rgb(1., 1., 1.);
 const EXTERIOR_WALL_OFFSET: f32 = 0.5;
 const EXTERIOR_WALL_THICKNESS_COEFF: f32 = 0.125;
 const EXTERIOR_WALL_LENGTH_COEFF: f32 = 1.;
 const INTERIOR_WALL_THICKNESS_COEFF: f32 = 0.5;
 const INTERIOR_WALL_LENGTH_COEFF: f32 = 1.;
-
-const TRANSPARENCY_COEFF: f32 = 0.1;
+const MAZE_WALL_COEFF: f32 = 0.9;
+
+/// Durées des obstacles en déplacements du serpent : ils suivent la vitesse de la partie et ses effets.
+const MOVING_OBSTACLE_STEP: u64 = 3;
+const TIMED_OBSTACLE_PERIOD: u64 = 33;
+const TIMED_OBSTACLE_WARNING: u64 = 7;
+const TIMED_OBSTACLE_WARNING_ALPHA: f32 = 0.3;
+
+const PORTAL_SIZE: f32 = 0.9;
+const PORTAL_COLOR_SPEED: f32 = 120.;
+
+/// Période minimale des changements de couleur lorsque les clignotements sont réduits.
+const FLASHING_MIN_PERIOD: f32 = 0.5;
+
+const VFX_PARTICLE_COUNT: usize = 12;
+const VFX_PARTICLE_SIZE: f32 = 4.;
+const VFX_PARTICLE_SPEED: f32 = 120.;
+const VFX_PARTICLE_DURATION: f32 = 0.5;
+const VFX_SHAKE_DURATION: f32 = 0.4;
+const VFX_SHAKE_INTENSITY: f32 = 6.;
+/// Temps restant à partir duquel une nourriture clignote avant de disparaître.
+const VFX_FOOD_EXPIRY_WARNING: f32 = 2.;
+const VFX_FOOD_BLINK_PERIOD: f32 = 0.15;
+const VFX_POP_DURATION: f32 = 0.25;
+
+/// Mode spectateur : facteur de zoom par cran de molette et limites de l'échelle de la caméra.
+const SPECTATOR_ZOOM_STEP: f32 = 1.1;
+const SPECTATOR_MIN_SCALE: f32 = 0.25;
+const SPECTATOR_MAX_SCALE: f32 = 2.;
+const SPECTATOR_FONT_SIZE: f32 = 8.;
+const SPECTATOR_OVERLAY_Z: f32 = 50.;
+
+/// Informations de développement (F3) et console (F4).
+const DEBUG_FONT_SIZE: f32 = 14.;
+const DEBUG_CONSOLE_MAX_LENGTH: usize = 40;
+
+const SOUND_SAMPLE_RATE: u32 = 44100;
+/// Durée des fondus au début et à la fin de chaque note.
+const SOUND_FADE_DURATION: f32 = 0.005;
 // endregion: --- Game Constants
 
 // region:    --- Resources
@@ -63,62 +206,154 @@ pub struct ArenaSize {
 	pub tile_height: u32,
 }
 
-#[derive(Resource)]
-struct FoodCount(u32);
-
-#[derive(Resource)]
-struct GameTextures {
-	bonus_star: Handle<Image>,
-}
-
 #[derive(Resource)]
 struct Score(u32);
 
+/// Nombre de déplacements effectués depuis le début de la partie.
+#[derive(Resource, Default)]
+pub struct GameTick(pub u64);
+
 #[derive(Resource)]
 struct Camera(Entity);
 
 #[derive(Resource)]
 struct PositionsAvailable(HashSet<Position>);
 
-#[derive(Resource)]
-pub struct CrossingObstaclesTimer(pub Option<Timer>, pub bool, pub bool);
-
-impl Default for CrossingObstaclesTimer {
-    fn default() -> Self {
-        Self(None, false, false)
-    }
+/// Couleurs des murs et du corps pendant l'invincibilité, qui permet de traverser les obstacles.
+#[derive(Resource, Default)]
+pub struct CrossingObstaclesTimer {
+	/// Clignotement du dernier cycle de l'invincibilité.
+	pub blink_timer: Option<Timer>,
+	/// Les couleurs ont été éclaircies au début de l'invincibilité.
+	pub started: bool,
+	/// Murs et corps éclaircis.
+	pub lighten: bool,
+	/// Tête dans un mur, pour ne compter chaque traversée qu'une fois.
+	pub in_wall: bool
 }
 // endregion: --- Resources
 
+// region:    --- Events
+
+/// Événements de jeu du joueur, utilisés par les statistiques de la partie et par les sons.
+pub enum GameEvent {
+	FoodEaten {
+		/// Index de la nourriture dans le catalogue.
+		kind: usize,
+		points: u32,
+		position: Position
+	},
+	Turned,
+	WallCrossed,
+	/// Début de l'invincibilité, puis chaque étape de clignotement avant sa fin.
+	BonusStarted,
+	BonusWarning,
+	BonusEnded,
+	ArenaFilled,
+	Died {
+		cause: DeathCause,
+		position: Position
+	}
+}
+
+// endregion: --- Events
+
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app
 		// .insert_resource(PositionsAvailable(HashSet::new()))
+		.add_event::<GameEvent>()
         .add_plugin(SnakePlugin)
 		.add_plugin(FoodPlugin)
-		.add_plugin(WallPlugin)
+		.add_plugin(FoodIndicatorPlugin)
+		.add_plugin(EffectPlugin)
 		.add_plugin(GameOverPlugin)
         .add_system_set(
 			SystemSet::on_enter(AppState::InGame)
 			.with_system(setup_system)
 		)
 		.add_fixed_timestep(
-			Duration::from_millis(150),
+			Duration::from_millis(SNAKE_MOVE_TIME),
 			// give it a label
 			"snake_move_time",
 		)
+		// sous-étape 0 : choix de la direction des adversaires, sous-étape 1 : déplacement de tous les serpents,
+		// sous-étape 2 : règles qui dépendent des déplacements (collisions, repas, combo, fin de partie)
+		.add_fixed_timestep_child_stage("snake_move_time")
+		.add_fixed_timestep_child_stage("snake_move_time")
 		.add_fixed_timestep_system(
 			"snake_move_time",
-			0,
+			1,
 			snake_movement_system
 				.run_in_bevy_state(AppState::InGame)
+				.label("snake_movement")
+		)
+		// les collisions et les repas sont vérifiés à chaque déplacement, même s'il y en a plusieurs par image
+		.add_fixed_timestep_system(
+			"snake_move_time",
+			2,
+			check_correct_snake_head_position_system
+				.run_in_bevy_state(AppState::InGame)
+				.run_if_not(check_snake_is_invincible_system)
+				.label("snake_head")
+		)
+		.add_fixed_timestep_system(
+			"snake_move_time",
+			2,
+			wall_crossing_system
+				.run_in_bevy_state(AppState::InGame)
+				.run_if(check_snake_is_invincible_system)
+				.run_if_resource_exists::<CrossingObstaclesTimer>()
+				.label("snake_head")
+		)
+		.add_fixed_timestep_system(
+			"snake_move_time",
+			2,
+			snake_ate_food_system
+				.run_in_bevy_state(AppState::InGame)
+				.label("snake_ate_food")
+				.after("snake_head")
+		)
+		.add_fixed_timestep_system(
+			"snake_move_time",
+			2,
+			check_end_of_game_system
+				.run_in_bevy_state(AppState::InGame)
+				.label("end_of_game")
+				.label("game_end")
+				.after("enemy_collision")
+		)
+		.add_fixed_timestep_system(
+			"snake_move_time",
+			2,
+			check_move_limit_system
+				.run_in_bevy_state(AppState::InGame)
+				.label("game_end")
+				.after("end_of_game")
 		)
+		.add_plugin(WallPlugin)
+		.add_plugin(EnemyPlugin)
+		.add_plugin(ComboPlugin)
+		.add_plugin(HudPlugin)
+		.add_plugin(StatsPlugin)
+		.add_plugin(ProfilePlugin)
+		.add_plugin(AchievementPlugin)
+		.add_plugin(ReplayPlugin)
+		.add_plugin(GhostRacePlugin)
+		.add_plugin(DailyPlugin)
+		.add_plugin(LeaderboardPlugin)
+		.add_plugin(SavePlugin)
+		.add_plugin(SettingsPlugin)
+		.add_plugin(AccessibilityPlugin)
+		.add_plugin(InterpolationPlugin)
+		.add_plugin(SpectatorPlugin)
 		.add_system_set_to_stage(
 			CoreStage::PostUpdate,
 			ConditionSet::new()
 				.run_in_bevy_state(AppState::InGame)
+				.label("position_translation")
 				.with_system(position_translation_system)
 				.with_system(size_scaling_system).into(),
 		)
@@ -128,32 +363,28 @@ impl Plugin for GamePlugin {
 		// 		.run_in_bevy_state(AppState::InGame)
 		// 		.with_system(check_correct_snake_head_position_system).into(),
 		// )
-		.add_system_set(
-			ConditionSet::new()
-			.run_if_not(check_snake_is_invincible_system)
-			.run_in_bevy_state(AppState::InGame)
-			.with_system(check_correct_snake_head_position_system)
-			.into()
-		)
 		.add_system_set(
 			ConditionSet::new()
 			.run_if(check_snake_is_invincible_system)
 			.run_if_resource_exists::<CrossingObstaclesTimer>()
 			.run_in_bevy_state(AppState::InGame)
+			.label("game_effects")
+			.after("game_walls")
 			.with_system(snake_bonus_timer_system)
 			.with_system(obstacles_crossing_system)
 			.into()
 		)
-		.add_system(snake_ate_food_system.run_in_bevy_state(AppState::InGame))
-		.add_system(score_system.run_in_bevy_state(AppState::InGame))
-		.add_system(check_end_of_game_system.run_in_bevy_state(AppState::InGame))
 		// .add_system_set_to_stage(
 		// 	CoreStage::PostUpdate,
 		// 	ConditionSet::new()
 		// 		.run_in_bevy_state(AppState::InGame)
 		// 		.with_system(score_system).into()
 		// 	)
-		.add_system_set(SystemSet::on_update(AppState::InGame).with_system(back_to_main_menu_controls_system))
+		.add_system_set(
+			SystemSet::on_update(AppState::InGame)
+			.label("game_input")
+			.with_system(back_to_main_menu_controls_system)
+		)
 		.add_system_set(
             SystemSet::on_update(AppState::Pause)
                 .with_system(back_to_main_menu_controls_system)
@@ -162,12 +393,22 @@ impl Plugin for GamePlugin {
 			SystemSet::on_exit(AppState::InGame)
 			.with_system(cleanup_system)
 		);
+
+		// une partie simulée n'a besoin que des couleurs du thème : ni son, ni effets visuels
+		if app.world.contains_resource::<Simulation>() {
+			theme::insert_theme_resources(app);
+		} else {
+			app
+				.add_plugin(ThemePlugin)
+				.add_plugin(SoundPlugin)
+				.add_plugin(VfxPlugin);
+		}
     }
 }
 
 fn setup_system(
 	mut commands: Commands,
-	mut windows: ResMut<Windows>,
+	windows: Res<Windows>,
 	asset_server: Res<AssetServer>,
 	game_type: Res<GameType>,
 	// mut positions_available: ResMut<PositionsAvailable>
@@ -180,9 +421,8 @@ fn setup_system(
 
 	commands.insert_resource(Camera(camera));
 
-	// capture window size
-	let window = windows.get_primary_mut().unwrap();
-	let (win_w, win_h) = (window.width(), window.height());
+	// capture window size (sans fenêtre en mode headless)
+	let (win_w, win_h) = windows.get_primary().map_or((WINDOW_WIDTH, WINDOW_HEIGHT), |window| (window.width(), window.height()));
 
 
 	// position window (for tutorial)
@@ -191,8 +431,8 @@ fn setup_system(
 	// add WinSize resource
 	commands.insert_resource(WinSize { width: win_w, height: win_h });
 
-	let mut tile_width = ARENA_WIDTH;
-	let mut tile_height = ARENA_HEIGHT;
+	let mut tile_width = game_type.arena_width;
+	let mut tile_height = game_type.arena_height;
 
 	
 	match game_type.wall_type {
@@ -207,22 +447,20 @@ fn setup_system(
 
 	// add ArenaSize resource
 	commands.insert_resource(ArenaSize {
-		px_width: WINDOW_WIDTH,
+		px_width: arena_px_width(game_type.arena_width),
 		tile_width,
-		px_height: WINDOW_HEIGHT,
+		px_height: arena_px_height(game_type.arena_height),
 		tile_height
 	});
 
 
-	// add count food resource
-	commands.insert_resource(FoodCount(0));
-
 	// add GameTextures resource
 	// commands.insert_resource(GameTextures {bonus_star: asset_server.load("star.png")}); ne voulais pas changer de couleur
 	// commands.insert_resource(GameTextures {bonus_star: asset_server.load("player_b_01.png")}); Seul png qui changeait bien de couleur
 	
 	// add score resource
 	commands.insert_resource(Score(0));
+	commands.insert_resource(GameTick::default());
 
 	// init positions available
 	commands.insert_resource(PositionsAvailable(get_all_arena_positions(tile_height as usize, tile_width as usize)));
@@ -322,73 +560,101 @@ fn get_all_arena_positions(height: usize, width: usize) -> HashSet<Position> {
 	}	
 } */
 
+#[allow(clippy::too_many_arguments)]
 fn snake_movement_system(
 	mut commands: Commands,
 	arena_size: Res<ArenaSize>,
 	game_type: Res<GameType>,
-	mut snake_head_query: Query<(&Direction, &mut Position, &mut SnakeHead)>,
+	palette: Res<Palette>,
+	mut game_tick: ResMut<GameTick>,
+	mut snake_head_query: Query<(&Direction, &mut Position, &mut SnakeHead, Option<&Enemy>)>,
 	mut snake_body_query: Query<(Entity, &mut SnakeBody)>,
+	portal_query: Query<(&Position, &Portal), Without<SnakeHead>>,
 ) {
-	if let Ok((snake_direction, mut snake_position, mut snake_head)) = snake_head_query.get_single_mut() {
-		let snake_head_actual_position = snake_position.clone();
+	game_tick.0 += 1;
+
+	for (snake_direction, mut snake_position, mut snake_head, enemy) in snake_head_query.iter_mut() {
+		let snake_head_actual_position = *snake_position;
 
-		update_snake_head_position(snake_direction, &mut snake_position, arena_size, game_type.wall_type);
+		update_snake_head_position(snake_direction, &mut snake_position, &arena_size, game_type.wall_type);
+		go_through_portal(&mut snake_position, &portal_query);
 		snake_head.moved = true;
+		snake_head.previous_position = Some(snake_head_actual_position);
 		
-		add_new_body_part(&mut commands, snake_head_actual_position, &mut *snake_head);
+		add_new_body_part(&mut commands, &palette, snake_head_actual_position, &mut snake_head, enemy.is_some());
 
-		if snake_head.ate {
-			snake_head.ate = false;
+		if snake_head.growth > 0 {
+			snake_head.growth -= 1;
 		} else {
-			remove_body_part(snake_head, snake_body_query, &mut commands);
+			remove_body_part(&mut snake_head, &mut snake_body_query, &mut commands);
 		}
 	}
 }
 
-fn update_snake_head_position(snake_direction: &Direction, mut snake_position: &mut Mut<Position>, arena_size: Res<ArenaSize>, wall_type: usize) {
-	let can_pass = wall_type != 1;
+fn update_snake_head_position(snake_direction: &Direction, snake_position: &mut Mut<Position>, arena_size: &ArenaSize, wall_type: usize) {
+	**snake_position = next_head_position(snake_position, snake_direction, arena_size, wall_type);
+}
+
+/// Case atteinte en avançant d'une case dans la direction donnée, en traversant les bords si le mode le permet.
+fn next_head_position(position: &Position, direction: &Direction, arena_size: &ArenaSize, wall_type: usize) -> Position {
+	let can_pass = wall_type != 1 && wall_type != 5;
+	let mut next_position = *position;
 	
-	match snake_direction {
+	match direction {
 		Direction::UP => {
-			snake_position.y += 1;
-			if snake_position.y == arena_size.tile_height as i32 && can_pass {
-				snake_position.y = 0;
+			next_position.y += 1;
+			if next_position.y == arena_size.tile_height as i32 && can_pass {
+				next_position.y = 0;
 			}
 		},
 		Direction::DOWN => {
-			snake_position.y -= 1;
-			if snake_position.y < 0 && can_pass {
-				snake_position.y = arena_size.tile_height as i32 - 1;
+			next_position.y -= 1;
+			if next_position.y < 0 && can_pass {
+				next_position.y = arena_size.tile_height as i32 - 1;
 			}
 		},
 		Direction::LEFT => {
-			snake_position.x -= 1;
-			if snake_position.x < 0 && can_pass {
-				snake_position.x = arena_size.tile_width as i32 - 1;
+			next_position.x -= 1;
+			if next_position.x < 0 && can_pass {
+				next_position.x = arena_size.tile_width as i32 - 1;
 			}
 		},
 		Direction::RIGHT => {
-			snake_position.x += 1;
-			if snake_position.x == arena_size.tile_width as i32 && can_pass {
-				snake_position.x = 0;
+			next_position.x += 1;
+			if next_position.x == arena_size.tile_width as i32 && can_pass {
+				next_position.x = 0;
 			}
 		},
 	}
+
+	next_position
+}
+
+fn go_through_portal(snake_position: &mut Mut<Position>, portal_query: &Query<(&Position, &Portal), Without<SnakeHead>>) {
+	for (portal_position, portal) in portal_query.iter() {
+		if is_same_position(snake_position, portal_position) {
+			**snake_position = portal.exit;
+			return;
+		}
+	}
 }
 
-fn add_new_body_part(commands: &mut Commands, snake_head_actual_position: Position, snake_head: &mut SnakeHead) {
+fn add_new_body_part(commands: &mut Commands, palette: &Palette, snake_head_actual_position: Position, snake_head: &mut SnakeHead, enemy: bool) {
 	// add snake_body
     // first snake_body
     let snake_body = SnakeBody {
-        is_tail: snake_head.body_parts.len() == 1 && !snake_head.ate,
+        is_tail: snake_head.body_parts.len() == 1 && snake_head.growth == 0,
         id: Uuid::new_v4()
     };
 
-	let color = get_color(snake_head.invincible, SNAKE_BODY_COLOR);
+	let color = match enemy {
+		true => palette.enemy_body,
+		false => get_color(snake_head.invincible, palette.snake_body, palette.crossing_alpha)
+	};
 
-    commands.spawn(SpriteBundle {
+    let mut body_part = commands.spawn(SpriteBundle {
         sprite: Sprite {
-            color: color,
+            color,
             ..Default::default()
         },
         transform: Transform {
@@ -396,15 +662,21 @@ fn add_new_body_part(commands: &mut Commands, snake_head_actual_position: Positi
             ..Default::default()
         },
         ..Default::default()
-    })
+    });
+
+    body_part
     .insert(snake_body.clone()) 
     .insert(Position {x: snake_head_actual_position.x, y: snake_head_actual_position.y})
     .insert(Size::square(0.6));
 
+	if enemy {
+		body_part.insert(Enemy);
+	}
+
 	snake_head.body_parts.push_back(snake_body);
 }
 
-fn remove_body_part(mut snake_head: Mut<SnakeHead>, mut snake_body_query: Query<(Entity, &mut SnakeBody)>, commands: &mut Commands) {
+fn remove_body_part(snake_head: &mut Mut<SnakeHead>, snake_body_query: &mut Query<(Entity, &mut SnakeBody)>, commands: &mut Commands) {
     let uuid_ex_body_part = &snake_head.body_parts.pop_front().unwrap().id;
 	snake_head.body_parts[0].is_tail = true;
 	let uuid_new_tail_body_part = &snake_head.body_parts[0].id;
@@ -429,12 +701,15 @@ fn size_scaling_system(arena_size: Res<ArenaSize>, mut q: Query<(&Size, &mut Tra
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn position_translation_system(
 	arena_size: Res<ArenaSize>,
 	mut snake_head_query: Query<(&Position, &mut Transform), With<SnakeHead>>,
-	mut snake_body_query: Query<(&Position, &mut Transform), (With<SnakeBody>, Without<SnakeHead>)>
+	mut snake_body_query: Query<(&Position, &mut Transform), (With<SnakeBody>, Without<SnakeHead>)>,
+	mut portal_query: Query<(&Position, &mut Transform), (With<Portal>, Without<SnakeBody>, Without<SnakeHead>)>,
+	mut ghost_query: Query<(&Position, &mut Transform), (With<GhostRacer>, Without<Portal>, Without<SnakeBody>, Without<SnakeHead>)>
 ) {
-	if let Ok((snake_position, mut snake_transform)) = snake_head_query.get_single_mut() {
+	for (snake_position, mut snake_transform) in snake_head_query.iter_mut() {
 		snake_transform.translation = Vec3::new(
 			convert(snake_position.x as f32, arena_size.px_width, arena_size.tile_width as f32),
 			convert(snake_position.y as f32, arena_size.px_height, arena_size.tile_height as f32),
@@ -449,114 +724,167 @@ fn position_translation_system(
 			0.0,
 		);
 	}
+
+	// les portails sont dessinés sous le serpent
+	for (portal_position, mut portal_transform) in portal_query.iter_mut() {
+		portal_transform.translation = Vec3::new(
+			convert(portal_position.x as f32, arena_size.px_width, arena_size.tile_width as f32),
+			convert(portal_position.y as f32, arena_size.px_height, arena_size.tile_height as f32),
+			-1.0,
+		);
+	}
+
+	// le fantôme est dessiné entre les portails et le serpent
+	for (ghost_position, mut ghost_transform) in ghost_query.iter_mut() {
+		ghost_transform.translation = Vec3::new(
+			convert(ghost_position.x as f32, arena_size.px_width, arena_size.tile_width as f32),
+			convert(ghost_position.y as f32, arena_size.px_height, arena_size.tile_height as f32),
+			-0.5,
+		);
+	}
 }
 
-fn check_snake_is_invincible_system(snake_head_query: Query<&SnakeHead>,) -> bool {
+fn check_snake_is_invincible_system(snake_head_query: Query<&SnakeHead, With<Player>>,) -> bool {
 	let snake_head = snake_head_query.get_single().unwrap();
 	snake_head.invincible
 }
 
+#[allow(clippy::too_many_arguments)]
 fn check_correct_snake_head_position_system(
-	snake_head_query: Query<&Position, With<SnakeHead>>,
-	snake_body_query: Query<&Position, With<SnakeBody>>,
+	snake_head_query: Query<&Position, (With<SnakeHead>, With<Player>)>,
+	snake_body_query: Query<(&Position, Option<&Enemy>), With<SnakeBody>>,
 	wall_query: Query<&Position, (With<Wall>, With<Collision>)>,
+	enemy_head_query: Query<&Position, (With<SnakeHead>, With<Enemy>)>,
+	active_effects: Option<Res<ActiveEffects>>,
+	fixed_timesteps: Option<ResMut<FixedTimesteps>>,
+	mut game_events: EventWriter<GameEvent>,
 	mut app_state: ResMut<State<AppState>>
 ) {
 	if let Ok(snake_head_position) = snake_head_query.get_single() {
-		if collide_with_body(snake_head_position, snake_body_query) || collide_with_wall(snake_head_position, wall_query) {
-			app_state.set(AppState::GameOver(false));
+		// en mode fantôme le serpent traverse les corps et les têtes, mais pas les murs
+		let ghost = active_effects.is_some_and(|active_effects| active_effects.ghost());
+		let collide_with_enemy_head = enemy_head_query.iter().any(|enemy_head_position| is_same_position(snake_head_position, enemy_head_position));
+		// le corps des adversaires est compris dans snake_body_query
+		let body_collision = snake_body_query.iter()
+			.find(|(snake_body_position, _)| is_same_position(snake_head_position, snake_body_position))
+			.map(|(_, enemy)| enemy.is_some());
+
+		let death_cause = if collide_with_wall(snake_head_position, wall_query) {
+			Some(DeathCause::Wall)
+		} else if ghost {
+			None
+		} else if collide_with_enemy_head || body_collision == Some(true) {
+			Some(DeathCause::Enemy)
+		} else if body_collision == Some(false) {
+			Some(DeathCause::OwnBody)
+		} else {
+			None
+		};
+
+		if let Some(death_cause) = death_cause {
+			game_events.send(GameEvent::Died { cause: death_cause, position: *snake_head_position });
+			// l'écran de fin suit le ralenti de la collision
+			app_state.set(AppState::DeathReplay);
+			stop_snake_moves(fixed_timesteps);
 		}
 	}
 }
 
-fn collide_with_body(snake_head_position: &Position, snake_body_query: Query<&Position, With<SnakeBody>>) -> bool {
-	for snake_body_position in snake_body_query.iter() {
-		if is_same_position(snake_head_position, snake_body_position) {
+fn collide_with_wall(snake_head_position: &Position, wall_query: Query<&Position, (With<Wall>, With<Collision>)>) -> bool {
+	for wall_position in wall_query.iter() {
+		if is_same_position(snake_head_position, wall_position) {
 			return true;
 		}
 	}
 	false
 }
 
-fn collide_with_wall(snake_head_position: &Position, wall_query: Query<&Position, (With<Wall>, With<Collision>)>) -> bool {
-	for wall_position in wall_query.iter() {
-		if is_same_position(snake_head_position, wall_position) {
-			return true;
+/// Une traversée est comptée à l'entrée dans un mur, quelle que soit son épaisseur.
+fn wall_crossing_system(
+	snake_head_query: Query<&Position, (With<SnakeHead>, With<Player>)>,
+	wall_query: Query<&Position, (With<Wall>, With<Collision>)>,
+	mut crossing_obstacles_timer: ResMut<CrossingObstaclesTimer>,
+	mut game_events: EventWriter<GameEvent>,
+) {
+	if let Ok(snake_head_position) = snake_head_query.get_single() {
+		let in_wall = collide_with_wall(snake_head_position, wall_query);
+		if in_wall && !crossing_obstacles_timer.in_wall {
+			game_events.send(GameEvent::WallCrossed);
 		}
+		crossing_obstacles_timer.in_wall = in_wall;
 	}
-	false
 }
 
+#[allow(clippy::type_complexity)]
 fn snake_bonus_timer_system(
     mut commands: Commands,
     time: Res<Time>,
-    mut snake_head_query: Query<(Entity, &mut Sprite, &mut SnakeHead, &mut BonusTimer), (Without<SnakeBody>, With<SnakeHead>)>,
+    palette: Res<Palette>,
+    mut snake_head_query: Query<(Entity, &mut Sprite, &mut SnakeHead, &mut BonusTimer), (Without<SnakeBody>, With<SnakeHead>, With<Player>)>,
+	mut game_events: EventWriter<GameEvent>,
 ) {
 	let (mut snake_entity, mut sprite, mut snake_head, mut bonus_timer) = snake_head_query.get_single_mut().unwrap();
     
     let mut life_timer = bonus_timer.life_timer.as_mut().unwrap();
     life_timer.tick(time.delta());
     if life_timer.finished() {
-        if bonus_timer.life_cycle == 3 {
+        if bonus_timer.life_cycle == BONUS_LIFE_CYCLES.len() - 1 {
             snake_head.invincible = false;
-			sprite.color = SNAKE_HEAD_COLOR;
+			sprite.color = palette.snake_head;
             commands.entity(snake_entity).remove::<BonusTimer>();
+			game_events.send(GameEvent::BonusEnded);
         } else {
-            bonus_timer.life_cycle += 1 as usize;
+            bonus_timer.life_cycle += 1;
+			game_events.send(GameEvent::BonusWarning);
             
             match bonus_timer.life_cycle {
-                1 => {
-                    bonus_timer.color_timer = Timer::from_seconds(0.5, TimerMode::Repeating);
-                    bonus_timer.life_timer = Some(Timer::from_seconds(5., TimerMode::Once));
-                },
-                2 => {
-                    bonus_timer.color_timer = Timer::from_seconds(1., TimerMode::Repeating);
-                    bonus_timer.life_timer = Some(Timer::from_seconds(6., TimerMode::Once));
-                },
-                3 => {
-                    bonus_timer.color_timer = Timer::from_seconds(2., TimerMode::Repeating);
-                    bonus_timer.life_timer = Some(Timer::from_seconds(4., TimerMode::Once));
-                },
+                1 => bonus_timer.color_timer = Timer::from_seconds(0.5, TimerMode::Repeating),
+                2 => bonus_timer.color_timer = Timer::from_seconds(1., TimerMode::Repeating),
+                3 => bonus_timer.color_timer = Timer::from_seconds(2., TimerMode::Repeating),
                 _ => ()
             }
+            bonus_timer.life_timer = Some(Timer::from_seconds(BONUS_LIFE_CYCLES[bonus_timer.life_cycle], TimerMode::Once));
         }
     }
 
 }
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn obstacles_crossing_system(
 	mut commands: Commands,
 	time: Res<Time>,
-    snake_head_query: Query<&SnakeHead, With<SnakeHead>>,
-    bonus_timer_query: Query<&BonusTimer, With<SnakeHead>>,
+	palette: Res<Palette>,
+    snake_head_query: Query<&SnakeHead, With<Player>>,
+    bonus_timer_query: Query<&BonusTimer, With<Player>>,
 	mut wall_query: Query<&mut Sprite, (With<Wall>, With<Collision>)>,
-	mut snake_body_query: Query<&mut Sprite, (With<SnakeBody>, Without<SnakeHead>, Without<Wall>)>,
+	mut snake_body_query: Query<&mut Sprite, (With<SnakeBody>, Without<SnakeHead>, Without<Wall>, Without<Enemy>)>,
 	mut crossing_obstacles_timer: ResMut<CrossingObstaclesTimer>,
 ) {
-	if !crossing_obstacles_timer.1 {
-		change_color_of_snake_body_and_walls(&mut wall_query, &mut snake_body_query, !crossing_obstacles_timer.2);
-		crossing_obstacles_timer.1 = true;
-		crossing_obstacles_timer.2 = true;
+	let snake_head = snake_head_query.get_single().unwrap();
+	if !crossing_obstacles_timer.started {
+		change_color_of_snake_body_and_walls(&mut wall_query, &mut snake_body_query, &palette, !crossing_obstacles_timer.lighten);
+		crossing_obstacles_timer.started = true;
+		crossing_obstacles_timer.lighten = true;
 	} else {
-		if !snake_head_query.get_single().unwrap().invincible {
+		if !snake_head.invincible {
 			
-			if crossing_obstacles_timer.2 {
-				change_color_of_snake_body_and_walls(&mut wall_query, &mut snake_body_query, !crossing_obstacles_timer.2);
+			if crossing_obstacles_timer.lighten {
+				change_color_of_snake_body_and_walls(&mut wall_query, &mut snake_body_query, &palette, !crossing_obstacles_timer.lighten);
 			}
 
 			commands.remove_resource::<CrossingObstaclesTimer>();
 		} else {
 			let life_cycle = bonus_timer_query.get_single().unwrap().life_cycle;
 			if life_cycle == 3 {
-				match &mut crossing_obstacles_timer.0 {
+				match &mut crossing_obstacles_timer.blink_timer {
 					None => {
-						crossing_obstacles_timer.0 = Some(Timer::from_seconds(0.5, TimerMode::Repeating));
+						crossing_obstacles_timer.blink_timer = Some(Timer::from_seconds(0.5, TimerMode::Repeating));
 					},
 					Some(timer) => {
 						timer.tick(time.delta());
     					if timer.finished() {
-							change_color_of_snake_body_and_walls(&mut wall_query, &mut snake_body_query, !crossing_obstacles_timer.2);
-							crossing_obstacles_timer.2 = !crossing_obstacles_timer.2;
+							change_color_of_snake_body_and_walls(&mut wall_query, &mut snake_body_query, &palette, !crossing_obstacles_timer.lighten);
+							crossing_obstacles_timer.lighten = !crossing_obstacles_timer.lighten;
 						}
 					}
 				}
@@ -566,16 +894,18 @@ fn obstacles_crossing_system(
 	}
 }
 
+#[allow(clippy::type_complexity)]
 fn change_color_of_snake_body_and_walls(
 	mut wall_query: &mut Query<&mut Sprite, (With<Wall>, With<Collision>)>,
-	mut snake_body_query: &mut Query<&mut Sprite, (With<SnakeBody>, Without<SnakeHead>, Without<Wall>)>,
+	mut snake_body_query: &mut Query<&mut Sprite, (With<SnakeBody>, Without<SnakeHead>, Without<Wall>, Without<Enemy>)>,
+	palette: &Palette,
 	lighten: bool
 ) {
-	let mut wall_color = get_color(lighten, WALL_COLOR);
-	let mut snake_body_color = get_color(lighten, SNAKE_BODY_COLOR);
+	let mut snake_body_color = get_color(lighten, palette.snake_body, palette.crossing_alpha);
 
+	// seule la transparence change : les obstacles n'ont pas tous la couleur des murs
 	for mut wall_sprite in wall_query.iter_mut() {
-		wall_sprite.color = wall_color;
+		wall_sprite.color.set_a(if lighten { palette.crossing_alpha } else { 1. });
 	}
 
 	for mut snake_body_sprite in snake_body_query.iter_mut() {
@@ -583,10 +913,10 @@ fn change_color_of_snake_body_and_walls(
 	}
 }
 
-fn get_color(lighten: bool, color_init: Color) -> Color {
+fn get_color(lighten: bool, color_init: Color, alpha: f32) -> Color {
 	if lighten {
 		let rgba = color_init.as_rgba_f32();
-		return Color::rgba(rgba[0], rgba[1], rgba[2], TRANSPARENCY_COEFF);
+		return Color::rgba(rgba[0], rgba[1], rgba[2], alpha);
 	}
 	
 	color_init
@@ -597,61 +927,78 @@ fn convert(pos: f32, bound_window: f32, bound_game: f32) -> f32 {
 	pos / bound_game * bound_window - (bound_window / 2.) + (tile_size / 2.)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn snake_ate_food_system(
 	mut commands: Commands,
-	mut food_count: ResMut<FoodCount>,
+	asset_server: Res<AssetServer>,
+	arena_size: Res<ArenaSize>,
+	palette: Res<Palette>,
+	game_tick: Res<GameTick>,
 	mut score: ResMut<Score>,
-	mut snake_head_query: Query<(Entity, &Position, &mut SnakeHead), With<SnakeHead>>,
+	mut combo: ResMut<Combo>,
+	mut active_effects: ResMut<ActiveEffects>,
+	food_catalogue: Res<FoodCatalogue>,
+	mut snake_head_query: Query<(Entity, &Position, &mut SnakeHead, Option<&Enemy>), With<SnakeHead>>,
+	mut snake_body_query: Query<(Entity, &mut SnakeBody)>,
 	food_query: Query<(Entity, &Position, &FoodTimer, &Food), With<Food>>,
-	game_type: Res<GameType>
+	game_type: Res<GameType>,
+	mut game_events: EventWriter<GameEvent>,
 ) {
-	if let Ok((mut snake_entity, snake_position, mut snake_head)) = snake_head_query.get_single_mut() {
+	for (mut snake_entity, snake_position, mut snake_head, enemy) in snake_head_query.iter_mut() {
 		for (food_entity, food_position, food_timer, food) in food_query.iter() {
 			if is_same_position(snake_position, food_position) {
-				snake_head.ate = true;
+				let food_kind = &food_catalogue.kinds[food.0];
+				snake_head.growth += food_kind.growth;
+				commands.entity(food_entity).despawn();
 
-				match food.0 {
-					FoodType::Simple => {
-						food_count.0 -= 1;
-						score.0 += (get_points(food_timer.0.duration().as_secs(), food_timer.0.elapsed().as_secs()) * game_type.multiplier);
-					},
-					FoodType::Gold => score.0 += (10 * game_type.multiplier),
-					FoodType::Bonus => {
-						snake_head.ate = false;
-						snake_head.invincible = true;
-						commands
-							.entity(snake_entity)
-							.insert(BonusTimer {
-								color_timer: Timer::from_seconds(0.05, TimerMode::Repeating),
-								colors: vec![
-									Color::rgb(1., 0., 0.), // Red
-									Color::rgb(1., 0.5, 0.), // Orange
-									Color::rgb(1., 1., 0.), // Yellow
-									Color::rgb(0.5, 1., 0.), // Chartreuse
-									Color::rgb(0., 1., 0.), // Green
-									Color::rgb(0., 1., 0.5), // Spring Green
-									Color::rgb(0., 1., 1.), // Cyan
-									Color::rgb(0., 0.5, 1.), // Dodger Blue
-									Color::rgb(0., 0., 1.), // Blue
-									Color::rgb(0.5, 0., 1.), // Purple
-									Color::rgb(1., 0., 1.), // Violet
-									Color::rgb(1., 0., 0.5), // Magenta
-								],
-								index_color: 0,
-								life_timer: Some(Timer::from_seconds(15., TimerMode::Once)),
-								life_cycle: 0
-							}
-						);
-
-						commands.insert_resource(CrossingObstaclesTimer::default());
-					}
+				// les adversaires grandissent mais ne marquent pas de points et ne profitent pas des effets
+				if enemy.is_some() {
+					continue;
+				}
+				let points = food_kind.points(food_timer) * game_type.multiplier * active_effects.score_multiplier() * combo.register_food(game_tick.0);
+				score.0 += points;
+				game_events.send(GameEvent::FoodEaten { kind: food.0, points, position: *food_position });
+				if points > 0 {
+					floating_text_spawn(&mut commands, &asset_server, &arena_size, &palette, food_position, points);
+				}
+
+				match food_kind.effect {
+					Some(FoodEffect::Invincibility) => start_invincibility(&mut commands, snake_entity, &mut snake_head, &mut game_events),
+					Some(FoodEffect::Shrink(length)) => shrink_snake(&mut snake_head, &mut snake_body_query, &mut commands, length),
+					Some(effect) => active_effects.add(effect),
+					None => ()
 				}
-				commands.entity(food_entity).despawn();
 			}
 		}
 	}
 }
 
+fn start_invincibility(commands: &mut Commands, snake_entity: Entity, snake_head: &mut SnakeHead, game_events: &mut EventWriter<GameEvent>) {
+	snake_head.invincible = true;
+	commands
+		.entity(snake_entity)
+		.insert(BonusTimer {
+			life_timer: Some(Timer::from_seconds(BONUS_LIFE_CYCLES[0], TimerMode::Once)),
+			..Default::default()
+		}
+	);
+
+	commands.insert_resource(CrossingObstaclesTimer::default());
+	game_events.send(GameEvent::BonusStarted);
+}
+
+/// Raccourcit le serpent par la queue en lui laissant au moins une partie de corps.
+fn shrink_snake(snake_head: &mut Mut<SnakeHead>, snake_body_query: &mut Query<(Entity, &mut SnakeBody)>, commands: &mut Commands, length: u32) {
+	snake_head.growth = snake_head.growth.saturating_sub(length);
+
+	for _ in 0..length {
+		if snake_head.body_parts.len() <= 1 {
+			return;
+		}
+		remove_body_part(snake_head, snake_body_query, commands);
+	}
+}
+
 fn is_same_position(position: &Position, other_position: &Position) -> bool {
 	position.x == other_position.x && position.y == other_position.y
 }
@@ -667,75 +1014,56 @@ fn get_points(duration_secs: u64, elapsed_secs: u64) -> u32 {
 	}
 }
 
-fn score_system(
-	mut commands: Commands,
-	asset_server: Res<AssetServer>,
-	win_size: Res<WinSize>,
-	score: Res<Score>,
-	mut query: Query<Entity, With<Text>>
+/// La partie est gagnée lorsque le serpent du joueur occupe toutes les positions disponibles.
+fn check_end_of_game_system(
+	snake_head_query: Query<&Position, (With<SnakeHead>, With<Player>)>,
+	snake_body_query: Query<&Position, (With<SnakeBody>, Without<Enemy>)>,
+	mut app_state: ResMut<State<AppState>>,
+	positions_available: Res<PositionsAvailable>,
+	fixed_timesteps: Option<ResMut<FixedTimesteps>>,
+	mut game_events: EventWriter<GameEvent>,
 ) {
-	let position_text_y = win_size.height / 2. + WINDOW_HEIGHT * UPPER_EDGE / 2.;
-	let font = asset_server.load("fonts/FiraSans-Bold.ttf");
-	let text_style = TextStyle {
-        font,
-        font_size: 40.,
-        color: Color::WHITE,
-    };
+	// le serpent peut aussi occuper des cases qui ne sont pas des positions disponibles (sortie d'un portail)
+	let occupied_positions: HashSet<Position> = snake_head_query.iter().chain(snake_body_query.iter()).copied().collect();
+	if occupied_positions.len() < positions_available.0.len() {
+		return;
+	}
 
-	for entity in query.iter() {
-		// println!("destruction avt re-creation");
-		// dbg!(entity);
-		commands.entity(entity).despawn();
+	if positions_available.0.iter().all(|position| occupied_positions.contains(position)) {
+		game_events.send(GameEvent::ArenaFilled);
+		app_state.set(AppState::GameOver(true));
+		stop_snake_moves(fixed_timesteps);
 	}
-	
-	let entity = commands.spawn(Text2dBundle {
-		text: Text::from_section(
-			score.0.to_string(),
-			text_style.clone()
-		),
-		// .with_alignment(TextAlignment::TOP_CENTER),
-        transform: Transform {
-            translation: Vec3::new(0., position_text_y - 10., 1.),
-            ..Default::default()
-        },
-		
-		// text_2d_bounds: Text2dBounds {
-		// 	size: Vec2::new(win_w / ARENA_WIDTH as f32, (win_h / (1. + UPPER_EDGE)) / ARENA_HEIGHT as f32)
-		// },
-		// transform: Transform {
-		// 	translation: Vec3::new(
-		// 		convert(x as f32, win_w, ARENA_WIDTH as f32),
-		// 		convert(y as f32, (win_h / (1. + UPPER_EDGE)), ARENA_HEIGHT as f32),
-		// 		1.
-		// 	),
-		// 	// scale: Vec3::new(100., 20., 1.),
-		// 	..Default::default()
-		// },
-		..default()
-	}).id();
-	// println!("creation");
-	// dbg!(entity);
 }
 
-fn check_end_of_game_system(
-	snake_head_query: Query<(&SnakeHead)>,
+/// Une partie limitée en déplacements s'arrête après le dernier, une fois la nourriture mangée.
+fn check_move_limit_system(
+	game_type: Res<GameType>,
+	game_tick: Res<GameTick>,
+	snake_head_query: Query<&Position, (With<SnakeHead>, With<Player>)>,
+	fixed_timesteps: Option<ResMut<FixedTimesteps>>,
+	mut game_events: EventWriter<GameEvent>,
 	mut app_state: ResMut<State<AppState>>,
-	arena_size: Res<ArenaSize>,
-	game_type: Res<GameType>
 ) {
-	let maximun_snake_size;
+	let Some(move_limit) = game_type.move_limit else {
+		return;
+	};
 
-	match game_type.wall_type {
-		4 => maximun_snake_size = (arena_size.tile_width - 1) * (arena_size.tile_height - 1),
-		2 | 3 => maximun_snake_size = (arena_size.tile_width - 1) * arena_size.tile_height,
-		_ => maximun_snake_size = arena_size.tile_width * arena_size.tile_height,
+	if game_tick.0 >= move_limit {
+		if let Ok(snake_head_position) = snake_head_query.get_single() {
+			game_events.send(GameEvent::Died { cause: DeathCause::Timeout, position: *snake_head_position });
+		}
+		// pas de ralenti : aucune collision à montrer
+		app_state.set(AppState::GameOver(false));
+		stop_snake_moves(fixed_timesteps);
 	}
-	
-	let snake_size = 1 + snake_head_query.get_single().unwrap().body_parts.len() as u32;
+}
 
-	if snake_size == maximun_snake_size {
-		println!("snake size {}, taille max {}", snake_size, maximun_snake_size);
-		app_state.set(AppState::GameOver(true));
+/// Le changement d'état n'a lieu qu'à la fin de l'image : les déplacements en retard ne sont pas joués
+/// après celui qui termine la partie.
+fn stop_snake_moves(fixed_timesteps: Option<ResMut<FixedTimesteps>>) {
+	if let Some(snake_move_time) = fixed_timesteps.and_then(|fixed_timesteps| fixed_timesteps.into_inner().get_mut("snake_move_time")) {
+		snake_move_time.accumulator = Duration::ZERO;
 	}
 }
 
@@ -764,23 +1092,15 @@ fn back_to_main_menu_controls_system(mut keys: ResMut<Input<KeyCode>>, mut app_s
     }
 }
 
-fn cleanup_system(mut commands: Commands, camera: Res<Camera>, mut query: Query<Entity, With<Text>>) {
-	if let Ok(entity) = query.get_single() {
-		// println!("destruction final");
-		// dbg!(entity);
-		commands.entity(entity).despawn();
-	}
-	
-	commands.remove_resource::<GameType>();
-	// println!("GameType");
+fn cleanup_system(mut commands: Commands, camera: Res<Camera>) {
+	// GameType est conservé pour pouvoir rejouer la même partie depuis l'écran de fin
 	commands.remove_resource::<ArenaSize>();
 	// println!("ArenaSize");
 	commands.remove_resource::<WinSize>();
 	// println!("WinSize");
-	commands.remove_resource::<FoodCount>();
-	// println!("FoodCount");
 	commands.remove_resource::<PositionsAvailable>();
 	// println!("PositionsAvailable");
+	commands.remove_resource::<GameTick>();
 	commands.remove_resource::<CrossingObstaclesTimer>();
 	commands.entity(camera.0).despawn_recursive();
 	// println!("camera");
@@ -0,0 +1,179 @@
+use std::{fs, collections::BTreeMap};
+
+use bevy::prelude::*;
+use iyes_loopless::prelude::IntoConditionalSystem;
+use serde::{Serialize, Deserialize};
+
+use crate::main_menu::sub_menu::{GameType, Controller};
+
+use super::{AppState, Score, SAVE_DIRECTORY, PROFILE_NAME_MAX_LENGTH, achievement::Achievement, high_score::HighScores, leaderboard::check_ranked, debug::DebugConsole, simulation::Simulation, stats::{RunStats, GameOutcome, DeathCause}};
+
+// region:    --- Profile
+
+/// Statistiques cumulées d'un mode de jeu (type de mur).
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct ModeStats {
+    pub games_played: u32,
+    /// Parties gagnées en remplissant l'arène.
+    pub wins: u32,
+    pub best_score: u32,
+    pub longest_snake: u32,
+    pub play_time: f32
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct ProfileStats {
+    pub modes: BTreeMap<usize, ModeStats>,
+    pub foods_eaten: BTreeMap<String, u32>,
+    pub deaths: BTreeMap<DeathCause, u32>
+}
+
+impl ProfileStats {
+    /// Statistiques de tous les modes réunis.
+    pub fn total(&self) -> ModeStats {
+        self.modes.values().fold(ModeStats::default(), |total, mode_stats| ModeStats {
+            games_played: total.games_played + mode_stats.games_played,
+            wins: total.wins + mode_stats.wins,
+            best_score: total.best_score.max(mode_stats.best_score),
+            longest_snake: total.longest_snake.max(mode_stats.longest_snake),
+            play_time: total.play_time + mode_stats.play_time
+        })
+    }
+
+    fn record(&mut self, run_stats: &RunStats, outcome: GameOutcome, score: u32) {
+        let mode_stats = self.modes.entry(run_stats.wall_type).or_default();
+        mode_stats.games_played += 1;
+        mode_stats.best_score = mode_stats.best_score.max(score);
+        mode_stats.longest_snake = mode_stats.longest_snake.max(run_stats.longest_snake);
+        mode_stats.play_time += run_stats.play_time;
+
+        match outcome {
+            GameOutcome::ArenaFilled => mode_stats.wins += 1,
+            GameOutcome::Died(death_cause) => *self.deaths.entry(death_cause).or_insert(0) += 1
+        }
+
+        for (name, count) in run_stats.foods_eaten.iter() {
+            *self.foods_eaten.entry(name.clone()).or_insert(0) += count;
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub stats: ProfileStats,
+    pub achievements: Vec<Achievement>
+}
+
+// endregion: --- Profile
+
+// region:    --- Resource
+
+/// Profils des joueurs et profil sélectionné, sauvegardés dans `SAVE_DIRECTORY`.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct Profiles {
+    pub profiles: Vec<Profile>,
+    pub current: Option<usize>
+}
+
+impl Profiles {
+    fn path() -> String {
+        format!("{}/profiles.ron", SAVE_DIRECTORY)
+    }
+
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| ron::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let saved = fs::create_dir_all(SAVE_DIRECTORY)
+            .ok()
+            .and_then(|_| ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).ok())
+            .and_then(|content| fs::write(Self::path(), content).ok());
+
+        if saved.is_none() {
+            println!("impossible de sauvegarder les profils dans {}", Self::path());
+        }
+    }
+
+    pub fn current(&self) -> Option<&Profile> {
+        self.current.and_then(|index| self.profiles.get(index))
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut Profile> {
+        self.current.and_then(|index| self.profiles.get_mut(index))
+    }
+
+    /// Crée un profil et le sélectionne ; un nom vide ou déjà pris est refusé.
+    pub fn create(&mut self, name: &str) -> bool {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > PROFILE_NAME_MAX_LENGTH || self.profiles.iter().any(|profile| profile.name == name) {
+            return false;
+        }
+
+        self.profiles.push(Profile {
+            name: name.to_owned(),
+            stats: ProfileStats::default(),
+            achievements: Vec::new()
+        });
+        self.current = Some(self.profiles.len() - 1);
+        true
+    }
+}
+
+// endregion: --- Resource
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(Profiles::load())
+        .insert_resource(HighScores::load())
+        .add_system_set(SystemSet::on_update(AppState::GameOver(false)).with_system(profile_record_system.run_unless_resource_exists::<Simulation>()))
+        .add_system_set(SystemSet::on_update(AppState::GameOver(true)).with_system(profile_record_system.run_unless_resource_exists::<Simulation>()));
+    }
+}
+
+/// Ajoute la partie terminée aux statistiques du profil courant et aux meilleurs scores, une seule fois par partie.
+/// L'issue de la partie est connue au plus tard à la première image de l'écran de fin.
+/// Les parties jouées par l'ordinateur ou modifiées avec la console de développement ne comptent pas.
+/// Seules les parties classées (`check_ranked`) entrent dans les meilleurs scores.
+fn profile_record_system(
+    mut run_stats: ResMut<RunStats>,
+    score: Res<Score>,
+    game_type: Res<GameType>,
+    debug_console: Option<Res<DebugConsole>>,
+    mut profiles: ResMut<Profiles>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    if run_stats.recorded {
+        return;
+    }
+
+    if game_type.controller == Controller::Bot || debug_console.is_some_and(|debug_console| debug_console.used) {
+        run_stats.recorded = true;
+        return;
+    }
+
+    if let Some(outcome) = run_stats.outcome {
+        run_stats.recorded = true;
+
+        let name = profiles.current().map_or("Anonyme".to_owned(), |profile| profile.name.clone());
+        // le tableau est rangé par mode : un défi du jour ou une arène modifiée n'y est pas comparable
+        if check_ranked(&game_type).is_ok() {
+            run_stats.high_score_rank = high_scores.insert(run_stats.wall_type, &name, score.0);
+        }
+        if run_stats.high_score_rank.is_some() {
+            high_scores.save();
+        }
+
+        if let Some(profile) = profiles.current_mut() {
+            profile.stats.record(&run_stats, outcome, score.0);
+            profiles.save();
+        }
+    }
+}
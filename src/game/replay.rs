@@ -0,0 +1,534 @@
+use std::fs;
+
+use bevy::{prelude::*, input::InputSystem};
+use iyes_loopless::{prelude::{IntoConditionalSystem, AppLooplessFixedTimestepExt}, fixedtimestep::FixedTimesteps};
+use serde::{Serialize, Deserialize};
+
+use crate::main_menu::sub_menu::GameType;
+
+use super::{AppState, save::SavedGame, simulation::Simulation, ArenaSize, Score, GameTick, convert, food::FoodCatalogue, stats::{RunStats, GameOutcome}, components::{SnakeHead, SnakeBody, Food, Wall, Collision, Position, Direction, Enemy, Player}, theme::Palette, SNAKE_HEAD_SIZE, SNAKE_BODY_SIZE, FOOD_SIZE, REPLAY_STEP, DEATH_FREEZE_DURATION, DEATH_REPLAY_FRAMES, DEATH_REPLAY_STEP, DEATH_HOLD_DURATION, COLLISION_HIGHLIGHT_ALPHA, COLLISION_HIGHLIGHT_SIZE, SAVE_DIRECTORY, WINDOW_HEIGHT, UPPER_EDGE};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+// region:    --- Resource
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplayCell {
+    PlayerHead,
+    PlayerBody,
+    EnemyHead,
+    EnemyBody,
+    /// Index de la nourriture dans le catalogue.
+    Food(usize),
+    Wall
+}
+
+/// Touches qui agissent sur une partie en cours ou en pause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LoggedKey {
+    Up,
+    Down,
+    Left,
+    Right,
+    Space,
+    Escape
+}
+
+impl LoggedKey {
+    pub const ALL: [LoggedKey; 6] = [LoggedKey::Up, LoggedKey::Down, LoggedKey::Left, LoggedKey::Right, LoggedKey::Space, LoggedKey::Escape];
+
+    pub fn key_code(&self) -> KeyCode {
+        match self {
+            LoggedKey::Up => KeyCode::Up,
+            LoggedKey::Down => KeyCode::Down,
+            LoggedKey::Left => KeyCode::Left,
+            LoggedKey::Right => KeyCode::Right,
+            LoggedKey::Space => KeyCode::Space,
+            LoggedKey::Escape => KeyCode::Escape
+        }
+    }
+}
+
+/// Une image de la partie : sa durée et les touches enfoncées ou relâchées.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LoggedFrame {
+    /// Durée de l'image en nanosecondes.
+    pub delta: u64,
+    pub pressed: Vec<LoggedKey>,
+    pub released: Vec<LoggedKey>
+}
+
+/// Touches de toute la partie, de l'entrée dans `AppState::InGame` à sa sortie, pour la simuler à l'identique.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct InputLog {
+    /// Avance du prochain déplacement à l'entrée dans la partie, en nanosecondes :
+    /// le pas de temps fixe garde le temps accumulé dans les menus.
+    pub start_accumulator: u64,
+    pub frames: Vec<LoggedFrame>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub cells: Vec<(Position, ReplayCell)>,
+    pub score: u32
+}
+
+/// Contenu de l'arène après chaque déplacement de la dernière partie,
+/// écrit dans `SAVE_DIRECTORY` à la fin de chaque partie.
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct RunRecording {
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub px_width: f32,
+    pub px_height: f32,
+    pub frames: Vec<ReplayFrame>,
+    /// Direction du joueur à chaque changement, avec le déplacement où elle a été prise en compte.
+    #[serde(default)]
+    pub inputs: Vec<(u64, Direction)>,
+    #[serde(default)]
+    pub game_type: Option<GameType>,
+    /// Partie reprise depuis une sauvegarde : le journal des touches ne commence pas au début.
+    #[serde(default)]
+    pub resumed: bool,
+    #[serde(default)]
+    pub input_log: InputLog,
+    /// Issue de la partie si elle est connue à l'enregistrement.
+    #[serde(default)]
+    pub outcome: Option<GameOutcome>
+}
+
+impl RunRecording {
+    pub fn path() -> String {
+        format!("{}/last_replay.ron", SAVE_DIRECTORY)
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        let run_recording: RunRecording = ron::from_str(&content).map_err(|error| error.to_string())?;
+        if run_recording.frames.is_empty() {
+            return Err("replay vide".to_owned());
+        }
+        Ok(run_recording)
+    }
+
+    /// Empreinte FNV-1a de la graine et des changements de direction pris en compte, pour vérifier un score partagé.
+    /// Le journal des touches n'y entre pas : deux parties aux mêmes changements de direction ont la même empreinte.
+    pub fn direction_hash(&self, seed: u64) -> u64 {
+        let input_bytes = self.inputs.iter()
+            .flat_map(|(tick, direction)| tick.to_le_bytes().into_iter().chain([*direction as u8]));
+
+        seed.to_le_bytes().into_iter()
+            .chain(input_bytes)
+            .fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+    }
+
+    /// Format compact : une partie enregistre l'arène entière à chaque déplacement.
+    fn save(&self) {
+        let saved = fs::create_dir_all(SAVE_DIRECTORY)
+            .ok()
+            .and_then(|_| ron::to_string(self).ok())
+            .and_then(|content| fs::write(Self::path(), content).ok());
+
+        if saved.is_none() {
+            println!("impossible de sauvegarder le replay dans {}", Self::path());
+        }
+    }
+}
+
+/// Les touches sont journalisées tant qu'elle existe, pause comprise.
+#[derive(Resource)]
+struct InputLogging;
+
+/// Le replay a été ouvert depuis un fichier : il ramène au menu principal plutôt qu'à l'écran de fin.
+#[derive(Resource)]
+pub struct ReplayFromFile;
+
+#[derive(PartialEq, Eq)]
+enum ReplayPhase {
+    /// Arrêt sur l'image de la collision, avant le ralenti.
+    Freeze,
+    Playing,
+    /// Dernière image du ralenti, avant l'écran de fin.
+    Hold
+}
+
+#[derive(Resource)]
+pub(super) struct ReplayPlayer {
+    frame: usize,
+    /// Dernière image affichée.
+    drawn: Option<usize>,
+    timer: Timer,
+    phase: ReplayPhase,
+    /// Met en évidence la case et l'objet percutés sur l'image finale.
+    highlight_collision: bool,
+    pub(super) camera_entity: Entity
+}
+
+// endregion: --- Resource
+
+// region:    --- Component
+
+#[derive(Component)]
+struct ReplayCellSprite;
+
+#[derive(Component)]
+struct ReplayText;
+
+// endregion: --- Component
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<RunRecording>()
+        .add_system_set(
+            SystemSet::on_enter(AppState::InGame)
+            .with_system(recording_setup_system)
+        )
+        .add_system_to_stage(
+            CoreStage::PreUpdate,
+            input_log_system
+                .run_if_resource_exists::<InputLogging>()
+                .after(InputSystem)
+        )
+        .add_fixed_timestep_system(
+            "snake_move_time",
+            2,
+            recording_system
+                .run_in_bevy_state(AppState::InGame)
+                // l'image porte le score du déplacement, repas compris
+                .after("game_end")
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::InGame)
+            .with_system(recording_save_system.run_unless_resource_exists::<Simulation>())
+            .with_system(input_logging_cleanup_system)
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::Replay)
+            .with_system(replay_setup_system)
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Replay)
+            .with_system(replay_step_system)
+            .with_system(replay_system.after(replay_step_system))
+            .with_system(replay_controls_system)
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::Replay)
+            .with_system(cleanup_replay_system)
+        )
+        .add_system_set(
+            SystemSet::on_enter(AppState::DeathReplay)
+            .with_system(death_replay_setup_system)
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::DeathReplay)
+            .with_system(death_replay_step_system)
+            .with_system(replay_system.after(death_replay_step_system))
+            .with_system(replay_controls_system)
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::DeathReplay)
+            .with_system(cleanup_replay_system)
+        );
+    }
+}
+
+/// L'image de l'entrée dans la partie est la première du journal : ses systèmes de mise à jour s'exécutent déjà.
+fn recording_setup_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    game_type: Res<GameType>,
+    saved_game: Option<Res<SavedGame>>,
+    fixed_timesteps: Option<Res<FixedTimesteps>>,
+    mut run_recording: ResMut<RunRecording>,
+) {
+    let start_accumulator = fixed_timesteps
+        .and_then(|fixed_timesteps| fixed_timesteps.get("snake_move_time").map(|info| info.accumulator))
+        .unwrap_or_default();
+
+    *run_recording = RunRecording {
+        game_type: Some(game_type.clone()),
+        resumed: saved_game.is_some(),
+        input_log: InputLog {
+            start_accumulator: start_accumulator.as_nanos() as u64,
+            frames: vec![logged_frame(&time, &keys)]
+        },
+        ..Default::default()
+    };
+    commands.insert_resource(InputLogging);
+}
+
+fn logged_frame(time: &Time, keys: &Input<KeyCode>) -> LoggedFrame {
+    LoggedFrame {
+        delta: time.delta().as_nanos() as u64,
+        pressed: LoggedKey::ALL.into_iter().filter(|key| keys.just_pressed(key.key_code())).collect(),
+        released: LoggedKey::ALL.into_iter().filter(|key| keys.just_released(key.key_code())).collect()
+    }
+}
+
+/// Juste après la mise à jour du clavier, avant que les systèmes du jeu n'effacent des appuis.
+fn input_log_system(time: Res<Time>, keys: Res<Input<KeyCode>>, mut run_recording: ResMut<RunRecording>) {
+    run_recording.input_log.frames.push(logged_frame(&time, &keys));
+}
+
+fn input_logging_cleanup_system(mut commands: Commands) {
+    commands.remove_resource::<InputLogging>();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn recording_system(
+    arena_size: Res<ArenaSize>,
+    score: Res<Score>,
+    game_tick: Res<GameTick>,
+    mut run_recording: ResMut<RunRecording>,
+    player_direction_query: Query<&Direction, (With<SnakeHead>, With<Player>)>,
+    snake_head_query: Query<(&Position, Option<&Enemy>), With<SnakeHead>>,
+    snake_body_query: Query<(&Position, Option<&Enemy>), With<SnakeBody>>,
+    food_query: Query<(&Position, &Food)>,
+    wall_query: Query<&Position, (With<Wall>, With<Collision>)>,
+) {
+    let mut cells: Vec<(Position, ReplayCell)> = wall_query.iter().map(|position| (*position, ReplayCell::Wall)).collect();
+    cells.extend(food_query.iter().map(|(position, food)| (*position, ReplayCell::Food(food.0))));
+    cells.extend(snake_body_query.iter().map(|(position, enemy)| {
+        (*position, if enemy.is_some() { ReplayCell::EnemyBody } else { ReplayCell::PlayerBody })
+    }));
+    cells.extend(snake_head_query.iter().map(|(position, enemy)| {
+        (*position, if enemy.is_some() { ReplayCell::EnemyHead } else { ReplayCell::PlayerHead })
+    }));
+
+    run_recording.tile_width = arena_size.tile_width;
+    run_recording.tile_height = arena_size.tile_height;
+    run_recording.px_width = arena_size.px_width;
+    run_recording.px_height = arena_size.px_height;
+    run_recording.frames.push(ReplayFrame { cells, score: score.0 });
+
+    // le premier déplacement est enregistré avec la direction de départ
+    if let Ok(direction) = player_direction_query.get_single() {
+        if run_recording.inputs.last().map(|(_, last_direction)| last_direction) != Some(direction) {
+            run_recording.inputs.push((game_tick.0, *direction));
+        }
+    }
+}
+
+fn recording_save_system(run_stats: Res<RunStats>, mut run_recording: ResMut<RunRecording>) {
+    run_recording.outcome = run_stats.outcome;
+    if !run_recording.frames.is_empty() {
+        run_recording.save();
+    }
+}
+
+fn replay_player_setup(commands: &mut Commands, asset_server: &AssetServer, run_recording: &RunRecording, replay_player: impl FnOnce(Entity) -> ReplayPlayer) {
+    // même cadrage que la caméra de la partie
+    let camera_entity = commands.spawn(Camera2dBundle {
+        transform: Transform::from_xyz(0., WINDOW_HEIGHT * UPPER_EDGE / 2., 1000. - 0.1),
+        ..Default::default()
+    }).id();
+
+    commands.insert_resource(replay_player(camera_entity));
+
+    commands.spawn(Text2dBundle {
+        text: Text::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 25.,
+                color: Color::WHITE,
+            }
+        )
+        .with_alignment(TextAlignment::CENTER),
+        transform: Transform {
+            translation: Vec3::new(0., run_recording.px_height / 2. + WINDOW_HEIGHT * UPPER_EDGE / 2., 1.),
+            ..Default::default()
+        },
+        ..default()
+    })
+    .insert(ReplayText);
+}
+
+fn replay_setup_system(mut commands: Commands, asset_server: Res<AssetServer>, run_stats: Res<RunStats>, run_recording: Res<RunRecording>) {
+    replay_player_setup(&mut commands, &asset_server, &run_recording, |camera_entity| ReplayPlayer {
+        frame: 0,
+        drawn: None,
+        timer: Timer::from_seconds(REPLAY_STEP, TimerMode::Repeating),
+        phase: ReplayPhase::Playing,
+        highlight_collision: matches!(run_stats.outcome, Some(GameOutcome::Died(_))),
+        camera_entity
+    });
+}
+
+/// Commence par un arrêt sur l'image de la collision.
+fn death_replay_setup_system(mut commands: Commands, asset_server: Res<AssetServer>, run_recording: Res<RunRecording>) {
+    replay_player_setup(&mut commands, &asset_server, &run_recording, |camera_entity| ReplayPlayer {
+        frame: run_recording.frames.len().saturating_sub(1),
+        drawn: None,
+        timer: Timer::from_seconds(DEATH_FREEZE_DURATION, TimerMode::Once),
+        phase: ReplayPhase::Freeze,
+        highlight_collision: true,
+        camera_entity
+    });
+}
+
+fn replay_step_system(time: Res<Time>, run_recording: Res<RunRecording>, mut replay_player: ResMut<ReplayPlayer>) {
+    replay_player.timer.tick(time.delta());
+    if replay_player.timer.just_finished() && replay_player.frame + 1 < run_recording.frames.len() {
+        replay_player.frame += 1;
+    }
+}
+
+/// Arrêt sur la collision, puis les dernières secondes au ralenti, puis l'écran de fin.
+fn death_replay_step_system(
+    time: Res<Time>,
+    run_recording: Res<RunRecording>,
+    mut replay_player: ResMut<ReplayPlayer>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    replay_player.timer.tick(time.delta());
+    if !replay_player.timer.just_finished() {
+        return;
+    }
+
+    let last_frame = run_recording.frames.len().saturating_sub(1);
+    match replay_player.phase {
+        ReplayPhase::Freeze => {
+            replay_player.frame = last_frame.saturating_sub(DEATH_REPLAY_FRAMES);
+            replay_player.timer = Timer::from_seconds(DEATH_REPLAY_STEP, TimerMode::Repeating);
+            replay_player.phase = ReplayPhase::Playing;
+        },
+        ReplayPhase::Playing => {
+            replay_player.frame = (replay_player.frame + 1).min(last_frame);
+            if replay_player.frame == last_frame {
+                replay_player.timer = Timer::from_seconds(DEATH_HOLD_DURATION, TimerMode::Once);
+                replay_player.phase = ReplayPhase::Hold;
+            }
+        },
+        ReplayPhase::Hold => app_state.set(AppState::GameOver(false)).expect("Couldn't switch state to GameOver")
+    }
+}
+
+fn replay_system(
+    mut commands: Commands,
+    run_recording: Res<RunRecording>,
+    food_catalogue: Res<FoodCatalogue>,
+    palette: Res<Palette>,
+    mut replay_player: ResMut<ReplayPlayer>,
+    cell_query: Query<Entity, With<ReplayCellSprite>>,
+    mut replay_text_query: Query<&mut Text, With<ReplayText>>,
+) {
+    let frame_index = replay_player.frame;
+    if replay_player.drawn == Some(frame_index) || frame_index >= run_recording.frames.len() {
+        return;
+    }
+    replay_player.drawn = Some(frame_index);
+    let frame = &run_recording.frames[frame_index];
+    let last_frame = frame_index + 1 == run_recording.frames.len();
+
+    for entity in cell_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let tile_width = run_recording.tile_width as f32;
+    let tile_height = run_recording.tile_height as f32;
+    let (px_width, px_height) = (run_recording.px_width, run_recording.px_height);
+    let cell_spawn = |commands: &mut Commands, position: &Position, color: Color, size: f32, z: f32| {
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color,
+                ..Default::default()
+            },
+            transform: Transform {
+                translation: Vec3::new(
+                    convert(position.x as f32, px_width, tile_width),
+                    convert(position.y as f32, px_height, tile_height),
+                    z,
+                ),
+                scale: Vec3::new(size / tile_width * px_width, size / tile_height * px_height, 1.),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(ReplayCellSprite);
+    };
+
+    // sur l'image finale, la tête du joueur est sur la case de la collision
+    let collision_position = frame.cells.iter()
+        .find(|(_, cell)| matches!(cell, ReplayCell::PlayerHead))
+        .map(|(position, _)| *position)
+        .filter(|_| last_frame && replay_player.highlight_collision);
+
+    if let Some(position) = collision_position {
+        cell_spawn(&mut commands, &position, *palette.collision.clone().set_a(COLLISION_HIGHLIGHT_ALPHA), COLLISION_HIGHLIGHT_SIZE, 4.);
+    }
+
+    for (position, cell) in frame.cells.iter() {
+        let (color, size, z) = match cell {
+            ReplayCell::PlayerHead => (palette.snake_head, SNAKE_HEAD_SIZE, 6.),
+            ReplayCell::PlayerBody => (palette.snake_body, SNAKE_BODY_SIZE, 2.),
+            ReplayCell::EnemyHead => (palette.enemy_head, SNAKE_HEAD_SIZE, 3.),
+            ReplayCell::EnemyBody => (palette.enemy_body, SNAKE_BODY_SIZE, 2.),
+            // un replay ouvert depuis un fichier peut venir d'un autre catalogue
+            ReplayCell::Food(kind) => match food_catalogue.kinds.get(*kind) {
+                Some(food_kind) => (palette.food_color(food_kind), FOOD_SIZE, 1.),
+                None => continue
+            },
+            ReplayCell::Wall => (palette.wall, 1., 0.)
+        };
+
+        // l'objet percuté est dessiné par-dessus le cadre de la collision
+        let collided = collision_position == Some(*position) && !matches!(cell, ReplayCell::PlayerHead | ReplayCell::Food(_));
+        if collided {
+            cell_spawn(&mut commands, position, palette.collision, size, 5.);
+        } else {
+            cell_spawn(&mut commands, position, color, size, z);
+        }
+    }
+
+    for mut text in replay_text_query.iter_mut() {
+        text.sections[0].value = match replay_player.phase {
+            ReplayPhase::Freeze => "Collision !".to_owned(),
+            _ if last_frame => format!("Replay : {} - Fin   (Échap pour revenir)", frame.score),
+            _ => format!("Replay : {}   (Échap pour revenir)", frame.score)
+        };
+    }
+}
+
+fn replay_controls_system(
+    mut commands: Commands,
+    mut keys: ResMut<Input<KeyCode>>,
+    run_stats: Res<RunStats>,
+    replay_from_file: Option<Res<ReplayFromFile>>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    for key_code in [KeyCode::Escape, KeyCode::Return, KeyCode::Space] {
+        if keys.just_pressed(key_code) {
+            keys.reset(key_code);
+            if replay_from_file.is_some() {
+                commands.remove_resource::<ReplayFromFile>();
+                app_state.set(AppState::MainMenu).expect("Couldn't switch state to MainMenu");
+                return;
+            }
+            // à la fin du ralenti de la mort, l'issue n'est pas forcément encore enregistrée
+            let won = *app_state.current() == AppState::Replay && run_stats.outcome == Some(GameOutcome::ArenaFilled);
+            app_state.set(AppState::GameOver(won)).expect("Couldn't switch state to GameOver");
+            return;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn cleanup_replay_system(
+    mut commands: Commands,
+    replay_player: Res<ReplayPlayer>,
+    replay_query: Query<Entity, Or<(With<ReplayCellSprite>, With<ReplayText>)>>,
+) {
+    for entity in replay_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    commands.entity(replay_player.camera_entity).despawn_recursive();
+    commands.remove_resource::<ReplayPlayer>();
+}
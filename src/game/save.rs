@@ -0,0 +1,778 @@
+use std::{fs, mem, collections::{HashMap, VecDeque}, time::Duration};
+
+use bevy::prelude::*;
+use iyes_loopless::{prelude::{IntoConditionalSystem, ConditionSet, ConditionHelpers}, fixedtimestep::FixedTimesteps};
+use rand_chacha::ChaCha8Rng;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use crate::main_menu::sub_menu::GameType;
+
+use super::{AppState, ArenaSize, Score, GameTick, CrossingObstaclesTimer, convert, get_color, combo::Combo, debug::DebugConsole, effect::ActiveEffects, stats::RunStats, enemy::EnemySpawner, simulation::Simulation, food::{food_spawn, FoodEffect, FoodCatalogue, FoodSpawnTimers, FoodRng}, theme::{Palette, ThemeTextures}, SAVE_DIRECTORY, SAVED_GAME_VERSION, BONUS_LIFE_CYCLES, SNAKE_HEAD_SIZE, SNAKE_BODY_SIZE};
+use super::components::{SnakeHead, SnakeBody, Direction, Position, Size, BonusTimer, Food, FoodTimer, MovingObstacle, TimedObstacle, Collision, Player, Enemy};
+
+// region:    --- Saved Game
+
+#[derive(Serialize, Deserialize)]
+struct SavedSnake {
+    position: Position,
+    direction: Direction,
+    growth: u32,
+    /// Parties du corps, de la queue vers la tête.
+    body: Vec<Position>,
+    bonus: Option<SavedBonus>
+}
+
+/// Invincibilité en cours, dans les étapes de `BONUS_LIFE_CYCLES`.
+#[derive(Serialize, Deserialize)]
+struct SavedBonus {
+    life_cycle: usize,
+    life_elapsed: Duration,
+    color_period: Duration,
+    color_elapsed: Duration,
+    index_color: usize
+}
+
+impl SavedBonus {
+    fn bonus_timer(&self) -> BonusTimer {
+        let life_cycle = self.life_cycle.min(BONUS_LIFE_CYCLES.len() - 1);
+
+        BonusTimer {
+            color_timer: timer_with_elapsed(self.color_period, TimerMode::Repeating, self.color_elapsed),
+            index_color: self.index_color,
+            life_timer: Some(timer_with_elapsed(Duration::from_secs_f32(BONUS_LIFE_CYCLES[life_cycle]), TimerMode::Once, self.life_elapsed)),
+            life_cycle,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedFood {
+    /// Index de la nourriture dans le catalogue.
+    kind: usize,
+    position: Position,
+    elapsed: Duration
+}
+
+/// Champs de `CrossingObstaclesTimer`.
+#[derive(Serialize, Deserialize)]
+struct SavedCrossing {
+    blink_elapsed: Option<Duration>,
+    /// Murs et corps éclaircis.
+    lighten: bool,
+    /// Tête dans un mur.
+    in_wall: bool
+}
+
+/// Obstacle mobile, reconnu par la première case de son chemin.
+#[derive(Serialize, Deserialize)]
+struct SavedMovingObstacle {
+    start: Position,
+    index: usize,
+    forward: bool
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedTimedObstacle {
+    position: Position,
+    active: bool
+}
+
+/// Partie quittée depuis la pause, avec de quoi la reprendre à l'identique, hasard des nourritures et des adversaires compris.
+/// Les temps écoulés sont des `Duration` pour ne rien perdre de la précision des timers.
+/// Tant qu'elle est une ressource, la partie reprise n'a pas encore été restaurée.
+#[derive(Resource, Serialize, Deserialize)]
+pub struct SavedGame {
+    version: u32,
+    game_type: GameType,
+    score: u32,
+    game_tick: u64,
+    /// Temps restant avant le prochain déplacement.
+    next_move_in: Duration,
+    player: SavedSnake,
+    enemies: Vec<SavedSnake>,
+    missing_enemies: u32,
+    enemy_spawn_elapsed: Duration,
+    enemy_rng: ChaCha8Rng,
+    foods: Vec<SavedFood>,
+    /// Temps écoulé de chaque timer d'apparition, dans l'ordre du catalogue.
+    food_spawn_elapsed: Vec<Duration>,
+    food_rng: ChaCha8Rng,
+    active_effects: Vec<(FoodEffect, Duration)>,
+    crossing_obstacles: Option<SavedCrossing>,
+    moving_obstacles: Vec<SavedMovingObstacle>,
+    timed_obstacles: Vec<SavedTimedObstacle>,
+    combo: Combo,
+    run_stats: RunStats,
+    /// La console de débogage a modifié la partie : reprise, elle reste hors des records.
+    console_used: bool
+}
+
+/// Lu en premier pour rejeter une sauvegarde d'une autre version sans essayer de la décoder.
+#[derive(Deserialize)]
+struct SavedGameVersion {
+    version: u32
+}
+
+impl SavedGame {
+    fn path() -> String {
+        format!("{}/saved_game.ron", SAVE_DIRECTORY)
+    }
+
+    /// Partie sauvegardée, si elle existe et qu'elle est de la version actuelle.
+    pub fn load() -> Option<Self> {
+        let content = fs::read_to_string(Self::path()).ok()?;
+        match Self::decode(&content) {
+            Ok(saved_game) => Some(saved_game),
+            Err(error) => {
+                println!("partie sauvegardée ignorée ({})", error);
+                None
+            }
+        }
+    }
+
+    fn decode(content: &str) -> Result<Self, String> {
+        ron::from_str::<SavedGameVersion>(content)
+            .map_err(|error| error.to_string())
+            .and_then(|saved_version| match saved_version.version {
+                SAVED_GAME_VERSION => ron::from_str::<SavedGame>(content).map_err(|error| error.to_string()),
+                version => Err(format!("version {} au lieu de {}", version, SAVED_GAME_VERSION))
+            })
+    }
+
+    /// Une partie ne se reprend qu'une fois : la sauvegarde est supprimée à la reprise.
+    pub fn take() -> Option<Self> {
+        let saved_game = Self::load()?;
+        if fs::remove_file(Self::path()).is_err() {
+            println!("impossible de supprimer la partie sauvegardée {}", Self::path());
+        }
+        Some(saved_game)
+    }
+
+    fn save(&self) {
+        let saved = fs::create_dir_all(SAVE_DIRECTORY)
+            .ok()
+            .and_then(|_| ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).ok())
+            .and_then(|content| fs::write(Self::path(), content).ok());
+
+        if saved.is_none() {
+            println!("impossible de sauvegarder la partie dans {}", Self::path());
+        }
+    }
+
+    pub fn game_type(&self) -> GameType {
+        self.game_type.clone()
+    }
+}
+
+fn timer_with_elapsed(duration: Duration, mode: TimerMode, elapsed: Duration) -> Timer {
+    let mut timer = Timer::new(duration, mode);
+    timer.set_elapsed(elapsed);
+    timer
+}
+
+// endregion: --- Saved Game
+
+// region:    --- Resources
+
+/// Pas allongé qui fait reprendre les déplacements là où ils s'étaient arrêtés.
+/// Il est réappliqué jusqu'au premier déplacement, car les effets de vitesse recalculent le pas à chaque image.
+#[derive(Resource)]
+struct ResumeTimestep {
+    step: Duration,
+    tick: u64
+}
+
+/// Obstacles à restaurer : ils apparaissent avec les murs, parfois une image après la reprise.
+#[derive(Resource)]
+struct SavedObstacles {
+    moving: Vec<SavedMovingObstacle>,
+    timed: Vec<SavedTimedObstacle>
+}
+
+// endregion: --- Resources
+
+// region:    --- Component
+
+#[derive(Component)]
+struct PauseText;
+
+// endregion: --- Component
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_system_set(
+            SystemSet::on_enter(AppState::Pause)
+            .with_system(pause_setup_system)
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Pause)
+            .with_system(save_and_quit_system.run_unless_resource_exists::<Simulation>())
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::Pause)
+            .with_system(cleanup_pause_system)
+        )
+        .add_system_set_to_stage(
+            CoreStage::PostUpdate,
+            ConditionSet::new()
+            .run_in_bevy_state(AppState::InGame)
+            .run_if_resource_exists::<SavedGame>()
+            .before("position_translation")
+            .with_system(restore_snakes_system)
+            .with_system(restore_foods_system)
+            .with_system(restore_resources_system)
+            .into()
+        )
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            resume_timestep_system
+                .run_in_bevy_state(AppState::InGame)
+                .run_if_resource_exists::<ResumeTimestep>()
+        )
+        .add_system(
+            restore_obstacles_system
+                .run_in_bevy_state(AppState::InGame)
+                .run_if_resource_exists::<SavedObstacles>()
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::InGame)
+            .with_system(cleanup_save_system)
+        );
+    }
+}
+
+/// Le pas de temps fixe est suspendu pendant la pause, pour conserver l'avancement du prochain déplacement.
+fn set_snake_move_paused(fixed_timesteps: Option<ResMut<FixedTimesteps>>, paused: bool) {
+    if let Some(snake_move_time) = fixed_timesteps.and_then(|fixed_timesteps| fixed_timesteps.into_inner().get_mut("snake_move_time")) {
+        snake_move_time.paused = paused;
+    }
+}
+
+fn pause_setup_system(mut commands: Commands, asset_server: Res<AssetServer>, fixed_timesteps: Option<ResMut<FixedTimesteps>>) {
+    set_snake_move_paused(fixed_timesteps, true);
+
+    commands.spawn(NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            size: bevy::ui::Size::new(Val::Percent(100.), Val::Percent(100.)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        background_color: Color::rgba(0., 0., 0., 0.5).into(),
+        ..Default::default()
+    })
+    .with_children(|parent| {
+        parent.spawn(TextBundle {
+            text: Text::from_section(
+                "Pause\n\nEspace : reprendre\nÉchap : sauvegarder et quitter",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: 24.,
+                    color: Color::WHITE,
+                }
+            )
+            .with_alignment(TextAlignment::CENTER),
+            ..Default::default()
+        });
+    })
+    .insert(PauseText);
+}
+
+fn cleanup_pause_system(mut commands: Commands, fixed_timesteps: Option<ResMut<FixedTimesteps>>, pause_text_query: Query<Entity, With<PauseText>>) {
+    set_snake_move_paused(fixed_timesteps, false);
+
+    for entity in pause_text_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn save_and_quit_system(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut app_state: ResMut<State<AppState>>,
+    game_type: Res<GameType>,
+    score: Res<Score>,
+    game_tick: Res<GameTick>,
+    run_stats: Res<RunStats>,
+    active_effects: Res<ActiveEffects>,
+    // au plus 16 paramètres par système
+    (food_spawn_timers, food_rng, debug_console): (Res<FoodSpawnTimers>, Res<FoodRng>, Option<Res<DebugConsole>>),
+    enemy_spawner: Res<EnemySpawner>,
+    crossing_obstacles_timer: Option<Res<CrossingObstaclesTimer>>,
+    fixed_timesteps: Option<Res<FixedTimesteps>>,
+    combo: Res<Combo>,
+    snake_head_query: Query<(&Position, &Direction, &SnakeHead, Option<&BonusTimer>, Option<&Enemy>)>,
+    snake_body_query: Query<(&SnakeBody, &Position)>,
+    food_query: Query<(&Food, &Position, &FoodTimer)>,
+    obstacle_query: Query<(&Position, Option<&MovingObstacle>, Option<&TimedObstacle>), Or<(With<MovingObstacle>, With<TimedObstacle>)>>,
+) {
+    if !keys.just_pressed(KeyCode::Escape) {
+        return;
+    }
+
+    let body_positions: HashMap<Uuid, Position> = snake_body_query.iter().map(|(snake_body, position)| (snake_body.id, *position)).collect();
+    let saved_snake = |position: &Position, direction: &Direction, snake_head: &SnakeHead, bonus_timer: Option<&BonusTimer>| SavedSnake {
+        position: *position,
+        direction: *direction,
+        growth: snake_head.growth,
+        body: snake_head.body_parts.iter().filter_map(|body_part| body_positions.get(&body_part.id).copied()).collect(),
+        bonus: bonus_timer.and_then(|bonus_timer| Some(SavedBonus {
+            life_cycle: bonus_timer.life_cycle,
+            life_elapsed: bonus_timer.life_timer.as_ref()?.elapsed(),
+            color_period: bonus_timer.color_timer.duration(),
+            color_elapsed: bonus_timer.color_timer.elapsed(),
+            index_color: bonus_timer.index_color
+        }))
+    };
+
+    let mut player = None;
+    let mut enemies = Vec::new();
+    for (position, direction, snake_head, bonus_timer, enemy) in snake_head_query.iter() {
+        match enemy {
+            Some(_) => enemies.push(saved_snake(position, direction, snake_head, None)),
+            None => player = Some(saved_snake(position, direction, snake_head, bonus_timer))
+        }
+    }
+    let player = match player {
+        Some(player) => player,
+        None => return
+    };
+
+    let mut moving_obstacles = Vec::new();
+    let mut timed_obstacles = Vec::new();
+    for (position, moving_obstacle, timed_obstacle) in obstacle_query.iter() {
+        if let Some(moving_obstacle) = moving_obstacle {
+            moving_obstacles.push(SavedMovingObstacle {
+                start: moving_obstacle.path[0],
+                index: moving_obstacle.index,
+                forward: moving_obstacle.forward
+            });
+        }
+        if let Some(timed_obstacle) = timed_obstacle {
+            timed_obstacles.push(SavedTimedObstacle {
+                position: *position,
+                active: timed_obstacle.active
+            });
+        }
+    }
+
+    // le pas de temps est suspendu pendant la pause : l'accumulateur est celui du moment de la pause
+    let next_move_in = fixed_timesteps
+        .and_then(|fixed_timesteps| fixed_timesteps.get("snake_move_time").map(|info| info.step.saturating_sub(info.accumulator)))
+        .unwrap_or_default();
+
+    SavedGame {
+        version: SAVED_GAME_VERSION,
+        game_type: game_type.clone(),
+        score: score.0,
+        game_tick: game_tick.0,
+        next_move_in,
+        player,
+        enemies,
+        missing_enemies: enemy_spawner.missing,
+        enemy_spawn_elapsed: enemy_spawner.timer.elapsed(),
+        enemy_rng: enemy_spawner.rng.clone(),
+        foods: food_query.iter().map(|(food, position, food_timer)| SavedFood {
+            kind: food.0,
+            position: *position,
+            elapsed: food_timer.0.elapsed()
+        }).collect(),
+        food_spawn_elapsed: food_spawn_timers.0.iter().map(|timer| timer.elapsed()).collect(),
+        food_rng: food_rng.0.clone(),
+        active_effects: active_effects.0.iter().map(|(effect, timer)| (*effect, timer.elapsed())).collect(),
+        crossing_obstacles: crossing_obstacles_timer.map(|crossing_obstacles_timer| SavedCrossing {
+            blink_elapsed: crossing_obstacles_timer.blink_timer.as_ref().map(|timer| timer.elapsed()),
+            lighten: crossing_obstacles_timer.lighten,
+            in_wall: crossing_obstacles_timer.in_wall
+        }),
+        moving_obstacles,
+        timed_obstacles,
+        combo: combo.clone(),
+        run_stats: run_stats.clone(),
+        console_used: debug_console.is_some_and(|debug_console| debug_console.used)
+    }.save();
+
+    // quitte la pause puis la partie
+    app_state.replace(AppState::MainMenu).unwrap();
+    keys.reset(KeyCode::Escape);
+}
+
+/// Fait apparaître le corps d'un serpent, de la queue vers la tête.
+fn snake_body_spawn(commands: &mut Commands, color: Color, body: &[Position], enemy: bool) -> VecDeque<SnakeBody> {
+    body.iter().enumerate().map(|(index, position)| {
+        let snake_body = SnakeBody {
+            is_tail: index == 0,
+            id: Uuid::new_v4()
+        };
+
+        let mut body_part = commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color,
+                ..Default::default()
+            },
+            transform: Transform {
+                translation: Vec3::new(position.x as f32, position.y as f32, 10.),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        body_part
+        .insert(snake_body.clone())
+        .insert(*position)
+        .insert(Size::square(SNAKE_BODY_SIZE));
+
+        if enemy {
+            body_part.insert(Enemy);
+        }
+
+        snake_body
+    }).collect()
+}
+
+/// Le serpent de départ prend la place de celui de la sauvegarde ; les adversaires n'apparaissent qu'après un délai,
+/// tous les corps présents sont donc ceux du serpent de départ.
+fn restore_snakes_system(
+    mut commands: Commands,
+    saved_game: Res<SavedGame>,
+    palette: Res<Palette>,
+    mut player_query: Query<(Entity, &mut Position, &mut Direction, &mut SnakeHead), With<Player>>,
+    snake_body_query: Query<Entity, With<SnakeBody>>,
+) {
+    for entity in snake_body_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    if let Ok((entity, mut position, mut direction, mut snake_head)) = player_query.get_single_mut() {
+        let saved_player = &saved_game.player;
+        let lighten = saved_game.crossing_obstacles.as_ref().is_some_and(|crossing_obstacles| crossing_obstacles.lighten);
+
+        *position = saved_player.position;
+        *direction = saved_player.direction;
+        snake_head.growth = saved_player.growth;
+        snake_head.invincible = saved_player.bonus.is_some();
+        snake_head.previous_position = None;
+        snake_head.body_parts = snake_body_spawn(&mut commands, get_color(lighten, palette.snake_body, palette.crossing_alpha), &saved_player.body, false);
+
+        if let Some(saved_bonus) = &saved_player.bonus {
+            commands.entity(entity).insert(saved_bonus.bonus_timer());
+        }
+    }
+
+    for saved_enemy in saved_game.enemies.iter() {
+        let body_parts = snake_body_spawn(&mut commands, palette.enemy_body, &saved_enemy.body, true);
+
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: palette.enemy_head,
+                ..Default::default()
+            },
+            transform: Transform {
+                translation: Vec3::new(saved_enemy.position.x as f32, saved_enemy.position.y as f32, 10.),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(SnakeHead {
+            moved: true,
+            growth: saved_enemy.growth,
+            invincible: false,
+            body_parts,
+            previous_position: None
+        })
+        .insert(saved_enemy.direction)
+        .insert(saved_enemy.position)
+        .insert(Size::square(SNAKE_HEAD_SIZE))
+        .insert(Enemy);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn restore_foods_system(
+    mut commands: Commands,
+    saved_game: Res<SavedGame>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    arena_size: Res<ArenaSize>,
+    food_catalogue: Res<FoodCatalogue>,
+    theme_textures: Res<ThemeTextures>,
+    palette: Res<Palette>,
+) {
+    // le catalogue a pu changer depuis la sauvegarde
+    for saved_food in saved_game.foods.iter().filter(|saved_food| saved_food.kind < food_catalogue.kinds.len()) {
+        let food_entity = food_spawn(&mut commands, &mut meshes, &mut materials, &arena_size, &food_catalogue, &theme_textures, &palette, saved_food.kind, saved_food.position);
+        let lifetime = Duration::from_secs_f32(food_catalogue.kinds[saved_food.kind].lifetime);
+        commands.entity(food_entity).insert(FoodTimer(timer_with_elapsed(lifetime, TimerMode::Once, saved_food.elapsed)));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn restore_resources_system(
+    mut commands: Commands,
+    mut saved_game: ResMut<SavedGame>,
+    mut score: ResMut<Score>,
+    mut game_tick: ResMut<GameTick>,
+    mut run_stats: ResMut<RunStats>,
+    mut combo: ResMut<Combo>,
+    mut active_effects: ResMut<ActiveEffects>,
+    mut food_spawn_timers: ResMut<FoodSpawnTimers>,
+    mut food_rng: ResMut<FoodRng>,
+    mut enemy_spawner: ResMut<EnemySpawner>,
+    debug_console: Option<ResMut<DebugConsole>>,
+    fixed_timesteps: Option<ResMut<FixedTimesteps>>,
+) {
+    score.0 = saved_game.score;
+    game_tick.0 = saved_game.game_tick;
+    *run_stats = mem::take(&mut saved_game.run_stats);
+    *combo = mem::take(&mut saved_game.combo);
+
+    for (effect, elapsed) in saved_game.active_effects.iter() {
+        active_effects.add(*effect);
+        if let Some((_, timer)) = active_effects.0.last_mut() {
+            timer.set_elapsed(*elapsed);
+        }
+    }
+
+    for (timer, elapsed) in food_spawn_timers.0.iter_mut().zip(saved_game.food_spawn_elapsed.iter()) {
+        timer.set_elapsed(*elapsed);
+    }
+    food_rng.0 = saved_game.food_rng.clone();
+
+    enemy_spawner.missing = saved_game.missing_enemies;
+    enemy_spawner.rng = saved_game.enemy_rng.clone();
+    enemy_spawner.timer.set_elapsed(saved_game.enemy_spawn_elapsed);
+
+    // après `debug_console_reset_system`, qui remet la console à zéro à l'entrée dans la partie
+    if let Some(mut debug_console) = debug_console {
+        debug_console.used |= saved_game.console_used;
+    }
+
+    if let Some(crossing_obstacles) = &saved_game.crossing_obstacles {
+        commands.insert_resource(CrossingObstaclesTimer {
+            blink_timer: crossing_obstacles.blink_elapsed.map(|elapsed| timer_with_elapsed(Duration::from_secs_f32(0.5), TimerMode::Repeating, elapsed)),
+            // les murs sont éclaircis au premier passage s'ils l'étaient à la sauvegarde
+            started: !crossing_obstacles.lighten,
+            lighten: false,
+            in_wall: crossing_obstacles.in_wall
+        });
+    }
+
+    // le prochain déplacement a lieu après le temps qu'il lui restait, quel que soit l'accumulateur actuel
+    if let Some(snake_move_time) = fixed_timesteps.and_then(|fixed_timesteps| fixed_timesteps.into_inner().get_mut("snake_move_time")) {
+        let step = saved_game.next_move_in + snake_move_time.accumulator;
+        snake_move_time.step = step;
+        commands.insert_resource(ResumeTimestep { step, tick: saved_game.game_tick });
+    }
+
+    if !saved_game.moving_obstacles.is_empty() || !saved_game.timed_obstacles.is_empty() {
+        commands.insert_resource(SavedObstacles {
+            moving: mem::take(&mut saved_game.moving_obstacles),
+            timed: mem::take(&mut saved_game.timed_obstacles)
+        });
+    }
+
+    commands.remove_resource::<SavedGame>();
+}
+
+fn resume_timestep_system(
+    mut commands: Commands,
+    resume_timestep: Res<ResumeTimestep>,
+    game_tick: Res<GameTick>,
+    fixed_timesteps: Option<ResMut<FixedTimesteps>>,
+) {
+    // premier déplacement effectué : les effets de vitesse reprennent la main sur le pas
+    if game_tick.0 != resume_timestep.tick {
+        commands.remove_resource::<ResumeTimestep>();
+        return;
+    }
+
+    if let Some(snake_move_time) = fixed_timesteps.and_then(|fixed_timesteps| fixed_timesteps.into_inner().get_mut("snake_move_time")) {
+        snake_move_time.step = resume_timestep.step;
+    }
+}
+
+fn restore_obstacles_system(
+    mut commands: Commands,
+    arena_size: Res<ArenaSize>,
+    saved_obstacles: Res<SavedObstacles>,
+    mut moving_obstacle_query: Query<(&mut MovingObstacle, &mut Position, &mut Transform)>,
+    mut timed_obstacle_query: Query<(Entity, &mut TimedObstacle, &Position, &mut Visibility), Without<MovingObstacle>>,
+) {
+    if moving_obstacle_query.is_empty() && timed_obstacle_query.is_empty() {
+        return;
+    }
+
+    for (mut obstacle, mut position, mut transform) in moving_obstacle_query.iter_mut() {
+        if let Some(saved_obstacle) = saved_obstacles.moving.iter().find(|saved_obstacle| saved_obstacle.start == obstacle.path[0]) {
+            obstacle.index = saved_obstacle.index.min(obstacle.path.len() - 1);
+            obstacle.forward = saved_obstacle.forward;
+
+            *position = obstacle.path[obstacle.index];
+            transform.translation.x = convert(position.x as f32, arena_size.px_width, arena_size.tile_width as f32);
+            transform.translation.y = convert(position.y as f32, arena_size.px_height, arena_size.tile_height as f32);
+        }
+    }
+
+    for (entity, mut obstacle, position, mut visibility) in timed_obstacle_query.iter_mut() {
+        if let Some(saved_obstacle) = saved_obstacles.timed.iter().find(|saved_obstacle| saved_obstacle.position == *position) {
+            obstacle.active = saved_obstacle.active;
+
+            visibility.is_visible = saved_obstacle.active;
+            if saved_obstacle.active {
+                commands.entity(entity).insert(Collision);
+            }
+        }
+    }
+
+    commands.remove_resource::<SavedObstacles>();
+}
+
+fn cleanup_save_system(mut commands: Commands) {
+    commands.remove_resource::<SavedGame>();
+    commands.remove_resource::<SavedObstacles>();
+    commands.remove_resource::<ResumeTimestep>();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use bevy::{time::TimePlugin, diagnostic::DiagnosticsPlugin};
+    use rand::{Rng, SeedableRng};
+
+    use crate::{add_headless_plugins, main_menu::MainMenuPlugin};
+    use super::*;
+    use super::super::{GamePlugin, debug::DebugPlugin, settings::Settings};
+
+    /// Partie sans obstacle : le serpent au milieu de l'arène, une nourriture et une console utilisée.
+    fn saved_game() -> SavedGame {
+        SavedGame {
+            version: SAVED_GAME_VERSION,
+            game_type: GameType { seed: 3, ..Default::default() },
+            score: 12,
+            game_tick: 40,
+            next_move_in: Duration::from_nanos(61_234_567),
+            player: SavedSnake {
+                position: Position { x: 10, y: 10 },
+                direction: Direction::RIGHT,
+                growth: 0,
+                body: vec![Position { x: 8, y: 10 }, Position { x: 9, y: 10 }],
+                bonus: None
+            },
+            enemies: Vec::new(),
+            missing_enemies: 0,
+            enemy_spawn_elapsed: Duration::from_nanos(1_500_000_001),
+            enemy_rng: ChaCha8Rng::seed_from_u64(1),
+            foods: vec![SavedFood { kind: 0, position: Position { x: 4, y: 15 }, elapsed: Duration::from_nanos(2_250_000_003) }],
+            food_spawn_elapsed: vec![Duration::from_nanos(700_000_009)],
+            food_rng: ChaCha8Rng::seed_from_u64(2),
+            active_effects: Vec::new(),
+            crossing_obstacles: None,
+            moving_obstacles: Vec::new(),
+            timed_obstacles: Vec::new(),
+            combo: Combo::default(),
+            run_stats: RunStats::default(),
+            console_used: true
+        }
+    }
+
+    /// Reprend la partie comme le bouton « Continuer », sans fenêtre ; `Simulation` évite d'écrire dans `SAVE_DIRECTORY`.
+    fn resume(saved_game: SavedGame) -> App {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins.build().disable::<TimePlugin>())
+            .add_plugin(DiagnosticsPlugin)
+            .init_resource::<Time>();
+        add_headless_plugins(&mut app);
+        app
+            .insert_resource(Simulation)
+            .add_state(AppState::MainMenu)
+            .add_plugin(GamePlugin)
+            .add_plugin(MainMenuPlugin)
+            .add_plugin(DebugPlugin)
+            .insert_resource(Settings::default())
+            .insert_resource(saved_game.game_type())
+            .insert_resource(saved_game);
+
+        let mut now = Instant::now();
+        app.world.resource_mut::<Time>().update_with_instant(now);
+        app.update();
+        app.world.resource_mut::<State<AppState>>().set(AppState::InGame).unwrap();
+        for _ in 0..3 {
+            now += Duration::from_millis(16);
+            app.world.resource_mut::<Time>().update_with_instant(now);
+            app.update();
+        }
+        app
+    }
+
+    fn encode(saved_game: &SavedGame) -> String {
+        ron::ser::to_string_pretty(saved_game, ron::ser::PrettyConfig::default()).unwrap()
+    }
+
+    /// Les graines tirées en cours de partie et les temps à la nanoseconde doivent revenir à l'identique.
+    #[test]
+    fn saved_game_round_trip() {
+        let mut saved_game = saved_game();
+        saved_game.food_rng.gen::<u64>();
+        saved_game.player.bonus = Some(SavedBonus {
+            life_cycle: 2,
+            life_elapsed: Duration::from_nanos(333_333_337),
+            color_period: Duration::from_millis(100),
+            color_elapsed: Duration::from_nanos(12_345_679),
+            index_color: 3
+        });
+        saved_game.crossing_obstacles = Some(SavedCrossing {
+            blink_elapsed: Some(Duration::from_nanos(250_000_011)),
+            lighten: true,
+            in_wall: false
+        });
+
+        let decoded = SavedGame::decode(&encode(&saved_game)).unwrap();
+        assert_eq!(decoded.score, saved_game.score);
+        assert_eq!(decoded.game_tick, saved_game.game_tick);
+        assert_eq!(decoded.game_type.seed, saved_game.game_type.seed);
+        assert_eq!(decoded.next_move_in, saved_game.next_move_in);
+        assert_eq!(decoded.enemy_spawn_elapsed, saved_game.enemy_spawn_elapsed);
+        assert_eq!(decoded.food_spawn_elapsed, saved_game.food_spawn_elapsed);
+        assert!(decoded.food_rng == saved_game.food_rng);
+        assert!(decoded.enemy_rng == saved_game.enemy_rng);
+        assert_eq!(decoded.food_rng.clone().gen::<u64>(), saved_game.food_rng.clone().gen::<u64>());
+        assert!(decoded.player.body == saved_game.player.body);
+        assert!(decoded.foods[0].position == saved_game.foods[0].position);
+        assert_eq!(decoded.foods[0].elapsed, saved_game.foods[0].elapsed);
+
+        let bonus = decoded.player.bonus.unwrap();
+        assert_eq!((bonus.life_cycle, bonus.index_color), (2, 3));
+        assert_eq!(bonus.life_elapsed, Duration::from_nanos(333_333_337));
+        assert_eq!(bonus.color_elapsed, Duration::from_nanos(12_345_679));
+        assert_eq!(decoded.crossing_obstacles.unwrap().blink_elapsed, Some(Duration::from_nanos(250_000_011)));
+        assert!(decoded.console_used);
+    }
+
+    #[test]
+    fn other_version_is_not_decoded() {
+        let content = encode(&saved_game()).replacen(
+            &format!("version: {}", SAVED_GAME_VERSION),
+            &format!("version: {}", SAVED_GAME_VERSION - 1),
+            1
+        );
+        let error = SavedGame::decode(&content).err().unwrap();
+        assert_eq!(error, format!("version {} au lieu de {}", SAVED_GAME_VERSION - 1, SAVED_GAME_VERSION));
+    }
+
+    #[test]
+    fn resumed_game_keeps_console_use() {
+        let app = resume(saved_game());
+
+        assert!(!app.world.contains_resource::<SavedGame>(), "partie non restaurée");
+        assert_eq!(app.world.resource::<Score>().0, 12);
+        assert!(app.world.resource::<DebugConsole>().used);
+    }
+}
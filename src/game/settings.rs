@@ -0,0 +1,160 @@
+use std::fs;
+
+use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use super::SAVE_DIRECTORY;
+
+/// Options du joueur, sauvegardées dans `SAVE_DIRECTORY`.
+/// Les options absentes d'un ancien fichier prennent leur valeur par défaut.
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Déplacement fluide des serpents entre deux cases.
+    pub smooth_movement: bool,
+    /// Nom du thème graphique, parmi ceux de `assets/themes.ron`.
+    pub theme: String,
+    pub palette_mode: PaletteMode,
+    /// Icône propre à chaque nourriture, même avec un thème sans atlas.
+    pub food_shapes: bool,
+    /// Ralentit les changements de couleur rapides (bonus arc-en-ciel).
+    pub reduce_flashing: bool,
+    pub text_size: TextSize,
+    /// Particules, tremblement de l'écran et animations des nourritures, sans effet sur le jeu.
+    pub visual_effects: bool,
+    pub food_indicator: FoodIndicatorStyle,
+    /// Fantôme de la meilleure partie en solo de même mode et de même graine.
+    pub ghost_race: bool,
+    /// Seule la première partie du défi du jour compte dans l'historique.
+    pub daily_first_attempt_only: bool,
+    /// Adresse (`hôte:port`) du serveur de classement du réseau local, sans envoi des scores si absente.
+    pub leaderboard_server: Option<String>,
+    /// Volumes entre 0 et 1.
+    pub music_volume: f32,
+    pub effects_volume: f32
+}
+
+/// Palette utilisée à la place de celle du thème.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaletteMode {
+    Standard,
+    Colorblind,
+    HighContrast
+}
+
+impl PaletteMode {
+    pub const ALL: [PaletteMode; 3] = [PaletteMode::Standard, PaletteMode::Colorblind, PaletteMode::HighContrast];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PaletteMode::Standard => "Standard",
+            PaletteMode::Colorblind => "Daltonien",
+            PaletteMode::HighContrast => "Contraste élevé"
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextSize {
+    Normal,
+    Large,
+    ExtraLarge
+}
+
+impl TextSize {
+    pub const ALL: [TextSize; 3] = [TextSize::Normal, TextSize::Large, TextSize::ExtraLarge];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TextSize::Normal => "Normale",
+            TextSize::Large => "Grande",
+            TextSize::ExtraLarge => "Très grande"
+        }
+    }
+
+    /// Coefficient appliqué à la taille de police de tous les textes.
+    pub fn scale(&self) -> f32 {
+        match self {
+            TextSize::Normal => 1.,
+            TextSize::Large => 1.2,
+            TextSize::ExtraLarge => 1.4
+        }
+    }
+}
+
+/// Affichage de la durée de vie restante de chaque nourriture, avec ses points actuels.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FoodIndicatorStyle {
+    Hidden,
+    /// Barre qui raccourcit sous la nourriture.
+    Bar,
+    /// Halo qui se resserre autour de la nourriture.
+    Halo,
+    /// Secondes restantes.
+    Countdown
+}
+
+impl FoodIndicatorStyle {
+    pub const ALL: [FoodIndicatorStyle; 4] = [FoodIndicatorStyle::Hidden, FoodIndicatorStyle::Bar, FoodIndicatorStyle::Halo, FoodIndicatorStyle::Countdown];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FoodIndicatorStyle::Hidden => "Aucune",
+            FoodIndicatorStyle::Bar => "Barre",
+            FoodIndicatorStyle::Halo => "Halo",
+            FoodIndicatorStyle::Countdown => "Compte à rebours"
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            smooth_movement: true,
+            theme: "Plat".to_owned(),
+            palette_mode: PaletteMode::Standard,
+            food_shapes: false,
+            reduce_flashing: false,
+            text_size: TextSize::Normal,
+            visual_effects: true,
+            food_indicator: FoodIndicatorStyle::Bar,
+            ghost_race: true,
+            daily_first_attempt_only: false,
+            leaderboard_server: None,
+            music_volume: 0.5,
+            effects_volume: 0.7
+        }
+    }
+}
+
+impl Settings {
+    pub fn path() -> String {
+        format!("{}/settings.ron", SAVE_DIRECTORY)
+    }
+
+    pub fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| ron::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let saved = fs::create_dir_all(SAVE_DIRECTORY)
+            .ok()
+            .and_then(|_| ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).ok())
+            .and_then(|content| fs::write(Self::path(), content).ok());
+
+        if saved.is_none() {
+            println!("impossible de sauvegarder les options dans {}", Self::path());
+        }
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Settings::load());
+    }
+}
@@ -0,0 +1,79 @@
+use std::time::{Duration, Instant};
+
+use bevy::{prelude::*, time::TimePlugin, input::{keyboard::KeyboardInput, ButtonState}};
+
+use crate::{AppState, add_headless_plugins, main_menu::{MainMenuPlugin, sub_menu::GameType}};
+
+use super::{GamePlugin, Score, SNAKE_MOVE_TIME, settings::Settings, stats::{RunStats, GameOutcome}, replay::{RunRecording, InputLog}};
+
+/// Présente pendant une partie simulée : rien n'est écrit dans `SAVE_DIRECTORY`.
+#[derive(Resource)]
+pub struct Simulation;
+
+pub struct SimulationResult {
+    pub score: u32,
+    /// `None` si la partie n'est pas finie à la fin du journal.
+    pub outcome: Option<GameOutcome>,
+    /// Enregistrement de la partie simulée, à comparer avec celui du joueur.
+    pub run_recording: RunRecording
+}
+
+/// Rejoue une partie sans fenêtre à partir de son journal de touches, image par image avec la même durée,
+/// pour en retrouver le score et l'issue selon les règles du jeu.
+pub fn simulate(game_type: &GameType, input_log: &InputLog) -> SimulationResult {
+    let mut app = App::new();
+    app
+        .add_plugins(MinimalPlugins.build().disable::<TimePlugin>())
+        .init_resource::<Time>();
+    add_headless_plugins(&mut app);
+    app
+        .insert_resource(game_type.clone())
+        // avant `GamePlugin`, qui n'ajoute alors ni le son, ni les effets visuels, ni les systèmes du thème
+        .insert_resource(Simulation)
+        .add_state(AppState::MainMenu)
+        .add_plugin(GamePlugin)
+        .add_plugin(MainMenuPlugin)
+        // les réglages du joueur ne changent que l'affichage
+        .insert_resource(Settings::default());
+
+    let mut now = Instant::now();
+    app.world.resource_mut::<Time>().update_with_instant(now);
+    app.update();
+
+    // une image dans le menu amène le pas de temps fixe à l'avance qu'il avait à l'entrée dans la partie
+    let first_delta = input_log.frames.first().map_or(0, |logged_frame| logged_frame.delta) as i128;
+    let step = Duration::from_millis(SNAKE_MOVE_TIME).as_nanos() as i128;
+    now += Duration::from_nanos((input_log.start_accumulator as i128 - first_delta).rem_euclid(step) as u64);
+    app.world.resource_mut::<Time>().update_with_instant(now);
+    app.update();
+
+    app.world.resource_mut::<State<AppState>>().set(AppState::InGame).expect("Couldn't switch state to InGame");
+
+    // la première image est celle de l'entrée dans `AppState::InGame`
+    for logged_frame in &input_log.frames {
+        let mut keyboard_events = app.world.resource_mut::<Events<KeyboardInput>>();
+        for (keys, state) in [(&logged_frame.pressed, ButtonState::Pressed), (&logged_frame.released, ButtonState::Released)] {
+            for key in keys {
+                keyboard_events.send(KeyboardInput { scan_code: 0, key_code: Some(key.key_code()), state });
+            }
+        }
+
+        now += Duration::from_nanos(logged_frame.delta);
+        app.world.resource_mut::<Time>().update_with_instant(now);
+        app.update();
+
+        if !matches!(app.world.resource::<State<AppState>>().current(), AppState::InGame | AppState::Pause) {
+            break;
+        }
+    }
+
+    // l'issue de la partie est connue au plus tard une image après la sortie de `AppState::InGame`
+    app.world.resource_mut::<Time>().update_with_instant(now);
+    app.update();
+
+    SimulationResult {
+        score: app.world.get_resource::<Score>().map_or(0, |score| score.0),
+        outcome: app.world.resource::<RunStats>().outcome,
+        run_recording: app.world.remove_resource::<RunRecording>().unwrap_or_default()
+    }
+}
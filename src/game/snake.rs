@@ -1,11 +1,12 @@
 
 use std::collections::VecDeque;
 
-use bevy::{prelude::{Plugin, App, StartupStage, Commands, Color, Vec2, Res, Input, KeyCode, Query, With, Mut, Transform, Vec3, SystemSet, Entity}, sprite::{SpriteBundle, Sprite}};
+use bevy::{prelude::{Plugin, App, StartupStage, Commands, Color, Vec2, Res, Input, KeyCode, Query, With, Without, Mut, Transform, Vec3, SystemSet, Entity, EventWriter}, sprite::{SpriteBundle, Sprite}};
 use uuid::Uuid;
 use super::AppState;
+use crate::main_menu::sub_menu::{GameType, Controller};
 
-use super::{components::{SnakeHead, Velocity, Direction, Position, Size, SnakeBody}, SNAKE_HEAD_COLOR, SNAKE_BODY_COLOR, SNAKE_BODY_SIZE, SNAKE_HEAD_SIZE};
+use super::{GameEvent, components::{SnakeHead, Velocity, Direction, Position, Size, SnakeBody, Player, Bot}, effect::ActiveEffects, theme::Palette, SNAKE_BODY_SIZE, SNAKE_HEAD_SIZE};
 
 pub struct SnakePlugin;
 
@@ -18,6 +19,7 @@ impl Plugin for SnakePlugin {
         )
         .add_system_set(
             SystemSet::on_update(AppState::InGame)
+            .label("game_input")
             .with_system(keyboard_event_system)
         )
         .add_system_set(
@@ -27,7 +29,7 @@ impl Plugin for SnakePlugin {
     }
 }
 
-fn snake_spawn_system(mut commands: Commands, /*win_size: Res<WinSize>*/) {
+fn snake_spawn_system(mut commands: Commands, palette: Res<Palette>, game_type: Res<GameType>, /*win_size: Res<WinSize>*/) {
     let (init_x, init_y) = (3, 3);
     let init_direction = Direction::RIGHT;
 
@@ -40,7 +42,7 @@ fn snake_spawn_system(mut commands: Commands, /*win_size: Res<WinSize>*/) {
 
     commands.spawn(SpriteBundle {
         sprite: Sprite {
-            color: SNAKE_BODY_COLOR,
+            color: palette.snake_body,
             ..Default::default()
         },
         transform: Transform {
@@ -54,9 +56,9 @@ fn snake_spawn_system(mut commands: Commands, /*win_size: Res<WinSize>*/) {
     .insert(Size::square(SNAKE_BODY_SIZE));
 
     // add snake_head
-    commands.spawn(SpriteBundle {
+    let snake_head = commands.spawn(SpriteBundle {
         sprite: Sprite {
-            color: SNAKE_HEAD_COLOR,
+            color: palette.snake_head,
             ..Default::default()
         },
         transform: Transform {
@@ -67,13 +69,20 @@ fn snake_spawn_system(mut commands: Commands, /*win_size: Res<WinSize>*/) {
     })
     .insert(SnakeHead { 
         moved: true, 
-        ate: false,
+        growth: 0,
         invincible: false,
-        body_parts: VecDeque::from([snake_body])
+        body_parts: VecDeque::from([snake_body]),
+        previous_position: None
     })
+    .insert(Player)
     .insert(init_direction)
     .insert(Position {x: init_x, y: init_y})
-    .insert(Size::square(SNAKE_HEAD_SIZE));
+    .insert(Size::square(SNAKE_HEAD_SIZE))
+    .id();
+
+    if game_type.controller == Controller::Bot {
+        commands.entity(snake_head).insert(Bot);
+    }
 
     // // add snake_body
     // commands.spawn(SpriteBundle {
@@ -92,34 +101,45 @@ fn snake_spawn_system(mut commands: Commands, /*win_size: Res<WinSize>*/) {
     // .insert(Size::square(0.6));
 }
 
-fn keyboard_event_system(kb: Res<Input<KeyCode>>, mut query: Query<(&mut Direction, &mut SnakeHead), With<SnakeHead>>) {
+#[allow(clippy::type_complexity)]
+fn keyboard_event_system(
+    kb: Res<Input<KeyCode>>,
+    active_effects: Option<Res<ActiveEffects>>,
+    mut query: Query<(&mut Direction, &mut SnakeHead), (With<Player>, Without<Bot>)>,
+    mut game_events: EventWriter<GameEvent>,
+) {
     if kb.any_just_pressed(vec![KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right]) {
         if let Ok((mut direction, mut snake_head)) = query.get_single_mut() {
             if !snake_head.moved {
                 return;
             }  
-            edit_snake_direction(kb, direction);
+            let reversed = active_effects.is_some_and(|active_effects| active_effects.reverse_controls());
+            if edit_snake_direction(kb, direction, reversed) {
+                game_events.send(GameEvent::Turned);
+            }
             snake_head.moved = false;
         }
     }
 }
 
-fn edit_snake_direction(kb: Res<Input<KeyCode>>, mut direction: Mut<Direction>) {
-    match *direction {
-        Direction::UP | Direction::DOWN => {
-            if kb.just_pressed(KeyCode::Left) {
-                *direction = Direction::LEFT;
-            } else if kb.just_pressed(KeyCode::Right) {
-                *direction = Direction::RIGHT;
-            }
+/// Renvoie vrai si la direction a changé.
+fn edit_snake_direction(kb: Res<Input<KeyCode>>, mut direction: Mut<Direction>, reversed: bool) -> bool {
+    // commandes inversées : chaque flèche envoie dans la direction opposée
+    let pressed = |key_code: KeyCode, key_direction: Direction| {
+        kb.just_pressed(key_code).then_some(if reversed { key_direction.opposite() } else { key_direction })
+    };
+
+    let new_direction = match *direction {
+        Direction::UP | Direction::DOWN => pressed(KeyCode::Left, Direction::LEFT).or_else(|| pressed(KeyCode::Right, Direction::RIGHT)),
+        Direction::LEFT | Direction::RIGHT => pressed(KeyCode::Up, Direction::UP).or_else(|| pressed(KeyCode::Down, Direction::DOWN))
+    };
+
+    match new_direction {
+        Some(new_direction) => {
+            *direction = new_direction;
+            true
         },
-        Direction::LEFT | Direction::RIGHT => {
-            if kb.just_pressed(KeyCode::Up) {
-                *direction = Direction::UP;
-            } else if kb.just_pressed(KeyCode::Down) {
-                *direction = Direction::DOWN;
-            }
-        }
+        None => false
     }
 }
 
@@ -128,10 +148,10 @@ fn cleanup_snake_system(
     mut snake_head_query: Query<Entity, With<SnakeHead>>,
 	mut snake_body_query: Query<Entity, With<SnakeBody>>,
 ) {
-    if let Ok(entity) = snake_head_query.get_single_mut() {
+    for entity in snake_head_query.iter_mut() {
         commands.entity(entity).despawn();
         // println!("snake_head_query");
-    };
+    }
 
     for entity in snake_body_query.iter_mut() {
         commands.entity(entity).despawn();
@@ -0,0 +1,281 @@
+use std::{fs, collections::HashMap, f32::consts::TAU, time::Duration};
+
+use bevy::{prelude::*, audio::{AudioOutput, AudioSink, Decodable, Source, play_queued_audio_system}, reflect::TypeUuid};
+use serde::Deserialize;
+
+use super::{AppState, GameEvent, SOUND_CATALOGUE_PATH, SOUND_SAMPLE_RATE, SOUND_FADE_DURATION, settings::Settings, food::FoodCatalogue};
+
+// region:    --- Tone
+
+#[derive(Clone, Copy, Deserialize)]
+pub enum Waveform {
+    Sine,
+    Square,
+    Triangle,
+    /// Bruit blanc, renouvelé à la fréquence de la note.
+    Noise
+}
+
+/// Son synthétisé à partir d'une suite de notes : le jeu n'embarque aucun fichier audio.
+#[derive(Clone, Deserialize, TypeUuid)]
+#[uuid = "5d3a8f0e-7c41-4b8e-9a6f-2e1b7c9d4f03"]
+pub struct Tone {
+    waveform: Waveform,
+    /// (fréquence en Hz, durée en secondes) ; une fréquence nulle est un silence.
+    notes: Vec<(f32, f32)>,
+    #[serde(default = "default_gain")]
+    gain: f32
+}
+
+fn default_gain() -> f32 {
+    0.3
+}
+
+impl Decodable for Tone {
+    type Decoder = ToneDecoder;
+    type DecoderItem = f32;
+
+    fn decoder(&self) -> Self::Decoder {
+        ToneDecoder {
+            tone: self.clone(),
+            note: 0,
+            sample: 0,
+            phase: 0.,
+            noise_state: 0x9e37_79b9,
+            noise_value: 0.
+        }
+    }
+}
+
+pub struct ToneDecoder {
+    tone: Tone,
+    note: usize,
+    /// Échantillon courant dans la note.
+    sample: u32,
+    phase: f32,
+    noise_state: u32,
+    noise_value: f32
+}
+
+impl ToneDecoder {
+    fn next_noise(&mut self) -> f32 {
+        // xorshift : un bruit reproductible suffit
+        self.noise_state ^= self.noise_state << 13;
+        self.noise_state ^= self.noise_state >> 17;
+        self.noise_state ^= self.noise_state << 5;
+        self.noise_state as f32 / u32::MAX as f32 * 2. - 1.
+    }
+}
+
+impl Iterator for ToneDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let (frequency, duration) = *self.tone.notes.get(self.note)?;
+        if self.sample as f32 >= duration * SOUND_SAMPLE_RATE as f32 {
+            self.note += 1;
+            self.sample = 0;
+            return self.next();
+        }
+
+        let time = self.sample as f32 / SOUND_SAMPLE_RATE as f32;
+        // fondu au début et à la fin de chaque note pour éviter les claquements
+        let envelope = (time / SOUND_FADE_DURATION).min((duration - time) / SOUND_FADE_DURATION).clamp(0., 1.);
+        let previous_phase = self.phase;
+        self.phase = (self.phase + frequency / SOUND_SAMPLE_RATE as f32).fract();
+        self.sample += 1;
+
+        if frequency <= 0. {
+            return Some(0.);
+        }
+
+        let value = match self.tone.waveform {
+            Waveform::Sine => (self.phase * TAU).sin(),
+            Waveform::Square => if self.phase < 0.5 { 1. } else { -1. },
+            Waveform::Triangle => 4. * (self.phase - 0.5).abs() - 1.,
+            Waveform::Noise => {
+                if self.phase < previous_phase {
+                    self.noise_value = self.next_noise();
+                }
+                self.noise_value
+            }
+        };
+
+        Some(value * envelope * self.tone.gain)
+    }
+}
+
+impl Source for ToneDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SOUND_SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(self.tone.notes.iter().map(|(_, duration)| duration).sum()))
+    }
+}
+
+// endregion: --- Tone
+
+// region:    --- Sound Catalogue
+
+/// Sons du jeu, chargés depuis `assets/sounds.ron`.
+#[derive(Deserialize)]
+struct SoundCatalogue {
+    effects: HashMap<String, Tone>,
+    /// Son joué en mangeant chaque nourriture, par nom ; "eat" sinon.
+    foods: HashMap<String, Tone>,
+    /// Musique jouée en boucle, par écran.
+    music: HashMap<String, Tone>
+}
+
+fn load_sound_catalogue() -> SoundCatalogue {
+    let embedded = include_str!("../../assets/sounds.ron");
+    let content = fs::read_to_string(SOUND_CATALOGUE_PATH).unwrap_or_else(|_| embedded.to_owned());
+
+    ron::from_str(&content).unwrap_or_else(|error| {
+        println!("catalogue de sons invalide ({}), utilisation du catalogue par défaut", error);
+        ron::from_str(embedded).expect("catalogue de sons par défaut invalide")
+    })
+}
+
+#[derive(Resource)]
+struct Sounds {
+    effects: HashMap<String, Handle<Tone>>,
+    foods: HashMap<String, Handle<Tone>>,
+    music: HashMap<String, Handle<Tone>>
+}
+
+// endregion: --- Sound Catalogue
+
+/// Sans périphérique de sortie (serveur, tests), bevy ne joue rien et les sons sont ignorés.
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_non_send_resource::<AudioOutput<Tone>>()
+            .add_asset::<Tone>()
+            .add_asset::<AudioSink>()
+            .init_resource::<Audio<Tone>>()
+            .add_system_to_stage(CoreStage::PostUpdate, play_queued_audio_system::<Tone>);
+
+        let catalogue = load_sound_catalogue();
+        let mut tones = app.world.resource_mut::<Assets<Tone>>();
+        let mut add_all = |sounds: HashMap<String, Tone>| -> HashMap<String, Handle<Tone>> {
+            sounds.into_iter().map(|(name, tone)| (name, tones.add(tone))).collect()
+        };
+        let sounds = Sounds {
+            effects: add_all(catalogue.effects),
+            foods: add_all(catalogue.foods),
+            music: add_all(catalogue.music)
+        };
+
+        app
+            .insert_resource(sounds)
+            .add_system(game_sound_system)
+            .add_system(menu_sound_system)
+            .add_system(music_system);
+    }
+}
+
+fn play_effect(audio: &Audio<Tone>, settings: &Settings, sound: Option<&Handle<Tone>>) {
+    if let Some(sound) = sound {
+        audio.play_with_settings(sound.clone(), PlaybackSettings::ONCE.with_volume(settings.effects_volume));
+    }
+}
+
+fn game_sound_system(
+    mut game_events: EventReader<GameEvent>,
+    food_catalogue: Res<FoodCatalogue>,
+    sounds: Res<Sounds>,
+    audio: Res<Audio<Tone>>,
+    settings: Res<Settings>,
+) {
+    for game_event in game_events.iter() {
+        let sound = match game_event {
+            GameEvent::FoodEaten { kind, .. } => sounds.foods.get(&food_catalogue.kinds[*kind].name).or_else(|| sounds.effects.get("eat")),
+            GameEvent::Turned => sounds.effects.get("turn"),
+            GameEvent::WallCrossed => sounds.effects.get("wall_crossed"),
+            GameEvent::BonusStarted => sounds.effects.get("bonus_start"),
+            GameEvent::BonusWarning => sounds.effects.get("bonus_warning"),
+            GameEvent::BonusEnded => sounds.effects.get("bonus_end"),
+            GameEvent::ArenaFilled => sounds.effects.get("victory"),
+            GameEvent::Died { .. } => sounds.effects.get("death")
+        };
+        play_effect(&audio, &settings, sound);
+    }
+}
+
+fn menu_sound_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+    sounds: Res<Sounds>,
+    audio: Res<Audio<Tone>>,
+    settings: Res<Settings>,
+) {
+    for interaction in interaction_query.iter() {
+        let sound = match interaction {
+            Interaction::Hovered => sounds.effects.get("menu_hover"),
+            Interaction::Clicked => sounds.effects.get("menu_click"),
+            Interaction::None => None
+        };
+        play_effect(&audio, &settings, sound);
+    }
+}
+
+fn music_track(state: &AppState) -> &'static str {
+    match state {
+        AppState::InGame | AppState::Pause => "game",
+        AppState::GameOver(_) | AppState::DeathReplay | AppState::Replay => "game_over",
+        _ => "menu"
+    }
+}
+
+/// Morceau en cours ; le lien fort garde la lecture sous contrôle.
+#[derive(Default)]
+struct Music {
+    track: Option<&'static str>,
+    sink: Handle<AudioSink>
+}
+
+/// Change de morceau avec l'écran, suspend celui de la partie pendant la pause
+/// et applique le volume choisi dans les options.
+fn music_system(
+    state: Res<State<AppState>>,
+    settings: Res<Settings>,
+    sounds: Res<Sounds>,
+    audio: Res<Audio<Tone>>,
+    sinks: Res<Assets<AudioSink>>,
+    mut music: Local<Music>,
+) {
+    let track = music_track(state.current());
+    if music.track != Some(track) {
+        if let Some(sink) = sinks.get(&music.sink) {
+            sink.stop();
+        }
+        music.sink = match sounds.music.get(track) {
+            Some(tone) => sinks.get_handle(audio.play_with_settings(tone.clone(), PlaybackSettings::LOOP.with_volume(settings.music_volume))),
+            None => Handle::default()
+        };
+        music.track = Some(track);
+    }
+
+    if let Some(sink) = sinks.get(&music.sink) {
+        if settings.is_changed() {
+            sink.set_volume(settings.music_volume);
+        }
+
+        let paused = *state.current() == AppState::Pause;
+        if paused != sink.is_paused() {
+            if paused { sink.pause() } else { sink.play() }
+        }
+    }
+}
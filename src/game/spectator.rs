@@ -0,0 +1,329 @@
+use bevy::{prelude::*, input::mouse::{MouseMotion, MouseWheel}};
+use iyes_loopless::prelude::{IntoConditionalSystem, ConditionSet};
+
+use crate::main_menu::sub_menu::{GameType, Controller};
+
+use super::{AppState, ArenaSize, PositionsAvailable, convert, replay::RunRecording, components::{SnakeHead, Food, FoodTimer, Player, PlannedPath, Position}, SPECTATOR_ZOOM_STEP, SPECTATOR_MIN_SCALE, SPECTATOR_MAX_SCALE, SPECTATOR_FONT_SIZE, SPECTATOR_OVERLAY_Z, WINDOW_HEIGHT, UPPER_EDGE};
+
+const OCCUPIED_COLOR: Color = Color::rgba(1., 0.2, 0.2, 0.35);
+const PLANNED_PATH_COLOR: Color = Color::rgba(1., 0.9, 0.2, 0.5);
+const PLANNED_PATH_SIZE: f32 = 0.4;
+
+// region:    --- Resource
+
+/// Caméra libre et calques du mode spectateur, pour les parties jouées par l'ordinateur et les replays.
+/// Molette : zoom, clic gauche maintenu : déplacement, Origine : recentrer, Tab : suivre le serpent suivant,
+/// F5 : chemin prévu par l'ordinateur, F6 : cases occupées, F7 : minuteurs des nourritures, F8 : coordonnées.
+/// Les replays n'ont que leurs images : seules la caméra et les coordonnées y sont disponibles.
+#[derive(Resource, Default)]
+struct SpectatorView {
+    /// Tête du serpent suivi par la caméra.
+    followed: Option<Entity>,
+    planned_paths: bool,
+    occupancy: bool,
+    food_timers: bool,
+    coordinates: bool
+}
+
+// endregion: --- Resource
+
+// region:    --- Component
+
+#[derive(Component)]
+enum SpectatorOverlay {
+    PlannedPath,
+    Occupancy,
+    FoodTimer,
+    Coordinates
+}
+
+// endregion: --- Component
+
+pub struct SpectatorPlugin;
+
+impl Plugin for SpectatorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<SpectatorView>()
+        .add_system_set(
+            ConditionSet::new()
+            .run_if(spectating)
+            .with_system(spectator_controls_system.into_conditional().label("spectator_controls"))
+            .with_system(spectator_follow_system.into_conditional().after("spectator_controls"))
+            .with_system(coordinates_overlay_system.into_conditional().after("spectator_controls"))
+            .into()
+        )
+        .add_system_set(
+            ConditionSet::new()
+            .run_if(spectating)
+            .run_if_resource_exists::<ArenaSize>()
+            .after("spectator_controls")
+            .with_system(planned_path_overlay_system)
+            .with_system(occupancy_overlay_system)
+            .with_system(food_timer_overlay_system)
+            .into()
+        )
+        .add_system_set(SystemSet::on_exit(AppState::InGame).with_system(spectator_cleanup_system))
+        .add_system_set(SystemSet::on_exit(AppState::Replay).with_system(spectator_cleanup_system))
+        .add_system_set(SystemSet::on_exit(AppState::DeathReplay).with_system(spectator_cleanup_system));
+    }
+}
+
+/// Partie jouée par l'ordinateur, en cours ou en pause, ou replay.
+fn spectating(app_state: Res<State<AppState>>, game_type: Option<Res<GameType>>) -> bool {
+    match app_state.current() {
+        AppState::Replay | AppState::DeathReplay => true,
+        AppState::InGame | AppState::Pause => game_type.is_some_and(|game_type| game_type.controller == Controller::Bot),
+        _ => false
+    }
+}
+
+/// Taille de l'arène en cases et en pixels : celle de la partie en cours, sinon celle du replay.
+fn arena_layout(arena_size: Option<&ArenaSize>, run_recording: &RunRecording) -> (f32, f32, f32, f32) {
+    match arena_size {
+        Some(arena_size) => (arena_size.tile_width as f32, arena_size.tile_height as f32, arena_size.px_width, arena_size.px_height),
+        None => (run_recording.tile_width as f32, run_recording.tile_height as f32, run_recording.px_width, run_recording.px_height)
+    }
+}
+
+fn tile_translation(arena_size: &ArenaSize, position: &Position, z: f32) -> Vec3 {
+    Vec3::new(
+        convert(position.x as f32, arena_size.px_width, arena_size.tile_width as f32),
+        convert(position.y as f32, arena_size.px_height, arena_size.tile_height as f32),
+        z
+    )
+}
+
+fn spectator_controls_system(
+    keys: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut spectator_view: ResMut<SpectatorView>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+    snake_head_query: Query<(Entity, Option<&Player>), With<SnakeHead>>,
+) {
+    let zoom: f32 = mouse_wheel_events.iter().map(|mouse_wheel| mouse_wheel.y.signum()).sum();
+    let drag: Vec2 = mouse_motion_events.iter().map(|mouse_motion| mouse_motion.delta).sum();
+    let dragging = mouse_buttons.pressed(MouseButton::Left) && drag != Vec2::ZERO;
+    if (dragging || keys.just_pressed(KeyCode::Home)) && spectator_view.followed.is_some() {
+        spectator_view.followed = None;
+    }
+
+    for (mut transform, mut projection) in camera_query.iter_mut() {
+        if zoom != 0. {
+            projection.scale = (projection.scale * SPECTATOR_ZOOM_STEP.powf(-zoom)).clamp(SPECTATOR_MIN_SCALE, SPECTATOR_MAX_SCALE);
+        }
+        // l'axe vertical de l'écran est inversé par rapport à celui du monde
+        if dragging {
+            transform.translation += Vec3::new(-drag.x, drag.y, 0.) * projection.scale;
+        }
+        if keys.just_pressed(KeyCode::Home) {
+            projection.scale = 1.;
+            transform.translation = Vec3::new(0., WINDOW_HEIGHT * UPPER_EDGE / 2., transform.translation.z);
+        }
+    }
+
+    // le joueur d'abord, puis les adversaires ; après le dernier, la caméra redevient libre
+    if keys.just_pressed(KeyCode::Tab) {
+        let mut snake_heads: Vec<(Entity, bool)> = snake_head_query.iter()
+            .map(|(entity, player)| (entity, player.is_some()))
+            .collect();
+        snake_heads.sort_by_key(|(entity, is_player)| (!is_player, *entity));
+
+        let next_index = spectator_view.followed
+            .and_then(|followed| snake_heads.iter().position(|(entity, _)| *entity == followed))
+            .map_or(0, |index| index + 1);
+        spectator_view.followed = snake_heads.get(next_index).map(|(entity, _)| *entity);
+    }
+
+    // les calques sont redessinés à chaque modification de la vue
+    if keys.just_pressed(KeyCode::F5) {
+        spectator_view.planned_paths = !spectator_view.planned_paths;
+    }
+    if keys.just_pressed(KeyCode::F6) {
+        spectator_view.occupancy = !spectator_view.occupancy;
+    }
+    if keys.just_pressed(KeyCode::F7) {
+        spectator_view.food_timers = !spectator_view.food_timers;
+    }
+    if keys.just_pressed(KeyCode::F8) {
+        spectator_view.coordinates = !spectator_view.coordinates;
+    }
+}
+
+/// Un serpent mort n'est plus suivi.
+fn spectator_follow_system(
+    mut spectator_view: ResMut<SpectatorView>,
+    snake_head_query: Query<&Transform, (With<SnakeHead>, Without<Camera2d>)>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    let Some(followed) = spectator_view.followed else {
+        return;
+    };
+
+    match snake_head_query.get(followed) {
+        Ok(snake_transform) => {
+            for mut camera_transform in camera_query.iter_mut() {
+                camera_transform.translation.x = snake_transform.translation.x;
+                camera_transform.translation.y = snake_transform.translation.y;
+            }
+        },
+        Err(_) => spectator_view.followed = None
+    }
+}
+
+fn overlay_despawn(commands: &mut Commands, overlay_query: &Query<(Entity, &SpectatorOverlay)>, matches: impl Fn(&SpectatorOverlay) -> bool) {
+    for (entity, overlay) in overlay_query.iter() {
+        if matches(overlay) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn overlay_text(asset_server: &AssetServer, label: String, translation: Vec3) -> Text2dBundle {
+    Text2dBundle {
+        text: Text::from_section(
+            label,
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: SPECTATOR_FONT_SIZE,
+                color: Color::WHITE,
+            }
+        )
+        .with_alignment(TextAlignment::CENTER),
+        transform: Transform::from_translation(translation),
+        ..Default::default()
+    }
+}
+
+fn overlay_tile(arena_size: &ArenaSize, position: &Position, color: Color, size: f32) -> SpriteBundle {
+    let tile_size = Vec2::new(arena_size.px_width / arena_size.tile_width as f32, arena_size.px_height / arena_size.tile_height as f32);
+    SpriteBundle {
+        sprite: Sprite {
+            color,
+            custom_size: Some(tile_size * size),
+            ..Default::default()
+        },
+        transform: Transform::from_translation(tile_translation(arena_size, position, SPECTATOR_OVERLAY_Z)),
+        ..Default::default()
+    }
+}
+
+/// Les coordonnées ne changent pas pendant la partie : elles ne sont créées qu'à l'activation du calque.
+fn coordinates_overlay_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    spectator_view: Res<SpectatorView>,
+    arena_size: Option<Res<ArenaSize>>,
+    run_recording: Res<RunRecording>,
+    overlay_query: Query<(Entity, &SpectatorOverlay)>,
+) {
+    let shown = overlay_query.iter().any(|(_, overlay)| matches!(overlay, SpectatorOverlay::Coordinates));
+    if !spectator_view.coordinates {
+        if shown {
+            overlay_despawn(&mut commands, &overlay_query, |overlay| matches!(overlay, SpectatorOverlay::Coordinates));
+        }
+        return;
+    }
+    if shown {
+        return;
+    }
+
+    let (tile_width, tile_height, px_width, px_height) = arena_layout(arena_size.as_deref(), &run_recording);
+    for y in 0..tile_height as i32 {
+        for x in 0..tile_width as i32 {
+            let translation = Vec3::new(convert(x as f32, px_width, tile_width), convert(y as f32, px_height, tile_height), SPECTATOR_OVERLAY_Z);
+            commands.spawn(overlay_text(&asset_server, format!("{},{}", x, y), translation))
+                .insert(SpectatorOverlay::Coordinates);
+        }
+    }
+}
+
+/// Redessiné à chaque nouveau chemin calculé, soit à chaque déplacement.
+fn planned_path_overlay_system(
+    mut commands: Commands,
+    spectator_view: Res<SpectatorView>,
+    arena_size: Res<ArenaSize>,
+    planned_path_query: Query<&PlannedPath>,
+    changed_path_query: Query<(), Changed<PlannedPath>>,
+    overlay_query: Query<(Entity, &SpectatorOverlay)>,
+) {
+    if !spectator_view.is_changed() && changed_path_query.is_empty() {
+        return;
+    }
+
+    overlay_despawn(&mut commands, &overlay_query, |overlay| matches!(overlay, SpectatorOverlay::PlannedPath));
+    if !spectator_view.planned_paths {
+        return;
+    }
+
+    for planned_path in planned_path_query.iter() {
+        for position in planned_path.0.iter() {
+            commands.spawn(overlay_tile(&arena_size, position, PLANNED_PATH_COLOR, PLANNED_PATH_SIZE))
+                .insert(SpectatorOverlay::PlannedPath);
+        }
+    }
+}
+
+/// Cases absentes de `PositionsAvailable`, où le jeu ne fait plus apparaître de nourriture.
+fn occupancy_overlay_system(
+    mut commands: Commands,
+    spectator_view: Res<SpectatorView>,
+    arena_size: Res<ArenaSize>,
+    positions_available: Option<Res<PositionsAvailable>>,
+    overlay_query: Query<(Entity, &SpectatorOverlay)>,
+) {
+    let Some(positions_available) = positions_available else {
+        return;
+    };
+    if !spectator_view.is_changed() && !positions_available.is_changed() {
+        return;
+    }
+
+    overlay_despawn(&mut commands, &overlay_query, |overlay| matches!(overlay, SpectatorOverlay::Occupancy));
+    if !spectator_view.occupancy {
+        return;
+    }
+
+    for y in 0..arena_size.tile_height as i32 {
+        for x in 0..arena_size.tile_width as i32 {
+            let position = Position { x, y };
+            if !positions_available.0.contains(&position) {
+                commands.spawn(overlay_tile(&arena_size, &position, OCCUPIED_COLOR, 1.))
+                    .insert(SpectatorOverlay::Occupancy);
+            }
+        }
+    }
+}
+
+/// Temps restant de chaque nourriture, mis à jour à chaque image.
+fn food_timer_overlay_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    spectator_view: Res<SpectatorView>,
+    arena_size: Res<ArenaSize>,
+    food_query: Query<(&Position, &FoodTimer), With<Food>>,
+    overlay_query: Query<(Entity, &SpectatorOverlay)>,
+) {
+    overlay_despawn(&mut commands, &overlay_query, |overlay| matches!(overlay, SpectatorOverlay::FoodTimer));
+    if !spectator_view.food_timers {
+        return;
+    }
+
+    for (position, food_timer) in food_query.iter() {
+        let remaining = food_timer.0.duration().as_secs_f32() - food_timer.0.elapsed_secs();
+        commands.spawn(overlay_text(&asset_server, format!("{:.1}", remaining), tile_translation(&arena_size, position, SPECTATOR_OVERLAY_Z)))
+            .insert(SpectatorOverlay::FoodTimer);
+    }
+}
+
+/// Les calques restent activés d'une partie à l'autre.
+fn spectator_cleanup_system(
+    mut commands: Commands,
+    mut spectator_view: ResMut<SpectatorView>,
+    overlay_query: Query<(Entity, &SpectatorOverlay)>,
+) {
+    overlay_despawn(&mut commands, &overlay_query, |_| true);
+    spectator_view.followed = None;
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use iyes_loopless::prelude::IntoConditionalSystem;
+use serde::{Serialize, Deserialize};
+
+use crate::main_menu::sub_menu::GameType;
+
+use super::{AppState, GameEvent, food::FoodCatalogue, components::{SnakeHead, Player, Position}};
+
+// region:    --- Resource
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DeathCause {
+    /// Collision avec son propre corps.
+    OwnBody,
+    /// Collision avec la tête ou le corps d'un adversaire.
+    Enemy,
+    Wall,
+    /// Temps écoulé dans une partie chronométrée.
+    Timeout,
+    /// Partie arrêtée avec la commande `fin` de la console de développement.
+    Console
+}
+
+impl DeathCause {
+    /// Causes comptées dans les statistiques du profil : une partie modifiée avec la console n'y est pas enregistrée.
+    pub const ALL: [DeathCause; 4] = [DeathCause::OwnBody, DeathCause::Enemy, DeathCause::Wall, DeathCause::Timeout];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeathCause::OwnBody => "Propre corps",
+            DeathCause::Enemy => "Adversaire",
+            DeathCause::Wall => "Mur",
+            DeathCause::Timeout => "Temps écoulé",
+            DeathCause::Console => "Console"
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameOutcome {
+    ArenaFilled,
+    Died(DeathCause)
+}
+
+impl GameOutcome {
+    pub fn label(&self) -> String {
+        match self {
+            GameOutcome::ArenaFilled => "arène remplie".to_owned(),
+            GameOutcome::Died(cause) => format!("mort ({})", cause.label())
+        }
+    }
+}
+
+/// Données de la partie en cours, remplies à partir des événements de jeu.
+/// Elles restent disponibles après la partie pour les succès, les profils et l'écran de fin.
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+pub struct RunStats {
+    pub wall_type: usize,
+    /// Nourritures mangées par nom dans le catalogue.
+    pub foods_eaten: BTreeMap<String, u32>,
+    /// Points gagnés par nom de nourriture.
+    pub points_by_food: BTreeMap<String, u32>,
+    /// Nourritures mangées qui ont un effet.
+    pub bonus_pickups: u32,
+    pub walls_crossed: u32,
+    pub longest_snake: u32,
+    pub play_time: f32,
+    pub outcome: Option<GameOutcome>,
+    pub death_position: Option<Position>,
+    /// Place dans le tableau des meilleurs scores du mode, connue une fois la partie enregistrée.
+    pub high_score_rank: Option<usize>,
+    /// Vrai une fois la partie ajoutée aux statistiques du profil.
+    pub recorded: bool
+}
+
+impl RunStats {
+    pub fn foods_eaten(&self, name: &str) -> u32 {
+        *self.foods_eaten.get(name).unwrap_or(&0)
+    }
+}
+
+// endregion: --- Resource
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<RunStats>()
+        .add_system_set(
+            SystemSet::on_enter(AppState::InGame)
+            .with_system(run_stats_setup_system)
+        )
+        .add_system(
+            run_stats_time_system
+                .run_in_bevy_state(AppState::InGame)
+                .after("game_spawn")
+        )
+        // les événements de fin de partie arrivent au moment du changement d'état
+        .add_system(run_stats_events_system.after("game_spawn"));
+    }
+}
+
+fn run_stats_setup_system(mut run_stats: ResMut<RunStats>, game_type: Res<GameType>) {
+    *run_stats = RunStats {
+        wall_type: game_type.wall_type,
+        longest_snake: 2,
+        ..Default::default()
+    };
+}
+
+fn run_stats_time_system(time: Res<Time>, mut run_stats: ResMut<RunStats>, snake_head_query: Query<&SnakeHead, With<Player>>) {
+    run_stats.play_time += time.delta_seconds();
+
+    if let Ok(snake_head) = snake_head_query.get_single() {
+        let snake_size = 1 + snake_head.body_parts.len() as u32;
+        if snake_size > run_stats.longest_snake {
+            run_stats.longest_snake = snake_size;
+        }
+    }
+}
+
+fn run_stats_events_system(
+    food_catalogue: Res<FoodCatalogue>,
+    mut game_events: EventReader<GameEvent>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    for game_event in game_events.iter() {
+        match game_event {
+            GameEvent::FoodEaten { kind, points, .. } => {
+                let food_kind = &food_catalogue.kinds[*kind];
+                *run_stats.foods_eaten.entry(food_kind.name.clone()).or_insert(0) += 1;
+                *run_stats.points_by_food.entry(food_kind.name.clone()).or_insert(0) += points;
+                if food_kind.effect.is_some() {
+                    run_stats.bonus_pickups += 1;
+                }
+            },
+            GameEvent::WallCrossed => run_stats.walls_crossed += 1,
+            GameEvent::Turned | GameEvent::BonusStarted | GameEvent::BonusWarning | GameEvent::BonusEnded => (),
+            GameEvent::ArenaFilled => run_stats.outcome = Some(GameOutcome::ArenaFilled),
+            GameEvent::Died { cause, position } => {
+                run_stats.outcome = Some(GameOutcome::Died(*cause));
+                run_stats.death_position = Some(*position);
+            }
+        }
+    }
+}
@@ -0,0 +1,394 @@
+use std::{fs, collections::{HashMap, HashSet}};
+
+use bevy::{prelude::*, render::texture::ImageSampler};
+use iyes_loopless::prelude::{IntoConditionalSystem, ConditionSet};
+use serde::{Deserialize, Deserializer};
+use uuid::Uuid;
+
+use super::{AppState, ArenaSize, THEME_CATALOGUE_PATH, settings::{Settings, PaletteMode}, food::FoodKind, interpolation::unwrapped_offset, components::{SnakeHead, SnakeBody, Direction, Position, Size, Wall, ExteriorWall}};
+
+// region:    --- Theme Catalogue
+
+/// Liste des thèmes graphiques, chargée depuis `assets/themes.ron`.
+#[derive(Resource, Deserialize)]
+pub struct ThemeCatalogue {
+    pub themes: Vec<Theme>,
+    /// Palettes d'accessibilité, utilisées quel que soit le thème.
+    pub colorblind: Palette,
+    pub high_contrast: Palette
+}
+
+#[derive(Deserialize)]
+pub struct Theme {
+    pub name: String,
+    pub palette: Palette,
+    /// Sans atlas, tout est dessiné en carrés de couleur.
+    #[serde(default)]
+    pub atlas: Option<ThemeAtlas>
+}
+
+/// Couleurs du thème courant, ou de la palette d'accessibilité choisie.
+#[derive(Resource, Deserialize, Clone)]
+pub struct Palette {
+    #[serde(deserialize_with = "rgb")]
+    pub background: Color,
+    #[serde(deserialize_with = "rgb")]
+    pub snake_head: Color,
+    #[serde(deserialize_with = "rgb")]
+    pub snake_body: Color,
+    #[serde(deserialize_with = "rgb")]
+    pub enemy_head: Color,
+    #[serde(deserialize_with = "rgb")]
+    pub enemy_body: Color,
+    #[serde(deserialize_with = "rgb")]
+    pub wall: Color,
+    #[serde(deserialize_with = "rgb")]
+    pub moving_obstacle: Color,
+    #[serde(deserialize_with = "rgb")]
+    pub timed_obstacle: Color,
+    /// Opacité des murs et du corps du serpent pendant l'invincibilité.
+    pub crossing_alpha: f32,
+    /// Combo et points gagnés.
+    #[serde(deserialize_with = "rgb")]
+    pub combo_text: Color,
+    /// Collision montrée pendant le ralenti.
+    #[serde(deserialize_with = "rgb")]
+    pub collision: Color,
+    /// Couleurs remplaçant celles du catalogue des nourritures, par nom.
+    #[serde(default, deserialize_with = "rgb_map")]
+    pub foods: HashMap<String, Color>
+}
+
+/// Index des tuiles dans l'atlas, ligne par ligne. Les directions sont dans l'ordre haut, droite, bas, gauche.
+#[derive(Deserialize, Clone)]
+pub struct ThemeAtlas {
+    /// Chemin de l'image, relatif au dossier des assets.
+    pub path: String,
+    pub tile_size: f32,
+    pub columns: usize,
+    pub head: [usize; 4],
+    /// Queue selon la direction du segment suivant.
+    pub tail: [usize; 4],
+    /// Corps droit : horizontal puis vertical.
+    pub straight: [usize; 2],
+    /// Coins haut-droite, droite-bas, bas-gauche et gauche-haut.
+    pub corners: [usize; 4],
+    /// Première des 16 tuiles de mur, indexées par les murs voisins (haut 1, droite 2, bas 4, gauche 8).
+    pub walls: usize,
+    /// Icône de chaque nourriture du catalogue, par nom.
+    #[serde(default)]
+    pub foods: HashMap<String, usize>
+}
+
+fn rgb<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let (r, g, b) = <(f32, f32, f32)>::deserialize(deserializer)?;
+    Ok(Color::rgb(r, g, b))
+}
+
+fn rgb_map<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<String, Color>, D::Error> {
+    let colors = HashMap::<String, (f32, f32, f32)>::deserialize(deserializer)?;
+    Ok(colors.into_iter().map(|(name, (r, g, b))| (name, Color::rgb(r, g, b))).collect())
+}
+
+impl Palette {
+    /// Couleur d'apparition de la nourriture.
+    pub fn food_color(&self, kind: &FoodKind) -> Color {
+        self.foods.get(&kind.name).copied().unwrap_or_else(|| kind.base_color())
+    }
+}
+
+/// Charge les thèmes depuis le dossier des assets, ou à défaut la version embarquée dans l'exécutable.
+fn load_theme_catalogue() -> ThemeCatalogue {
+    let embedded = include_str!("../../assets/themes.ron");
+    let content = fs::read_to_string(THEME_CATALOGUE_PATH).unwrap_or_else(|_| embedded.to_owned());
+
+    parse_theme_catalogue(&content).unwrap_or_else(|error| {
+        println!("catalogue de thèmes invalide ({}), utilisation du catalogue par défaut", error);
+        parse_theme_catalogue(embedded).expect("catalogue de thèmes par défaut invalide")
+    })
+}
+
+/// Un catalogue sans thème est refusé : le premier thème sert de thème par défaut.
+fn parse_theme_catalogue(content: &str) -> Result<ThemeCatalogue, String> {
+    let theme_catalogue: ThemeCatalogue = ron::from_str(content).map_err(|error| error.to_string())?;
+    if theme_catalogue.themes.is_empty() {
+        return Err("aucun thème".to_owned());
+    }
+    Ok(theme_catalogue)
+}
+
+impl ThemeCatalogue {
+    /// Premier thème du catalogue, qui n'est jamais vide.
+    pub fn default_theme(&self) -> &Theme {
+        &self.themes[0]
+    }
+
+    /// Thème choisi dans les options, ou le premier du catalogue s'il n'existe plus.
+    pub fn get(&self, name: &str) -> &Theme {
+        self.themes.iter()
+            .find(|theme| theme.name == name)
+            .unwrap_or_else(|| self.default_theme())
+    }
+
+    /// Nom du thème qui suit celui donné, pour faire défiler les thèmes.
+    pub fn next_name(&self, name: &str) -> String {
+        let index = self.themes.iter().position(|theme| theme.name == name).unwrap_or(0);
+        self.themes.get(index + 1).unwrap_or_else(|| self.default_theme()).name.clone()
+    }
+
+    pub fn palette(&self, settings: &Settings) -> &Palette {
+        match settings.palette_mode {
+            PaletteMode::Standard => &self.get(&settings.theme).palette,
+            PaletteMode::Colorblind => &self.colorblind,
+            PaletteMode::HighContrast => &self.high_contrast
+        }
+    }
+
+    /// Atlas dont les icônes de nourriture sont utilisées : celui du thème,
+    /// ou si l'option est activée le premier atlas du catalogue.
+    fn food_icons(&self, settings: &Settings) -> Option<&ThemeAtlas> {
+        self.get(&settings.theme).atlas.as_ref().or_else(|| {
+            self.themes.iter()
+                .filter(|_| settings.food_shapes)
+                .find_map(|theme| theme.atlas.as_ref())
+        })
+    }
+}
+
+/// Atlas du thème courant ; vide pour un thème sans atlas.
+#[derive(Resource, Default)]
+pub struct ThemeTextures {
+    atlas: Option<(Handle<Image>, ThemeAtlas)>,
+    food_icons: Option<(Handle<Image>, ThemeAtlas)>
+}
+
+fn tile((image, atlas): &(Handle<Image>, ThemeAtlas), index: usize) -> (Handle<Image>, Rect) {
+    let min = Vec2::new((index % atlas.columns) as f32, (index / atlas.columns) as f32) * atlas.tile_size;
+    (image.clone(), Rect::from_corners(min, min + Vec2::splat(atlas.tile_size)))
+}
+
+impl ThemeTextures {
+    fn tile(&self, index: usize) -> Option<(Handle<Image>, Rect)> {
+        self.atlas.as_ref().map(|atlas| tile(atlas, index))
+    }
+
+    fn layout(&self) -> Option<&ThemeAtlas> {
+        self.atlas.as_ref().map(|(_, atlas)| atlas)
+    }
+
+    /// Icône de la nourriture, si le thème ou l'option des formes en donne une.
+    pub fn food(&self, name: &str) -> Option<(Handle<Image>, Rect)> {
+        self.food_icons.as_ref()
+            .and_then(|food_icons| food_icons.1.foods.get(name).map(|index| tile(food_icons, *index)))
+    }
+}
+
+// endregion: --- Theme Catalogue
+
+pub struct ThemePlugin;
+
+/// Ressources du thème par défaut, sans les systèmes qui le changent ni ceux qui l'affichent.
+pub(super) fn insert_theme_resources(app: &mut App) {
+    let theme_catalogue = load_theme_catalogue();
+
+    app
+        .insert_resource(ClearColor(theme_catalogue.default_theme().palette.background))
+        .insert_resource(theme_catalogue.default_theme().palette.clone())
+        .insert_resource(theme_catalogue)
+        .init_resource::<ThemeTextures>();
+}
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        insert_theme_resources(app);
+
+        app
+            .add_system(theme_change_system)
+            .add_system(atlas_sampler_system)
+            .add_system_set_to_stage(
+                CoreStage::PostUpdate,
+                ConditionSet::new()
+                    .run_in_bevy_state(AppState::InGame)
+                    .run_if(theme_has_atlas)
+                    .with_system(snake_skin_system)
+                    .with_system(wall_skin_system)
+                    .into()
+            );
+    }
+}
+
+fn theme_has_atlas(theme_textures: Res<ThemeTextures>) -> bool {
+    theme_textures.atlas.is_some()
+}
+
+/// Applique le thème et les options d'accessibilité, au lancement puis à chaque changement.
+fn theme_change_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    theme_catalogue: Res<ThemeCatalogue>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let palette = theme_catalogue.palette(&settings);
+    commands.insert_resource(ClearColor(palette.background));
+    commands.insert_resource(palette.clone());
+
+    let load = |atlas: &ThemeAtlas| (asset_server.load(atlas.path.as_str()), atlas.clone());
+    commands.insert_resource(ThemeTextures {
+        atlas: theme_catalogue.get(&settings.theme).atlas.as_ref().map(load),
+        food_icons: theme_catalogue.food_icons(&settings).map(load)
+    });
+}
+
+/// Les tuiles sont petites : pas de lissage, sinon les tuiles voisines de l'atlas débordent.
+fn atlas_sampler_system(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    theme_textures: Res<ThemeTextures>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for event in image_events.iter() {
+        if let AssetEvent::Created { handle } = event {
+            let is_atlas = |atlas: &Option<(Handle<Image>, ThemeAtlas)>| atlas.as_ref().is_some_and(|(atlas, _)| atlas == handle);
+            if is_atlas(&theme_textures.atlas) || is_atlas(&theme_textures.food_icons) {
+                if let Some(image) = images.get_mut(handle) {
+                    image.sampler_descriptor = ImageSampler::nearest();
+                }
+            }
+        }
+    }
+}
+
+/// Côté (haut 0, droite 1, bas 2, gauche 3) de la case voisine ; `None` après un portail.
+fn side(from: &Position, to: &Position, arena_size: &ArenaSize) -> Option<usize> {
+    match unwrapped_offset(from, to, arena_size)? {
+        (0, 1) => Some(0),
+        (1, 0) => Some(1),
+        (0, -1) => Some(2),
+        _ => Some(3)
+    }
+}
+
+fn direction_side(direction: &Direction) -> usize {
+    match direction {
+        Direction::UP => 0,
+        Direction::RIGHT => 1,
+        Direction::DOWN => 2,
+        Direction::LEFT => 3
+    }
+}
+
+fn apply_tile(tile: Option<(Handle<Image>, Rect)>, sprite: &mut Sprite, texture: &mut Handle<Image>) {
+    if let Some((image, rect)) = tile {
+        if *texture != image {
+            *texture = image;
+        }
+        sprite.rect = Some(rect);
+        // l'échelle est donnée par `Size`, comme pour un carré de couleur
+        sprite.custom_size = Some(Vec2::ONE);
+    }
+}
+
+/// Tête orientée selon la direction, et pour chaque segment une pièce droite, un coin ou une queue
+/// selon les segments voisins dans `body_parts`.
+#[allow(clippy::type_complexity)]
+fn snake_skin_system(
+    arena_size: Res<ArenaSize>,
+    theme_textures: Res<ThemeTextures>,
+    mut snake_head_query: Query<(&SnakeHead, &Direction, &Position, &mut Sprite, &mut Handle<Image>, &mut Size)>,
+    mut snake_body_query: Query<(&SnakeBody, &Position, &mut Sprite, &mut Handle<Image>, &mut Size), Without<SnakeHead>>,
+) {
+    let atlas = match theme_textures.layout() {
+        Some(atlas) => atlas,
+        None => return
+    };
+    let body_positions: HashMap<Uuid, Position> = snake_body_query.iter().map(|(snake_body, position, ..)| (snake_body.id, *position)).collect();
+    let mut body_tiles = HashMap::new();
+
+    for (snake_head, direction, head_position, mut sprite, mut texture, mut size) in snake_head_query.iter_mut() {
+        apply_tile(theme_textures.tile(atlas.head[direction_side(direction)]), &mut sprite, &mut texture);
+        *size = Size::square(1.);
+
+        // body_parts va de la queue vers la tête
+        let positions: Vec<Option<&Position>> = snake_head.body_parts.iter().map(|body| body_positions.get(&body.id)).collect();
+        for (index, body) in snake_head.body_parts.iter().enumerate() {
+            let position = match positions[index] {
+                Some(position) => position,
+                None => continue
+            };
+            let toward_head = positions.get(index + 1).copied().flatten().unwrap_or(head_position);
+            let head_side = side(position, toward_head, &arena_size);
+            let tail_side = index.checked_sub(1)
+                .and_then(|previous| positions[previous])
+                .and_then(|toward_tail| side(position, toward_tail, &arena_size));
+
+            let tile = match (index, head_side, tail_side) {
+                (0, Some(head_side), _) => atlas.tail[head_side],
+                (_, Some(a), Some(b)) if a % 2 == b % 2 => atlas.straight[(a + 1) % 2],
+                (_, Some(a), Some(b)) => atlas.corners[if (a + 1) % 4 == b { a } else { b }],
+                (_, Some(a), None) | (_, None, Some(a)) => atlas.straight[(a + 1) % 2],
+                _ => continue
+            };
+            body_tiles.insert(body.id, tile);
+        }
+    }
+
+    for (snake_body, _, mut sprite, mut texture, mut size) in snake_body_query.iter_mut() {
+        if let Some(tile) = body_tiles.get(&snake_body.id) {
+            apply_tile(theme_textures.tile(*tile), &mut sprite, &mut texture);
+            *size = Size::square(1.);
+        }
+    }
+}
+
+/// Les murs dans l'arène occupent toute leur case et se raccordent à leurs voisins ;
+/// les bords extérieurs restent des bandes de couleur.
+#[allow(clippy::type_complexity)]
+fn wall_skin_system(
+    arena_size: Res<ArenaSize>,
+    theme_textures: Res<ThemeTextures>,
+    mut wall_query: Query<(&Position, &mut Sprite, &mut Handle<Image>, &mut Transform), (With<Wall>, Without<ExteriorWall>)>,
+) {
+    let atlas = match theme_textures.layout() {
+        Some(atlas) => atlas,
+        None => return
+    };
+    let wall_positions: HashSet<Position> = wall_query.iter().map(|(position, ..)| *position).collect();
+
+    for (position, mut sprite, mut texture, mut transform) in wall_query.iter_mut() {
+        let mask = [(0, 1), (1, 0), (0, -1), (-1, 0)].iter()
+            .enumerate()
+            .filter(|(_, (dx, dy))| wall_positions.contains(&Position {x: position.x + dx, y: position.y + dy}))
+            .fold(0, |mask, (bit, _)| mask | 1 << bit);
+
+        apply_tile(theme_textures.tile(atlas.walls + mask), &mut sprite, &mut texture);
+        transform.scale = Vec3::new(
+            arena_size.px_width / arena_size.tile_width as f32,
+            arena_size.px_height / arena_size.tile_height as f32,
+            1.,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_catalogue_is_valid() {
+        let theme_catalogue = parse_theme_catalogue(include_str!("../../assets/themes.ron")).unwrap();
+        assert_eq!(theme_catalogue.next_name(&theme_catalogue.themes.last().unwrap().name), theme_catalogue.default_theme().name);
+    }
+
+    #[test]
+    fn empty_catalogue_is_rejected() {
+        let embedded = include_str!("../../assets/themes.ron");
+        let themes_start = embedded.find("themes: [").unwrap() + "themes: [".len();
+        let themes_end = embedded.find("\n    ],").unwrap();
+        let empty = format!("{}{}", &embedded[..themes_start], &embedded[themes_end..]);
+
+        assert!(parse_theme_catalogue(&empty).is_err());
+    }
+}
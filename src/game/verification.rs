@@ -0,0 +1,317 @@
+use std::fmt;
+
+use super::{components::Position, replay::{RunRecording, ReplayFrame, ReplayCell}, simulation::simulate, stats::GameOutcome};
+
+/// Résultat d'une partie : celui annoncé par le replay ou celui retrouvé par la simulation.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct RunResult {
+    pub score: u32,
+    /// Longueur du serpent du joueur au dernier déplacement.
+    pub length: u32,
+    pub outcome: Option<GameOutcome>
+}
+
+/// État du joueur à un déplacement.
+pub struct TickState {
+    pub score: u32,
+    pub length: u32,
+    pub head: Option<Position>
+}
+
+/// Premier déplacement où la simulation s'écarte du replay.
+pub struct Divergence {
+    /// Numéro du déplacement, à partir de 0 : l'image correspondante du replay.
+    pub tick: usize,
+    /// `None` si la partie est déjà terminée de ce côté.
+    pub expected: Option<TickState>,
+    pub actual: Option<TickState>,
+    /// Cases du replay absentes de la simulation.
+    pub missing_cells: Vec<(Position, ReplayCell)>,
+    /// Cases de la simulation absentes du replay.
+    pub unexpected_cells: Vec<(Position, ReplayCell)>
+}
+
+/// Comparaison d'un replay avec la partie rejouée à partir de son journal de touches.
+pub struct VerificationReport {
+    pub expected: RunResult,
+    pub actual: RunResult,
+    pub divergence: Option<Divergence>
+}
+
+impl VerificationReport {
+    pub fn is_valid(&self) -> bool {
+        self.divergence.is_none()
+            && self.expected.score == self.actual.score
+            && self.expected.length == self.actual.length
+            // l'issue n'est pas toujours connue à l'enregistrement du replay
+            && self.expected.outcome.is_none_or(|outcome| Some(outcome) == self.actual.outcome)
+    }
+}
+
+fn player_length(frame: &ReplayFrame) -> u32 {
+    frame.cells.iter()
+        .filter(|(_, cell)| matches!(cell, ReplayCell::PlayerHead | ReplayCell::PlayerBody))
+        .count() as u32
+}
+
+fn tick_state(frame: &ReplayFrame) -> TickState {
+    TickState {
+        score: frame.score,
+        length: player_length(frame),
+        head: frame.cells.iter()
+            .find(|(_, cell)| matches!(cell, ReplayCell::PlayerHead))
+            .map(|(position, _)| *position)
+    }
+}
+
+fn cell_rank(cell: &ReplayCell) -> usize {
+    match cell {
+        ReplayCell::PlayerHead => 0,
+        ReplayCell::PlayerBody => 1,
+        ReplayCell::EnemyHead => 2,
+        ReplayCell::EnemyBody => 3,
+        ReplayCell::Wall => 4,
+        ReplayCell::Food(kind) => 5 + kind
+    }
+}
+
+/// Les cases d'une image suivent l'ordre des requêtes, qui change d'une exécution à l'autre.
+fn sorted_cells(frame: &ReplayFrame) -> Vec<(Position, ReplayCell)> {
+    let mut cells = frame.cells.clone();
+    cells.sort_by_key(|(position, cell)| (position.y, position.x, cell_rank(cell)));
+    cells
+}
+
+fn cells_difference(cells: &[(Position, ReplayCell)], other_cells: &[(Position, ReplayCell)]) -> Vec<(Position, ReplayCell)> {
+    let mut other_cells = other_cells.to_vec();
+    cells.iter()
+        .filter(|cell| match other_cells.iter().position(|other_cell| other_cell == *cell) {
+            Some(index) => {
+                other_cells.remove(index);
+                false
+            },
+            None => true
+        })
+        .copied()
+        .collect()
+}
+
+fn divergence(expected_frames: &[ReplayFrame], actual_frames: &[ReplayFrame]) -> Option<Divergence> {
+    let tick_count = expected_frames.len().max(actual_frames.len());
+    (0..tick_count).find_map(|tick| {
+        let expected_cells = expected_frames.get(tick).map(sorted_cells).unwrap_or_default();
+        let actual_cells = actual_frames.get(tick).map(sorted_cells).unwrap_or_default();
+        // le score absent d'une partie déjà terminée compte aussi comme un écart
+        let same_score = expected_frames.get(tick).map(|frame| frame.score) == actual_frames.get(tick).map(|frame| frame.score);
+        if same_score && expected_cells == actual_cells {
+            return None;
+        }
+
+        Some(Divergence {
+            tick,
+            expected: expected_frames.get(tick).map(tick_state),
+            actual: actual_frames.get(tick).map(tick_state),
+            missing_cells: cells_difference(&expected_cells, &actual_cells),
+            unexpected_cells: cells_difference(&actual_cells, &expected_cells)
+        })
+    })
+}
+
+/// Rejoue la partie d'un replay sans fenêtre et la compare déplacement par déplacement à ses images,
+/// puis compare le score annoncé, la longueur du serpent et l'issue à ceux de la simulation.
+pub fn verify(run_recording: &RunRecording, claimed_score: u32) -> Result<VerificationReport, String> {
+    let game_type = run_recording.game_type.as_ref().ok_or("replay sans réglages de partie (ancien format)")?;
+    if run_recording.resumed {
+        return Err("partie reprise d'une sauvegarde : le journal des touches ne commence pas au début".to_owned());
+    }
+    if run_recording.input_log.frames.is_empty() {
+        return Err("replay sans journal des touches".to_owned());
+    }
+
+    let simulation_result = simulate(game_type, &run_recording.input_log);
+    let actual_frames = &simulation_result.run_recording.frames;
+
+    Ok(VerificationReport {
+        expected: RunResult {
+            score: claimed_score,
+            length: run_recording.frames.last().map_or(0, player_length),
+            outcome: run_recording.outcome
+        },
+        actual: RunResult {
+            score: simulation_result.score,
+            length: actual_frames.last().map_or(0, player_length),
+            outcome: simulation_result.outcome
+        },
+        divergence: divergence(&run_recording.frames, actual_frames)
+    })
+}
+
+// region:    --- Display
+
+fn outcome_label(outcome: Option<GameOutcome>) -> String {
+    outcome.map_or("inconnue".to_owned(), |outcome| outcome.label())
+}
+
+fn cell_label(cell: &ReplayCell) -> String {
+    match cell {
+        ReplayCell::PlayerHead => "tête du joueur".to_owned(),
+        ReplayCell::PlayerBody => "corps du joueur".to_owned(),
+        ReplayCell::EnemyHead => "tête d'adversaire".to_owned(),
+        ReplayCell::EnemyBody => "corps d'adversaire".to_owned(),
+        ReplayCell::Food(kind) => format!("nourriture {}", kind),
+        ReplayCell::Wall => "mur".to_owned()
+    }
+}
+
+fn cells_label(cells: &[(Position, ReplayCell)]) -> String {
+    if cells.is_empty() {
+        return "aucune".to_owned();
+    }
+    cells.iter()
+        .map(|(position, cell)| format!("{} en ({}, {})", cell_label(cell), position.x, position.y))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl fmt::Display for TickState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "score {}, longueur {}", self.score, self.length)?;
+        match self.head {
+            Some(head) => write!(f, ", tête en ({}, {})", head.x, head.y),
+            None => write!(f, ", pas de tête")
+        }
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", if self.is_valid() { "replay vérifié" } else { "replay non conforme" })?;
+        writeln!(f, "             annoncé | rejoué")?;
+        writeln!(f, "score      : {} | {}", self.expected.score, self.actual.score)?;
+        writeln!(f, "longueur   : {} | {}", self.expected.length, self.actual.length)?;
+        write!(f, "issue      : {} | {}", outcome_label(self.expected.outcome), outcome_label(self.actual.outcome))?;
+
+        if let Some(divergence) = &self.divergence {
+            let state_label = |tick_state: &Option<TickState>| tick_state.as_ref().map_or("partie terminée".to_owned(), |tick_state| tick_state.to_string());
+            write!(f, "\npremier écart au déplacement {}", divergence.tick)?;
+            write!(f, "\n  replay     : {}", state_label(&divergence.expected))?;
+            write!(f, "\n  simulation : {}", state_label(&divergence.actual))?;
+            write!(f, "\n  cases manquantes   : {}", cells_label(&divergence.missing_cells))?;
+            write!(f, "\n  cases inattendues  : {}", cells_label(&divergence.unexpected_cells))?;
+        }
+        Ok(())
+    }
+}
+
+// endregion: --- Display
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::OnceLock, time::{Duration, Instant}};
+
+    use bevy::{prelude::*, time::TimePlugin};
+
+    use crate::{AppState, add_headless_plugins, main_menu::{MainMenuPlugin, sub_menu::{GameType, Controller}}};
+    use super::*;
+    use super::super::{GamePlugin, Score, combo::Combo, settings::Settings, simulation::Simulation, stats::RunStats};
+
+    const MAX_FRAMES: u64 = 20_000;
+
+    struct BotRun {
+        run_recording: RunRecording,
+        score: u32,
+        best_combo: u32
+    }
+
+    /// Partie jouée par l'ordinateur comme sans fenêtre, avec des images de durées variables.
+    /// `Simulation` évite seulement d'écrire dans `SAVE_DIRECTORY`.
+    fn record_bot_run(game_type: GameType) -> BotRun {
+        let mut app = App::new();
+        app
+            .add_plugins(MinimalPlugins.build().disable::<TimePlugin>())
+            .init_resource::<Time>();
+        add_headless_plugins(&mut app);
+        app
+            .insert_resource(Simulation)
+            .add_state(AppState::MainMenu)
+            .add_plugin(GamePlugin)
+            .add_plugin(MainMenuPlugin)
+            .insert_resource(Settings::default())
+            .insert_resource(game_type);
+
+        let mut now = Instant::now();
+        let mut step = |app: &mut App, milliseconds: u64| {
+            now += Duration::from_millis(milliseconds);
+            app.world.resource_mut::<Time>().update_with_instant(now);
+            app.update();
+        };
+        for _ in 0..3 {
+            step(&mut app, 13);
+        }
+        app.world.resource_mut::<State<AppState>>().set(AppState::InGame).unwrap();
+
+        let mut best_combo = 0;
+        for frame in 0..MAX_FRAMES {
+            step(&mut app, 10 + frame * 7 % 11);
+            best_combo = best_combo.max(app.world.get_resource::<Combo>().map_or(0, |combo| combo.count));
+            if !matches!(app.world.resource::<State<AppState>>().current(), AppState::InGame | AppState::Pause) {
+                break;
+            }
+        }
+        for _ in 0..3 {
+            step(&mut app, 16);
+        }
+
+        // ce que `recording_save_system` fait avant d'écrire le replay
+        let mut run_recording = app.world.remove_resource::<RunRecording>().unwrap();
+        run_recording.outcome = app.world.resource::<RunStats>().outcome;
+        BotRun {
+            score: app.world.resource::<Score>().0,
+            run_recording,
+            best_combo
+        }
+    }
+
+    /// Mode à obstacles mobiles et temporaires, avec adversaires ; la limite de déplacements borne la partie.
+    fn bot_game_type() -> GameType {
+        GameType {
+            wall_type: 3,
+            seed: 5,
+            enemies: 2,
+            controller: Controller::Bot,
+            move_limit: Some(400),
+            ..Default::default()
+        }
+    }
+
+    /// Partie partagée par les tests : l'enregistrer prend autant de temps que la rejouer.
+    fn bot_run() -> &'static BotRun {
+        static BOT_RUN: OnceLock<BotRun> = OnceLock::new();
+        BOT_RUN.get_or_init(|| record_bot_run(bot_game_type()))
+    }
+
+    #[test]
+    fn verify_reproduces_bot_run() {
+        let bot_run = bot_run();
+        let run_recording = &bot_run.run_recording;
+        assert!(run_recording.outcome.is_some(), "partie inachevée après {} images", MAX_FRAMES);
+        assert!(bot_run.best_combo >= 2, "aucun combo pendant la partie");
+        assert!(run_recording.frames.iter().any(|frame| frame.cells.iter().any(|(_, cell)| matches!(cell, ReplayCell::EnemyHead))));
+
+        let report = verify(run_recording, bot_run.score).unwrap();
+        assert!(report.is_valid(), "{}", report);
+        assert_eq!(report.actual.score, bot_run.score);
+        assert_eq!(report.actual.length, player_length(run_recording.frames.last().unwrap()));
+        assert_eq!(report.actual.outcome, run_recording.outcome);
+    }
+
+    #[test]
+    fn verify_rejects_tampered_score() {
+        let bot_run = bot_run();
+
+        let report = verify(&bot_run.run_recording, bot_run.score + 1).unwrap();
+        assert!(!report.is_valid());
+        assert!(report.divergence.is_none(), "{}", report);
+        assert_eq!(report.actual.score, bot_run.score);
+    }
+}
@@ -0,0 +1,221 @@
+use bevy::prelude::*;
+use iyes_loopless::prelude::{IntoConditionalSystem, ConditionSet, ConditionHelpers};
+use rand::Rng;
+
+use super::{AppState, ArenaSize, GameEvent, convert, settings::Settings, food::FoodCatalogue, theme::Palette, replay::ReplayPlayer, components::{Food, FoodTimer, Size}, VFX_PARTICLE_COUNT, VFX_PARTICLE_SIZE, VFX_PARTICLE_SPEED, VFX_PARTICLE_DURATION, VFX_SHAKE_DURATION, VFX_SHAKE_INTENSITY, VFX_FOOD_EXPIRY_WARNING, VFX_FOOD_BLINK_PERIOD, VFX_POP_DURATION};
+
+// region:    --- Components
+
+/// Éclat lancé quand une nourriture est mangée.
+#[derive(Component)]
+struct Particle {
+    velocity: Vec2,
+    timer: Timer
+}
+
+// endregion: --- Components
+
+// region:    --- Resource
+
+/// Tremblement d'une caméra autour de sa position d'origine.
+/// La caméra peut disparaître avant la fin si le joueur quitte le ralenti.
+#[derive(Resource)]
+struct ScreenShake {
+    camera_entity: Entity,
+    origin: Vec3,
+    timer: Timer
+}
+
+// endregion: --- Resource
+
+/// Effets de pure présentation : ils ne lisent que les événements et l'état du jeu,
+/// et leur hasard ne touche pas celui de la partie.
+pub struct VfxPlugin;
+
+impl Plugin for VfxPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system(
+                food_eaten_particles_system
+                    .run_in_bevy_state(AppState::InGame)
+                    .run_if(visual_effects_enabled)
+            )
+            .add_system(particle_system.run_in_bevy_state(AppState::InGame))
+            .add_system(
+                death_shake_system
+                    .run_in_bevy_state(AppState::DeathReplay)
+                    .run_if(visual_effects_enabled)
+                    .run_if_resource_exists::<ReplayPlayer>()
+            )
+            .add_system(screen_shake_system.run_if_resource_exists::<ScreenShake>())
+            .add_system_set_to_stage(
+                CoreStage::PostUpdate,
+                ConditionSet::new()
+                    .run_in_bevy_state(AppState::InGame)
+                    .run_if(visual_effects_enabled)
+                    .after("position_translation")
+                    .with_system(food_expiry_system)
+                    .with_system(food_pop_system)
+                    .into()
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::InGame)
+                    .with_system(cleanup_particles_system)
+            );
+    }
+}
+
+fn visual_effects_enabled(settings: Res<Settings>) -> bool {
+    settings.visual_effects
+}
+
+fn food_eaten_particles_system(
+    mut commands: Commands,
+    mut game_events: EventReader<GameEvent>,
+    arena_size: Res<ArenaSize>,
+    food_catalogue: Res<FoodCatalogue>,
+    palette: Res<Palette>,
+) {
+    let mut rng = rand::thread_rng();
+
+    for game_event in game_events.iter() {
+        if let GameEvent::FoodEaten { kind, position, .. } = game_event {
+            let color = palette.food_color(&food_catalogue.kinds[*kind]);
+            let translation = Vec3::new(
+                convert(position.x as f32, arena_size.px_width, arena_size.tile_width as f32),
+                convert(position.y as f32, arena_size.px_height, arena_size.tile_height as f32),
+                5.,
+            );
+
+            for _ in 0..VFX_PARTICLE_COUNT {
+                let angle = rng.gen_range(0. ..std::f32::consts::TAU);
+                let speed = rng.gen_range(0.5..1.) * VFX_PARTICLE_SPEED;
+                commands.spawn(SpriteBundle {
+                    sprite: Sprite {
+                        color,
+                        custom_size: Some(Vec2::splat(VFX_PARTICLE_SIZE)),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(translation),
+                    ..Default::default()
+                })
+                .insert(Particle {
+                    velocity: Vec2::from_angle(angle) * speed,
+                    timer: Timer::from_seconds(VFX_PARTICLE_DURATION, TimerMode::Once)
+                });
+            }
+        }
+    }
+}
+
+/// Les éclats ralentissent et s'effacent avant de disparaître.
+fn particle_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particle_query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    for (entity, mut particle, mut transform, mut sprite) in particle_query.iter_mut() {
+        particle.timer.tick(time.delta());
+        if particle.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let remaining = 1. - particle.timer.percent();
+        transform.translation += (particle.velocity * remaining * time.delta_seconds()).extend(0.);
+        sprite.color.set_a(remaining);
+    }
+}
+
+/// La caméra du ralenti de la mort tremble pendant l'arrêt sur la collision.
+fn death_shake_system(mut commands: Commands, replay_player: Res<ReplayPlayer>, camera_query: Query<&Transform>) {
+    if !replay_player.is_added() {
+        return;
+    }
+
+    if let Ok(transform) = camera_query.get(replay_player.camera_entity) {
+        commands.insert_resource(ScreenShake {
+            camera_entity: replay_player.camera_entity,
+            origin: transform.translation,
+            timer: Timer::from_seconds(VFX_SHAKE_DURATION, TimerMode::Once)
+        });
+    }
+}
+
+fn screen_shake_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut screen_shake: ResMut<ScreenShake>,
+    mut camera_query: Query<&mut Transform>,
+) {
+    screen_shake.timer.tick(time.delta());
+    let mut transform = match camera_query.get_mut(screen_shake.camera_entity) {
+        Ok(transform) => transform,
+        Err(_) => {
+            commands.remove_resource::<ScreenShake>();
+            return;
+        }
+    };
+
+    if screen_shake.timer.finished() {
+        transform.translation = screen_shake.origin;
+        commands.remove_resource::<ScreenShake>();
+        return;
+    }
+
+    let mut rng = rand::thread_rng();
+    let intensity = VFX_SHAKE_INTENSITY * (1. - screen_shake.timer.percent());
+    let offset = Vec2::new(rng.gen_range(-1. ..1.), rng.gen_range(-1. ..1.)) * intensity;
+    transform.translation = screen_shake.origin + offset.extend(0.);
+}
+
+/// Une nourriture sur le point de disparaître s'efface en clignotant ;
+/// sans clignotement lorsqu'ils sont réduits dans les options.
+#[allow(clippy::type_complexity)]
+fn food_expiry_system(
+    settings: Res<Settings>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut food_query: Query<(&FoodTimer, &mut Visibility, Option<&mut Sprite>, Option<&Handle<ColorMaterial>>), With<Food>>,
+) {
+    for (food_timer, mut visibility, sprite, color_material) in food_query.iter_mut() {
+        let remaining = food_timer.0.remaining_secs();
+        if remaining >= VFX_FOOD_EXPIRY_WARNING {
+            continue;
+        }
+
+        let alpha = remaining / VFX_FOOD_EXPIRY_WARNING;
+        if let Some(mut sprite) = sprite {
+            sprite.color.set_a(alpha);
+        }
+        if let Some(material) = color_material.and_then(|color_material| materials.get_mut(color_material)) {
+            material.color.set_a(alpha);
+        }
+
+        visibility.is_visible = settings.reduce_flashing || ((remaining / VFX_FOOD_BLINK_PERIOD) as u32).is_multiple_of(2);
+    }
+}
+
+/// Une nouvelle nourriture grossit en dépassant un peu sa taille avant de s'y fixer.
+fn food_pop_system(mut food_query: Query<(&FoodTimer, &mut Transform, Option<&Size>), With<Food>>) {
+    for (food_timer, mut transform, size) in food_query.iter_mut() {
+        let progress = (food_timer.0.elapsed_secs() / VFX_POP_DURATION).min(1.);
+        let scale = ease_out_back(progress);
+        // l'échelle des sprites est recalculée à chaque image, pas celle des formes
+        transform.scale = match size {
+            Some(_) => transform.scale * scale,
+            None => Vec3::splat(scale)
+        };
+    }
+}
+
+fn ease_out_back(progress: f32) -> f32 {
+    let overshoot = 1.70158;
+    let t = progress - 1.;
+    1. + (overshoot + 1.) * t.powi(3) + overshoot * t.powi(2)
+}
+
+fn cleanup_particles_system(mut commands: Commands, particle_query: Query<Entity, With<Particle>>) {
+    for entity in particle_query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
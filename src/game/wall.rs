@@ -1,9 +1,9 @@
-use bevy::{prelude::{Plugin, App, StartupStage, Commands, Res, Transform, Vec3, SystemSet, Query, Entity, With, CoreStage, ResMut}, sprite::{SpriteBundle, Sprite}};
-use iyes_loopless::prelude::{IntoConditionalSystem, ConditionSet};
+use bevy::{prelude::{Plugin, App, StartupStage, Commands, Res, Transform, Vec3, SystemSet, Query, Entity, With, CoreStage, ResMut, Color, Or, Visibility}, sprite::{SpriteBundle, Sprite}, time::{Time, Timer, TimerMode}};
+use iyes_loopless::prelude::{IntoConditionalSystem, ConditionSet, AppLooplessFixedTimestepExt};
 
 use crate::{AppState, main_menu::sub_menu::GameType};
 
-use super::{ArenaSize, ARENA_HEIGHT, WALL_COLOR, ARENA_WIDTH, EXTERIOR_WALL_OFFSET, EXTERIOR_WALL_THICKNESS_COEFF, EXTERIOR_WALL_LENGTH_COEFF, components::{Wall, Collision, Position}, GamePlugin, INTERIOR_WALL_THICKNESS_COEFF, INTERIOR_WALL_LENGTH_COEFF, PositionsAvailable};
+use super::{ArenaSize, GameTick, ARENA_HEIGHT, ARENA_WIDTH, EXTERIOR_WALL_OFFSET, EXTERIOR_WALL_THICKNESS_COEFF, EXTERIOR_WALL_LENGTH_COEFF, components::{Wall, ExteriorWall, Collision, Position, Portal, Size, MovingObstacle, TimedObstacle}, theme::Palette, GamePlugin, INTERIOR_WALL_THICKNESS_COEFF, INTERIOR_WALL_LENGTH_COEFF, MAZE_WALL_COEFF, PORTAL_SIZE, PORTAL_COLOR_SPEED, MOVING_OBSTACLE_STEP, TIMED_OBSTACLE_PERIOD, TIMED_OBSTACLE_WARNING, TIMED_OBSTACLE_WARNING_ALPHA, PositionsAvailable, CrossingObstaclesTimer, maze::generate_maze};
 
 
 
@@ -17,8 +17,28 @@ impl Plugin for WallPlugin {
                 ConditionSet::new()
                     .run_if(wall_not_exists)
                     .run_in_bevy_state(AppState::InGame)
+                    .label("game_walls")
+                    .after("game_input")
                     .with_system(exterior_walls_spawn_system)
-                    .with_system(interior_walls_spawn_system).into()
+                    .with_system(interior_walls_spawn_system)
+                    .with_system(portals_spawn_system)
+                    .with_system(obstacles_spawn_system).into()
+            )
+            .add_system(portal_color_system.run_in_bevy_state(AppState::InGame))
+            // les obstacles avancent avec les serpents, avant les collisions
+            .add_fixed_timestep_system(
+                "snake_move_time",
+                1,
+                moving_obstacles_system
+                    .run_in_bevy_state(AppState::InGame)
+                    .after("snake_movement")
+            )
+            .add_fixed_timestep_system(
+                "snake_move_time",
+                1,
+                timed_obstacles_system
+                    .run_in_bevy_state(AppState::InGame)
+                    .after("snake_movement")
             )
             // .add_startup_system_set_to_stage(
             //     CoreStage::PostStartup,
@@ -45,25 +65,26 @@ fn exterior_walls_spawn_system(
     mut commands: Commands,
     arena_size: Res<ArenaSize>,
     game_type: Res<GameType>,
+    palette: Res<Palette>,
 ) {
     let multiplier_wall_thickness = match game_type.wall_type {
-        1 => 3,
+        1 | 5 => 3,
         _ => 1
     };
     
-    exterior_walls_spawn_by_axe(&mut commands, &arena_size, multiplier_wall_thickness, "vertical");
-    exterior_walls_spawn_by_axe(&mut commands, &arena_size, multiplier_wall_thickness, "horizontal");    
-    exterior_wall_corners_spawn(&mut commands, &arena_size);
+    exterior_walls_spawn_by_axe(&mut commands, &arena_size, &palette, multiplier_wall_thickness, "vertical");
+    exterior_walls_spawn_by_axe(&mut commands, &arena_size, &palette, multiplier_wall_thickness, "horizontal");    
+    exterior_wall_corners_spawn(&mut commands, &arena_size, &palette);
 }
 
-fn exterior_walls_spawn_by_axe(mut commands: &mut Commands, arena_size: &Res<ArenaSize>, multiplier_wall_thickness: u32, axe: &str) {
+fn exterior_walls_spawn_by_axe(mut commands: &mut Commands, arena_size: &Res<ArenaSize>, palette: &Palette, multiplier_wall_thickness: u32, axe: &str) {
     match axe {
         "vertical" => {
             for y in 0..arena_size.tile_height {
                 // bord gauche
                 let left_brick = SpriteBundle {
                     sprite: Sprite {
-                        color: WALL_COLOR,
+                        color: palette.wall,
                         ..Default::default()
                     },
                     transform: Transform {
@@ -85,7 +106,7 @@ fn exterior_walls_spawn_by_axe(mut commands: &mut Commands, arena_size: &Res<Are
                 // bord droit
                 let right_brick = SpriteBundle {
                     sprite: Sprite {
-                        color: WALL_COLOR,
+                        color: palette.wall,
                         ..Default::default()
                     },
                     transform: Transform {
@@ -108,18 +129,22 @@ fn exterior_walls_spawn_by_axe(mut commands: &mut Commands, arena_size: &Res<Are
                     commands.spawn(left_brick)
                         .insert(Position {x: - (EXTERIOR_WALL_OFFSET * 2.) as i32, y: y as i32})
                         .insert(Wall)
+                        .insert(ExteriorWall)
                         .insert(Collision);
                     commands.spawn(right_brick)
                         .insert(Position {x: (arena_size.tile_width as f32) as i32, y: y as i32})
                         .insert(Wall)
+                        .insert(ExteriorWall)
                         .insert(Collision);
                 } else {
                     commands.spawn(left_brick)
                         .insert(Position {x: - (EXTERIOR_WALL_OFFSET * 2.) as i32, y: y as i32})
-                        .insert(Wall);
+                        .insert(Wall)
+                        .insert(ExteriorWall);
                     commands.spawn(right_brick)
                         .insert(Position {x: (arena_size.tile_width as f32 ) as i32, y: y as i32})
-                        .insert(Wall);
+                        .insert(Wall)
+                        .insert(ExteriorWall);
                 }
             }
         },
@@ -128,7 +153,7 @@ fn exterior_walls_spawn_by_axe(mut commands: &mut Commands, arena_size: &Res<Are
                 // bord superieur
                 let top_brick = SpriteBundle {
                     sprite: Sprite {
-                        color: WALL_COLOR,
+                        color: palette.wall,
                         ..Default::default()
                     },
                     transform: Transform {
@@ -150,7 +175,7 @@ fn exterior_walls_spawn_by_axe(mut commands: &mut Commands, arena_size: &Res<Are
                 // bord inferieur
                 let bottom_brick = SpriteBundle {
                     sprite: Sprite {
-                        color: WALL_COLOR,
+                        color: palette.wall,
                         ..Default::default()
                     },
                     transform: Transform {
@@ -173,18 +198,22 @@ fn exterior_walls_spawn_by_axe(mut commands: &mut Commands, arena_size: &Res<Are
                     commands.spawn(top_brick)
                         .insert(Position {x: x as i32, y: (arena_size.tile_height as f32) as i32})
                         .insert(Wall)
+                        .insert(ExteriorWall)
                         .insert(Collision);
                     commands.spawn(bottom_brick)
                         .insert(Position {x: x as i32, y: - (EXTERIOR_WALL_OFFSET * 2.) as i32})
                         .insert(Wall)
+                        .insert(ExteriorWall)
                         .insert(Collision);
                 } else {
                     commands.spawn(top_brick)
                         .insert(Position {x: x as i32, y: (arena_size.tile_height as f32) as i32})
-                        .insert(Wall);
+                        .insert(Wall)
+                        .insert(ExteriorWall);
                     commands.spawn(bottom_brick)
                         .insert(Position {x: x as i32, y: - (EXTERIOR_WALL_OFFSET * 2.) as i32})
-                        .insert(Wall);
+                        .insert(Wall)
+                        .insert(ExteriorWall);
                 }
             }
 
@@ -193,7 +222,7 @@ fn exterior_walls_spawn_by_axe(mut commands: &mut Commands, arena_size: &Res<Are
     }
 }
 
-fn exterior_wall_corners_spawn(mut commands: &mut Commands, arena_size: &Res<ArenaSize>) {
+fn exterior_wall_corners_spawn(mut commands: &mut Commands, arena_size: &Res<ArenaSize>, palette: &Palette) {
     // (x, y, decalage x, decalage y)
     let corner_positions: Vec<(f32,f32,f32,f32)> = vec![
         (-EXTERIOR_WALL_OFFSET, -EXTERIOR_WALL_OFFSET, -EXTERIOR_WALL_THICKNESS_COEFF / arena_size.tile_width as f32 * arena_size.px_width, -EXTERIOR_WALL_THICKNESS_COEFF / arena_size.tile_height as f32 * arena_size.px_height), // coin inférieur gauche
@@ -205,7 +234,7 @@ fn exterior_wall_corners_spawn(mut commands: &mut Commands, arena_size: &Res<Are
     for corner_position in corner_positions.into_iter() {
         commands.spawn(SpriteBundle {
             sprite: Sprite {
-                color: WALL_COLOR,
+                color: palette.wall,
                 ..Default::default()
             },
             transform: Transform {
@@ -224,7 +253,8 @@ fn exterior_wall_corners_spawn(mut commands: &mut Commands, arena_size: &Res<Are
             ..Default::default()
         })
         .insert(Position {x: corner_position.0 as i32, y: corner_position.1 as i32})
-        .insert(Wall);
+        .insert(Wall)
+        .insert(ExteriorWall);
     }
 }
 
@@ -237,20 +267,52 @@ fn interior_walls_spawn_system(
     mut commands: Commands,
     arena_size: Res<ArenaSize>,
     game_type: Res<GameType>,
+    palette: Res<Palette>,
     mut positions_available: ResMut<PositionsAvailable>
 ) {
     match game_type.wall_type {
-        2 => interior_walls_spawn(&mut commands, &arena_size, &mut positions_available, "vertical"),
-        3 => interior_walls_spawn(&mut commands, &arena_size, &mut positions_available, "horizontal"),
+        2 => interior_walls_spawn(&mut commands, &arena_size, &palette, &mut positions_available, "vertical"),
+        3 => interior_walls_spawn(&mut commands, &arena_size, &palette, &mut positions_available, "horizontal"),
         4 => {
-            interior_walls_spawn(&mut commands, &arena_size, &mut positions_available, "vertical");
-            interior_walls_spawn(&mut commands, &arena_size, &mut positions_available, "horizontal");
+            interior_walls_spawn(&mut commands, &arena_size, &palette, &mut positions_available, "vertical");
+            interior_walls_spawn(&mut commands, &arena_size, &palette, &mut positions_available, "horizontal");
         }
-        _ => return,
+        5 => maze_walls_spawn(&mut commands, &arena_size, &palette, &mut positions_available, game_type.seed),
+        _ => (),
     }
 }
 
-fn interior_walls_spawn(mut commands: &mut Commands, arena_size: &Res<ArenaSize>, mut positions_available: &mut ResMut<PositionsAvailable>, axe: &str) {
+fn maze_walls_spawn(commands: &mut Commands, arena_size: &Res<ArenaSize>, palette: &Palette, positions_available: &mut ResMut<PositionsAvailable>, seed: u64) {
+    for wall_position in generate_maze(arena_size, seed) {
+        positions_available.0.remove(&wall_position);
+
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: palette.wall,
+                ..Default::default()
+            },
+            transform: Transform {
+                translation: Vec3::new(
+                    convert(wall_position.x as f32, arena_size.px_width, arena_size.tile_width as f32),
+                    convert(wall_position.y as f32, arena_size.px_height, arena_size.tile_height as f32),
+                    0.0,
+                ),
+                scale: Vec3::new(
+                    MAZE_WALL_COEFF / arena_size.tile_width as f32 * arena_size.px_width,
+                    MAZE_WALL_COEFF / arena_size.tile_height as f32 * arena_size.px_height,
+                    1.0,
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(wall_position)
+        .insert(Wall)
+        .insert(Collision);
+    }
+}
+
+fn interior_walls_spawn(mut commands: &mut Commands, arena_size: &Res<ArenaSize>, palette: &Palette, mut positions_available: &mut ResMut<PositionsAvailable>, axe: &str) {
     match axe {
         "vertical" => {
             let middle = get_middle(arena_size.tile_width);
@@ -262,7 +324,7 @@ fn interior_walls_spawn(mut commands: &mut Commands, arena_size: &Res<ArenaSize>
 
                 commands.spawn(SpriteBundle {
                     sprite: Sprite {
-                        color: WALL_COLOR,
+                        color: palette.wall,
                         ..Default::default()
                     },
                     transform: Transform {
@@ -295,7 +357,7 @@ fn interior_walls_spawn(mut commands: &mut Commands, arena_size: &Res<ArenaSize>
                 
                 commands.spawn(SpriteBundle {
                     sprite: Sprite {
-                        color: WALL_COLOR,
+                        color: palette.wall,
                         ..Default::default()
                     },
                     transform: Transform {
@@ -326,13 +388,237 @@ fn interior_walls_spawn(mut commands: &mut Commands, arena_size: &Res<ArenaSize>
 fn get_middle(size: u32) -> u32 {
     match size % 2 {
         0 => {
-            (size + 1) / 2
+            size.div_ceil(2)
         },
         _ => size / 2
     }
 }
 
-fn cleanup_wall_system(mut commands: Commands, mut query: Query<Entity, With<Wall>>) {
+/// Paires de portails (entrée, sortie) propres à chaque disposition de murs.
+fn portal_layout(wall_type: usize, arena_size: &ArenaSize) -> Vec<(Position, Position)> {
+    let (width, height) = (arena_size.tile_width as i32, arena_size.tile_height as i32);
+    let (middle_x, middle_y) = (get_middle(arena_size.tile_width) as i32, get_middle(arena_size.tile_height) as i32);
+
+    match wall_type {
+        1 => vec![
+            (Position {x: 2, y: height - 3}, Position {x: width - 3, y: 2}),
+        ],
+        2 => vec![
+            (Position {x: middle_x - 4, y: height - 4}, Position {x: middle_x + 4, y: 4}),
+        ],
+        3 => vec![
+            (Position {x: 4, y: middle_y + 5}, Position {x: width - 5, y: middle_y - 5}),
+        ],
+        4 => vec![
+            (Position {x: middle_x - 5, y: middle_y + 5}, Position {x: middle_x + 5, y: middle_y - 5}),
+            (Position {x: middle_x + 5, y: middle_y + 5}, Position {x: middle_x - 5, y: middle_y - 5}),
+        ],
+        _ => Vec::new()
+    }
+}
+
+fn portals_spawn_system(
+    mut commands: Commands,
+    arena_size: Res<ArenaSize>,
+    game_type: Res<GameType>,
+    mut positions_available: ResMut<PositionsAvailable>
+) {
+    let portal_pairs = portal_layout(game_type.wall_type, &arena_size);
+    let hue_step = 360. / portal_pairs.len().max(1) as f32;
+
+    for (index, (entry, exit)) in portal_pairs.into_iter().enumerate() {
+        let hue = index as f32 * hue_step;
+
+        for (portal_position, portal_exit) in [(entry, exit), (exit, entry)] {
+            positions_available.0.remove(&portal_position);
+
+            commands.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::hsl(hue, 1., 0.5),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(portal_position)
+            .insert(Portal {exit: portal_exit, hue})
+            .insert(Size::square(PORTAL_SIZE));
+        }
+    }
+}
+
+fn portal_color_system(time: Res<Time>, mut query: Query<(&mut Sprite, &Portal)>) {
+    let shift = time.elapsed_seconds() * PORTAL_COLOR_SPEED;
+    let lightness = 0.5 + 0.2 * (time.elapsed_seconds() * 4.).sin();
+
+    for (mut sprite, portal) in query.iter_mut() {
+        sprite.color = Color::hsl((portal.hue + shift) % 360., 1., lightness);
+    }
+}
+
+/// Obstacles mobiles (un chemin par case) et obstacles temporisés propres à chaque disposition de murs.
+fn obstacle_layout(wall_type: usize, arena_size: &ArenaSize) -> (Vec<Vec<Position>>, Vec<Position>) {
+    let (width, height) = (arena_size.tile_width as i32, arena_size.tile_height as i32);
+    let (middle_x, middle_y) = (get_middle(arena_size.tile_width) as i32, get_middle(arena_size.tile_height) as i32);
+
+    match wall_type {
+        1 => (
+            slider(&[Position {x: 6, y: 12}, Position {x: 6, y: 13}, Position {x: 6, y: 14}], (1, 0), 8),
+            vec![Position {x: width - 5, y: 8}, Position {x: width - 4, y: 8}]
+        ),
+        2 => (
+            slider(&[Position {x: middle_x + 3, y: 8}, Position {x: middle_x + 4, y: 8}], (0, 1), 6),
+            (2..=5).map(|x| Position {x, y: height - 6}).collect()
+        ),
+        3 => (
+            slider(&[Position {x: 5, y: middle_y + 4}, Position {x: 5, y: middle_y + 5}], (1, 0), 9),
+            (middle_x..middle_x + 3).map(|x| Position {x, y: middle_y - 4}).collect()
+        ),
+        4 => (
+            Vec::new(),
+            vec![
+                Position {x: middle_x - 3, y: middle_y - 3},
+                Position {x: middle_x + 3, y: middle_y + 3},
+                Position {x: middle_x - 3, y: middle_y + 3},
+                Position {x: middle_x + 3, y: middle_y - 3},
+            ]
+        ),
+        _ => (Vec::new(), Vec::new())
+    }
+}
+
+fn slider(cells: &[Position], (dx, dy): (i32, i32), steps: i32) -> Vec<Vec<Position>> {
+    cells.iter()
+        .map(|cell| (0..=steps).map(|step| Position {x: cell.x + step * dx, y: cell.y + step * dy}).collect())
+        .collect()
+}
+
+fn obstacles_spawn_system(
+    mut commands: Commands,
+    arena_size: Res<ArenaSize>,
+    game_type: Res<GameType>,
+    palette: Res<Palette>,
+    mut positions_available: ResMut<PositionsAvailable>
+) {
+    let (moving_paths, timed_positions) = obstacle_layout(game_type.wall_type, &arena_size);
+
+    for path in moving_paths {
+        // la nourriture n'apparaît jamais sur le trajet d'un obstacle mobile
+        for position in path.iter() {
+            positions_available.0.remove(position);
+        }
+
+        commands.spawn(obstacle_brick(&arena_size, &path[0], palette.moving_obstacle))
+            .insert(path[0])
+            .insert(MovingObstacle {
+                path,
+                index: 0,
+                forward: true
+            })
+            .insert(Wall)
+            .insert(Collision);
+    }
+
+    for position in timed_positions {
+        positions_available.0.remove(&position);
+
+        let mut brick = obstacle_brick(&arena_size, &position, palette.timed_obstacle);
+        brick.visibility = Visibility::INVISIBLE;
+
+        commands.spawn(brick)
+            .insert(position)
+            .insert(TimedObstacle {
+                active: false
+            })
+            .insert(Wall);
+    }
+}
+
+fn obstacle_brick(arena_size: &Res<ArenaSize>, position: &Position, color: Color) -> SpriteBundle {
+    SpriteBundle {
+        sprite: Sprite {
+            color,
+            ..Default::default()
+        },
+        transform: Transform {
+            translation: Vec3::new(
+                convert(position.x as f32, arena_size.px_width, arena_size.tile_width as f32),
+                convert(position.y as f32, arena_size.px_height, arena_size.tile_height as f32),
+                0.0,
+            ),
+            scale: Vec3::new(
+                MAZE_WALL_COEFF / arena_size.tile_width as f32 * arena_size.px_width,
+                MAZE_WALL_COEFF / arena_size.tile_height as f32 * arena_size.px_height,
+                1.0,
+            ),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Un pas tous les `MOVING_OBSTACLE_STEP` déplacements.
+fn moving_obstacles_system(
+    game_tick: Res<GameTick>,
+    arena_size: Res<ArenaSize>,
+    mut query: Query<(&mut MovingObstacle, &mut Position, &mut Transform)>
+) {
+    if !game_tick.0.is_multiple_of(MOVING_OBSTACLE_STEP) {
+        return;
+    }
+
+    for (mut obstacle, mut position, mut transform) in query.iter_mut() {
+        let last_index = obstacle.path.len() - 1;
+        if obstacle.forward && obstacle.index == last_index {
+            obstacle.forward = false;
+        } else if !obstacle.forward && obstacle.index == 0 {
+            obstacle.forward = true;
+        }
+
+        if obstacle.forward {
+            obstacle.index += 1;
+        } else {
+            obstacle.index -= 1;
+        }
+
+        *position = obstacle.path[obstacle.index];
+        transform.translation.x = convert(position.x as f32, arena_size.px_width, arena_size.tile_width as f32);
+        transform.translation.y = convert(position.y as f32, arena_size.px_height, arena_size.tile_height as f32);
+    }
+}
+
+/// Les obstacles changent d'état tous les `TIMED_OBSTACLE_PERIOD` déplacements.
+fn timed_obstacles_system(
+    mut commands: Commands,
+    game_tick: Res<GameTick>,
+    palette: Res<Palette>,
+    crossing_obstacles_timer: Option<Res<CrossingObstaclesTimer>>,
+    mut query: Query<(Entity, &mut TimedObstacle, &mut Sprite, &mut Visibility)>
+) {
+    // pendant l'invincibilité, les obstacles qui apparaissent doivent rester transparents comme les autres murs
+    let lighten = crossing_obstacles_timer.is_some_and(|timer| timer.lighten);
+    let phase = game_tick.0 % TIMED_OBSTACLE_PERIOD;
+
+    for (entity, mut obstacle, mut sprite, mut visibility) in query.iter_mut() {
+        if phase == 0 {
+            obstacle.active = !obstacle.active;
+
+            if obstacle.active {
+                sprite.color.set_a(if lighten { palette.crossing_alpha } else { 1. });
+                commands.entity(entity).insert(Collision);
+            } else {
+                visibility.is_visible = false;
+                commands.entity(entity).remove::<Collision>();
+            }
+        } else if !obstacle.active && TIMED_OBSTACLE_PERIOD - phase <= TIMED_OBSTACLE_WARNING {
+            // avertissement avant l'apparition
+            visibility.is_visible = true;
+            sprite.color.set_a(TIMED_OBSTACLE_WARNING_ALPHA);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn cleanup_wall_system(mut commands: Commands, mut query: Query<Entity, Or<(With<Wall>, With<Portal>)>>) {
     // let mut count = 0;
     
     for entity in query.iter_mut() {
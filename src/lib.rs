@@ -0,0 +1,69 @@
+#![allow(unused)]
+pub mod game;
+pub mod main_menu;
+pub mod cli;
+
+// use main_menu::sub_menu;
+
+use bevy::{prelude::*, time::FixedTimestep, text::Text2dBounds, ecs::query, window::WindowPlugin};
+use iyes_loopless::{prelude::AppLooplessStateExt, state::{CurrentState, NextState}};
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum AppState {
+    MainMenu,
+    SubMenu,
+    InGame,
+	Pause,
+	GameOver(bool),
+	Achievements,
+	ProfileSelect,
+	Statistics,
+	Replay,
+	DeathReplay,
+	Settings,
+	/// Lancement depuis la ligne de commande : l'état demandé n'est atteint qu'à la première image,
+	/// pour que ses systèmes d'entrée s'exécutent avant ceux de mise à jour.
+	Launch
+}
+
+// region:    --- Game Constants
+
+pub const WINDOW_WIDTH: f32 = 500.;
+pub const WINDOW_HEIGHT: f32 = 500.;
+pub const UPPER_EDGE : f32 = 0.1;
+
+pub const ARENA_WIDTH: u32 = 20;
+pub const ARENA_HEIGHT: u32 = 20;
+/// Limites de la taille d'arène choisie en ligne de commande : les obstacles mobiles ont besoin de place.
+const ARENA_MIN_SIZE: u32 = 16;
+const ARENA_MAX_SIZE: u32 = 40;
+const SPEED_MIN: f32 = 0.25;
+const SPEED_MAX: f32 = 4.;
+
+pub const HEADLESS_FRAME_TIME: f32 = 1. / 60.;
+
+// endregion: --- Game Constants
+
+/// Les cases gardent la taille de l'arène par défaut : une arène plus grande agrandit la fenêtre.
+fn arena_px_width(arena_width: u32) -> f32 {
+	WINDOW_WIDTH * arena_width as f32 / ARENA_WIDTH as f32
+}
+
+fn arena_px_height(arena_height: u32) -> f32 {
+	WINDOW_HEIGHT * arena_height as f32 / ARENA_HEIGHT as f32
+}
+
+
+/// Sans fenêtre ni rendu : de quoi faire tourner le jeu, en plus de `MinimalPlugins`.
+pub fn add_headless_plugins(app: &mut App) {
+	app
+		.add_plugin(AssetPlugin::default())
+		.add_plugin(WindowPlugin { add_primary_window: false, exit_on_all_closed: false, ..Default::default() })
+		.add_plugin(bevy::input::InputPlugin)
+		.add_plugin(TransformPlugin)
+		.add_plugin(HierarchyPlugin)
+		.add_asset::<ColorMaterial>()
+		.add_asset::<Mesh>()
+		.add_asset::<Image>()
+		.add_asset::<Font>();
+}
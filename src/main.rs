@@ -1,55 +1,71 @@
-#![allow(unused)]
-mod game;
-use game::GamePlugin;
+use std::{process, time::Duration};
 
-mod main_menu;
-use main_menu::MainMenuPlugin;
-
-// use main_menu::sub_menu;
-
-use bevy::{prelude::*, time::FixedTimestep, text::Text2dBounds, ecs::query};
-use iyes_loopless::{prelude::AppLooplessStateExt, state::{CurrentState, NextState}};
-
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-enum AppState {
-    MainMenu,
-    SubMenu,
-    InGame,
-	Pause,
-	GameOver(bool)
-}
-
-// region:    --- Game Constants
-
-const WINDOW_WIDTH: f32 = 500.;
-const WINDOW_HEIGHT: f32 = 500.;
-const UPPER_EDGE : f32 = 0.1;
-
-const ARENA_WIDTH: u32 = 20;
-const ARENA_HEIGHT: u32 = 20;
-
-// endregion: --- Game Constants
+use bevy::{prelude::*, app::ScheduleRunnerSettings, window::WindowPlugin};
 
+use snake::{game::{self, GamePlugin}, main_menu::MainMenuPlugin, cli::{self, LaunchOptions}};
+use snake::{WINDOW_WIDTH, WINDOW_HEIGHT, UPPER_EDGE, ARENA_WIDTH, ARENA_HEIGHT, HEADLESS_FRAME_TIME, add_headless_plugins};
 
 // TODO : ajouter gestion de fin de partie (si jamais le serpent = (W x H) - 1) : Manque l'effacement du texte
 // TODO : Constat d'une erreur de despawn certainement du à la simultanéité du fin de tps de vie de la nourriture et du fait que le serpent l'ai mangée
 
 fn main() {
-    App::new()
-        .insert_resource(ClearColor(Color::rgb(0.04,0.04,0.04)))
-        // .insert_resource(ClearColor(Color::rgb(1.,1.,1.)))
-        .add_plugins(DefaultPlugins.set(WindowPlugin {
+	let launch_options = match LaunchOptions::parse(std::env::args().skip(1)) {
+		Ok(launch_options) => launch_options,
+		Err(error) => {
+			eprintln!("{}\nsnake --help pour la liste des options", error);
+			process::exit(2);
+		}
+	};
+
+	if launch_options.help {
+		println!("{}", cli::usage());
+		return;
+	}
+
+	if let Some(run_recording) = &launch_options.verify {
+		let claimed_score = launch_options.claimed_score
+			.unwrap_or_else(|| run_recording.frames.last().map_or(0, |replay_frame| replay_frame.score));
+		match game::verification::verify(run_recording, claimed_score) {
+			Ok(report) => {
+				println!("{}", report);
+				if !report.is_valid() {
+					process::exit(1);
+				}
+			},
+			Err(error) => {
+				eprintln!("vérification impossible : {}", error);
+				process::exit(2);
+			}
+		}
+		return;
+	}
+
+	let mut app = App::new();
+	if launch_options.headless {
+		app
+			.add_plugins(MinimalPlugins)
+			.insert_resource(ScheduleRunnerSettings::run_loop(Duration::from_secs_f32(HEADLESS_FRAME_TIME)));
+		add_headless_plugins(&mut app);
+		app.add_plugin(game::headless::HeadlessPlugin { max_ticks: launch_options.max_ticks });
+	} else {
+		let (arena_px_width, arena_px_height) = launch_options.arena_px_size();
+		app.add_plugins(DefaultPlugins.set(WindowPlugin {
 			window: WindowDescriptor {
 				title: "Snake".to_string(),
-				width: WINDOW_WIDTH + (WINDOW_WIDTH / ARENA_WIDTH as f32) * 3., // 600 + 2 * 6 + 12 // taille initiale + epaisseur mur + espace libre
-				height: WINDOW_HEIGHT + (WINDOW_HEIGHT * UPPER_EDGE) + (WINDOW_HEIGHT / ARENA_HEIGHT as f32) * 2., // 600 + 600 * 0.1 + 2 * 6 + 12 // taille initiale + espace de score + epaisseur mur + espace libre
+				width: arena_px_width + (WINDOW_WIDTH / ARENA_WIDTH as f32) * 3., // 600 + 2 * 6 + 12 // taille initiale + epaisseur mur + espace libre
+				height: arena_px_height + (WINDOW_HEIGHT * UPPER_EDGE) + (WINDOW_HEIGHT / ARENA_HEIGHT as f32) * 2., // 600 + 600 * 0.1 + 2 * 6 + 12 // taille initiale + espace de score + epaisseur mur + espace libre
 				resizable: false,
+				scale_factor_override: launch_options.scale,
 				..Default::default()
 			},
 			..Default::default()
 		}))
-		.add_state(AppState::MainMenu)
+		.add_plugin(game::debug::DebugPlugin);
+	}
+
+	launch_options.setup(&mut app);
+	app
 		.add_plugin(GamePlugin)
         .add_plugin(MainMenuPlugin)
 		.run();
-}
\ No newline at end of file
+}
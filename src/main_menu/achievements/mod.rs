@@ -0,0 +1,120 @@
+use crate::{main_menu::MenuMaterials, game::{achievement::Achievement, profile::Profiles}};
+
+use super::{AppState, MenuData};
+
+use bevy::prelude::*;
+
+const UNLOCKED_COLOR: Color = Color::rgb(1., 0.84, 0.);
+const LOCKED_COLOR: Color = Color::rgb(0.4, 0.4, 0.4);
+
+// region:    --- Component
+
+#[derive(Component)]
+struct BackButton;
+
+// endregion: --- Component
+
+pub struct AchievementsMenuPlugin;
+
+impl Plugin for AchievementsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(SystemSet::on_update(AppState::Achievements).with_system(button_press_system))
+            .add_system_set(SystemSet::on_enter(AppState::Achievements).with_system(setup_system))
+            .add_system_set(SystemSet::on_exit(AppState::Achievements).with_system(cleanup_system));
+    }
+}
+
+fn button_press_system(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut state: ResMut<State<AppState>>,
+) {
+    for interaction in buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            state.set(AppState::MainMenu).expect("Couldn't switch state to MainMenu");
+        }
+    }
+}
+
+fn root() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::ColumnReverse,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn button() -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            size: Size::new(Val::Px(200.0), Val::Px(50.0)),
+            margin: UiRect::all(Val::Px(10.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn text(asset_server: &Res<AssetServer>, label: &str, font_size: f32, color: Color) -> TextBundle {
+    TextBundle {
+        style: Style {
+            margin: UiRect::all(Val::Px(4.0)),
+            ..Default::default()
+        },
+        text: Text::from_section(
+            label,
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size,
+                color,
+            },
+        ),
+        ..Default::default()
+    }
+}
+
+fn setup_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    materials: Res<MenuMaterials>,
+    profiles: Res<Profiles>,
+    mut menu_data: ResMut<MenuData>
+) {
+    let unlocked_achievements = profiles.current().map(|profile| profile.achievements.as_slice()).unwrap_or_default();
+    let unlocked_count = Achievement::ALL.iter().filter(|achievement| unlocked_achievements.contains(achievement)).count();
+
+    let ui_root = commands
+        .spawn(root())
+        .with_children(|parent| {
+            parent.spawn(text(&asset_server, &format!("Succès ({}/{})", unlocked_count, Achievement::ALL.len()), 35., Color::WHITE));
+
+            for achievement in Achievement::ALL {
+                let color = if unlocked_achievements.contains(&achievement) { UNLOCKED_COLOR } else { LOCKED_COLOR };
+                parent.spawn(text(&asset_server, achievement.title(), 25., color));
+                parent.spawn(text(&asset_server, achievement.description(), 16., color));
+            }
+
+            parent.spawn(button())
+                .with_children(|parent| {
+                    parent.spawn(text(&asset_server, "Retour", 30., materials.button_text));
+                })
+                .insert(BackButton);
+        })
+        .id();
+
+    menu_data.ui_root = ui_root;
+}
+
+fn cleanup_system(mut commands: Commands, menu_data: Res<MenuData>) {
+    commands.entity(menu_data.ui_root).despawn_recursive();
+    commands.entity(menu_data.camera_entity).despawn_recursive();
+
+    commands.remove_resource::<MenuData>();
+}
@@ -3,6 +3,11 @@ use bevy::prelude::Component;
 
 #[derive(Component)]
 pub enum MenuButton {
+    Continue,
     Play,
+    Achievements,
+    Statistics,
+    Settings,
+    Profiles,
     Quit,
 }
\ No newline at end of file
@@ -1,11 +1,24 @@
 use super::AppState;
+use crate::game::save::SavedGame;
 use bevy::{prelude::*, app::AppExit};
 mod components;
 use components::MenuButton;
 
-pub(crate) mod sub_menu;
+pub mod sub_menu;
 use sub_menu::SubMenuPlugin;
 
+mod achievements;
+use achievements::AchievementsMenuPlugin;
+
+mod profile_select;
+use profile_select::ProfileSelectPlugin;
+
+mod statistics;
+use statistics::StatisticsMenuPlugin;
+
+mod settings;
+use settings::SettingsMenuPlugin;
+
 pub struct MainMenuPlugin;
 
 
@@ -45,6 +58,7 @@ impl FromWorld for MenuMaterials {
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn button_system(
     materials: Res<MenuMaterials>,
     mut buttons: Query<
@@ -61,7 +75,9 @@ fn button_system(
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn button_press_system(
+    mut commands: Commands,
     buttons: Query<(&Interaction, &MenuButton), (Changed<Interaction>, With<Button>)>,
     mut state: ResMut<State<AppState>>,
     mut exit: EventWriter<AppExit>
@@ -69,9 +85,30 @@ fn button_press_system(
     for (interaction, button) in buttons.iter() {
         if *interaction == Interaction::Clicked {
             match button {
+                MenuButton::Continue => {
+                    if let Some(saved_game) = SavedGame::take() {
+                        commands.insert_resource(saved_game.game_type());
+                        commands.insert_resource(saved_game);
+                        state
+                            .set(AppState::InGame)
+                            .expect("Couldn't switch state to InGame");
+                    }
+                },
                 MenuButton::Play => state
                     .set(AppState::SubMenu)
                     .expect("Couldn't switch state to SubMenu"),
+                MenuButton::Achievements => state
+                    .set(AppState::Achievements)
+                    .expect("Couldn't switch state to Achievements"),
+                MenuButton::Statistics => state
+                    .set(AppState::Statistics)
+                    .expect("Couldn't switch state to Statistics"),
+                MenuButton::Settings => state
+                    .set(AppState::Settings)
+                    .expect("Couldn't switch state to Settings"),
+                MenuButton::Profiles => state
+                    .set(AppState::ProfileSelect)
+                    .expect("Couldn't switch state to ProfileSelect"),
                 MenuButton::Quit => exit.send(AppExit),
             };
         }
@@ -82,6 +119,10 @@ impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<MenuMaterials>()
             .add_plugin(SubMenuPlugin)
+            .add_plugin(AchievementsMenuPlugin)
+            .add_plugin(ProfileSelectPlugin)
+            .add_plugin(StatisticsMenuPlugin)
+            .add_plugin(SettingsMenuPlugin)
             .add_system(button_system)
             .add_system(button_press_system)
             .add_system_set(SystemSet::on_enter(AppState::MainMenu).with_system(setup_system))
@@ -143,7 +184,7 @@ fn button(materials: &Res<MenuMaterials>) -> ButtonBundle {
 }
 
 fn button_text(asset_server: &Res<AssetServer>, materials: &Res<MenuMaterials>, label: &str) -> TextBundle {
-    return TextBundle {
+    TextBundle {
         style: Style {
             margin: UiRect::all(Val::Px(10.0)),
             ..Default::default()
@@ -153,11 +194,11 @@ fn button_text(asset_server: &Res<AssetServer>, materials: &Res<MenuMaterials>,
             TextStyle {
                 font: asset_server.load("fonts/FiraSans-Bold.ttf"),
                 font_size: 30.0,
-                color: materials.button_text.clone(),
+                color: materials.button_text,
             },
         ),
         ..Default::default()
-    };
+    }
 }
 
 fn setup_system(
@@ -183,11 +224,38 @@ fn setup_system(
                                     parent.spawn(button_text(&asset_server, &materials, "Quitter"));
                                 })
                                 .insert(MenuButton::Quit);
+                            parent.spawn(button(&materials))
+                                .with_children(|parent| {
+                                    parent.spawn(button_text(&asset_server, &materials, "Changer de profil"));
+                                })
+                                .insert(MenuButton::Profiles);
+                            parent.spawn(button(&materials))
+                                .with_children(|parent| {
+                                    parent.spawn(button_text(&asset_server, &materials, "Options"));
+                                })
+                                .insert(MenuButton::Settings);
+                            parent.spawn(button(&materials))
+                                .with_children(|parent| {
+                                    parent.spawn(button_text(&asset_server, &materials, "Statistiques"));
+                                })
+                                .insert(MenuButton::Statistics);
+                            parent.spawn(button(&materials))
+                                .with_children(|parent| {
+                                    parent.spawn(button_text(&asset_server, &materials, "Succès"));
+                                })
+                                .insert(MenuButton::Achievements);
                             parent.spawn(button(&materials))
                                 .with_children(|parent| {
                                     parent.spawn(button_text(&asset_server, &materials, "Nouvelle Partie"));
                                 })
                                 .insert(MenuButton::Play);
+                            if SavedGame::load().is_some() {
+                                parent.spawn(button(&materials))
+                                    .with_children(|parent| {
+                                        parent.spawn(button_text(&asset_server, &materials, "Continuer"));
+                                    })
+                                    .insert(MenuButton::Continue);
+                            }
                         });
                 });
         })
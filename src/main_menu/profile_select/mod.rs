@@ -0,0 +1,203 @@
+use crate::{main_menu::MenuMaterials, game::{PROFILE_NAME_MAX_LENGTH, profile::Profiles}};
+
+use super::{AppState, MenuData};
+
+use bevy::prelude::*;
+
+const SELECTED_COLOR: Color = Color::rgb(1., 0.84, 0.);
+
+// region:    --- Resource
+
+/// Nom du profil en cours de saisie.
+#[derive(Resource, Default)]
+struct ProfileNameInput(String);
+
+// endregion: --- Resource
+
+// region:    --- Component
+
+/// Index du profil dans `Profiles`.
+#[derive(Component)]
+struct ProfileButton(usize);
+
+#[derive(Component)]
+struct CreateProfileButton;
+
+#[derive(Component)]
+struct ProfileNameText;
+
+// endregion: --- Component
+
+pub struct ProfileSelectPlugin;
+
+impl Plugin for ProfileSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_update(AppState::ProfileSelect)
+                    .with_system(button_press_system)
+                    .with_system(name_input_system)
+            )
+            .add_system_set(SystemSet::on_enter(AppState::ProfileSelect).with_system(setup_system))
+            .add_system_set(SystemSet::on_exit(AppState::ProfileSelect).with_system(cleanup_system));
+    }
+}
+
+fn button_press_system(
+    profile_buttons: Query<(&Interaction, &ProfileButton), Changed<Interaction>>,
+    create_buttons: Query<&Interaction, (Changed<Interaction>, With<CreateProfileButton>)>,
+    name_input: Res<ProfileNameInput>,
+    mut profiles: ResMut<Profiles>,
+    mut state: ResMut<State<AppState>>,
+) {
+    for (interaction, profile_button) in profile_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            profiles.current = Some(profile_button.0);
+            profiles.save();
+            state.set(AppState::MainMenu).expect("Couldn't switch state to MainMenu");
+            return;
+        }
+    }
+
+    for interaction in create_buttons.iter() {
+        if *interaction == Interaction::Clicked && profiles.create(&name_input.0) {
+            profiles.save();
+            state.set(AppState::MainMenu).expect("Couldn't switch state to MainMenu");
+        }
+    }
+}
+
+/// Saisie du nom d'un nouveau profil ; Entrée crée le profil.
+fn name_input_system(
+    mut received_characters: EventReader<ReceivedCharacter>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut name_input: ResMut<ProfileNameInput>,
+    mut name_text_query: Query<&mut Text, With<ProfileNameText>>,
+    mut profiles: ResMut<Profiles>,
+    mut state: ResMut<State<AppState>>,
+) {
+    for received_character in received_characters.iter() {
+        let character = received_character.char;
+        if (character.is_alphanumeric() || character == ' ' || character == '-' || character == '_') && name_input.0.chars().count() < PROFILE_NAME_MAX_LENGTH {
+            name_input.0.push(character);
+        }
+    }
+
+    if keys.just_pressed(KeyCode::Back) {
+        name_input.0.pop();
+    }
+
+    if keys.just_pressed(KeyCode::Return) && profiles.create(&name_input.0) {
+        keys.reset(KeyCode::Return);
+        profiles.save();
+        state.set(AppState::MainMenu).expect("Couldn't switch state to MainMenu");
+    }
+
+    if name_input.is_changed() {
+        for mut text in name_text_query.iter_mut() {
+            text.sections[0].value = name_input_label(&name_input.0);
+        }
+    }
+}
+
+fn name_input_label(name: &str) -> String {
+    format!("Nom : {}_", name)
+}
+
+fn root() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::ColumnReverse,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn button() -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            size: Size::new(Val::Px(250.0), Val::Px(40.0)),
+            margin: UiRect::all(Val::Px(5.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn text(asset_server: &Res<AssetServer>, label: &str, font_size: f32, color: Color) -> TextBundle {
+    TextBundle {
+        style: Style {
+            margin: UiRect::all(Val::Px(4.0)),
+            ..Default::default()
+        },
+        text: Text::from_section(
+            label,
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size,
+                color,
+            },
+        ),
+        ..Default::default()
+    }
+}
+
+/// Cet écran est affiché au lancement, avant le menu principal : il crée alors sa propre caméra.
+fn setup_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    materials: Res<MenuMaterials>,
+    profiles: Res<Profiles>,
+    menu_data: Option<Res<MenuData>>,
+) {
+    commands.insert_resource(ProfileNameInput::default());
+
+    let camera_entity = match menu_data {
+        Some(menu_data) => menu_data.camera_entity,
+        None => commands.spawn(Camera2dBundle::default()).id()
+    };
+
+    let ui_root = commands
+        .spawn(root())
+        .with_children(|parent| {
+            parent.spawn(text(&asset_server, "Choisir un profil", 35., Color::WHITE));
+
+            for (index, profile) in profiles.profiles.iter().enumerate() {
+                let color = if profiles.current == Some(index) { SELECTED_COLOR } else { materials.button_text };
+                parent.spawn(button())
+                    .with_children(|parent| {
+                        parent.spawn(text(&asset_server, &profile.name, 25., color));
+                    })
+                    .insert(ProfileButton(index));
+            }
+
+            parent.spawn(text(&asset_server, "Nouveau profil", 25., Color::WHITE));
+            parent.spawn(text(&asset_server, &name_input_label(""), 20., Color::WHITE))
+                .insert(ProfileNameText);
+            parent.spawn(button())
+                .with_children(|parent| {
+                    parent.spawn(text(&asset_server, "Créer", 25., materials.button_text));
+                })
+                .insert(CreateProfileButton);
+        })
+        .id();
+
+    commands.insert_resource(MenuData {
+        camera_entity,
+        ui_root,
+    });
+}
+
+fn cleanup_system(mut commands: Commands, menu_data: Res<MenuData>) {
+    commands.entity(menu_data.ui_root).despawn_recursive();
+    commands.entity(menu_data.camera_entity).despawn_recursive();
+
+    commands.remove_resource::<MenuData>();
+    commands.remove_resource::<ProfileNameInput>();
+}
@@ -0,0 +1,330 @@
+use crate::{main_menu::MenuMaterials, game::{GHOST_RUN_MAX_FILES, settings::{Settings, PaletteMode, TextSize, FoodIndicatorStyle}, theme::ThemeCatalogue}};
+
+use super::{AppState, MenuData};
+
+use bevy::{prelude::*, ui::FocusPolicy};
+
+// region:    --- Component
+
+/// Chaque bouton fait défiler les valeurs d'une option.
+#[derive(Component, Clone, Copy)]
+enum SettingButton {
+    SmoothMovement,
+    Theme,
+    PaletteMode,
+    FoodShapes,
+    ReduceFlashing,
+    TextSize,
+    VisualEffects,
+    FoodIndicator,
+    GhostRace,
+    DailyFirstAttempt
+}
+
+impl SettingButton {
+    const ALL: [SettingButton; 10] = [
+        SettingButton::SmoothMovement,
+        SettingButton::Theme,
+        SettingButton::PaletteMode,
+        SettingButton::FoodShapes,
+        SettingButton::ReduceFlashing,
+        SettingButton::TextSize,
+        SettingButton::VisualEffects,
+        SettingButton::FoodIndicator,
+        SettingButton::GhostRace,
+        SettingButton::DailyFirstAttempt
+    ];
+
+    fn label(&self, settings: &Settings) -> String {
+        match self {
+            SettingButton::SmoothMovement => format!("Mouvement fluide : {}", on_off(settings.smooth_movement)),
+            SettingButton::Theme => format!("Thème : {}", settings.theme),
+            SettingButton::PaletteMode => format!("Couleurs : {}", settings.palette_mode.label()),
+            SettingButton::FoodShapes => format!("Formes des nourritures : {}", on_off(settings.food_shapes)),
+            SettingButton::ReduceFlashing => format!("Clignotements réduits : {}", on_off(settings.reduce_flashing)),
+            SettingButton::TextSize => format!("Taille du texte : {}", settings.text_size.label()),
+            SettingButton::VisualEffects => format!("Effets visuels : {}", on_off(settings.visual_effects)),
+            SettingButton::FoodIndicator => format!("Durée des nourritures : {}", settings.food_indicator.label()),
+            SettingButton::GhostRace => format!("Fantôme du record ({} max) : {}", GHOST_RUN_MAX_FILES, on_off(settings.ghost_race)),
+            SettingButton::DailyFirstAttempt => format!("Défi du jour : {}", if settings.daily_first_attempt_only { "1re partie seule" } else { "toutes les parties" })
+        }
+    }
+
+    fn next_value(&self, settings: &mut Settings, theme_catalogue: &ThemeCatalogue) {
+        match self {
+            SettingButton::SmoothMovement => settings.smooth_movement = !settings.smooth_movement,
+            SettingButton::Theme => settings.theme = theme_catalogue.next_name(&settings.theme),
+            SettingButton::PaletteMode => settings.palette_mode = next(&PaletteMode::ALL, settings.palette_mode),
+            SettingButton::FoodShapes => settings.food_shapes = !settings.food_shapes,
+            SettingButton::ReduceFlashing => settings.reduce_flashing = !settings.reduce_flashing,
+            SettingButton::TextSize => settings.text_size = next(&TextSize::ALL, settings.text_size),
+            SettingButton::VisualEffects => settings.visual_effects = !settings.visual_effects,
+            SettingButton::FoodIndicator => settings.food_indicator = next(&FoodIndicatorStyle::ALL, settings.food_indicator),
+            SettingButton::GhostRace => settings.ghost_race = !settings.ghost_race,
+            SettingButton::DailyFirstAttempt => settings.daily_first_attempt_only = !settings.daily_first_attempt_only
+        }
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "Oui" } else { "Non" }
+}
+
+fn next<T: PartialEq + Copy>(values: &[T], value: T) -> T {
+    let index = values.iter().position(|candidate| *candidate == value).unwrap_or(0);
+    values[(index + 1) % values.len()]
+}
+
+/// Glissière de volume : cliquer ou faire glisser le curseur règle la valeur.
+#[derive(Component, Clone, Copy)]
+enum VolumeSlider {
+    Music,
+    Effects
+}
+
+impl VolumeSlider {
+    const ALL: [VolumeSlider; 2] = [VolumeSlider::Music, VolumeSlider::Effects];
+
+    fn label(&self) -> &'static str {
+        match self {
+            VolumeSlider::Music => "Musique",
+            VolumeSlider::Effects => "Effets sonores"
+        }
+    }
+
+    fn value(&self, settings: &Settings) -> f32 {
+        match self {
+            VolumeSlider::Music => settings.music_volume,
+            VolumeSlider::Effects => settings.effects_volume
+        }
+    }
+
+    fn set_value(&self, settings: &mut Settings, value: f32) {
+        match self {
+            VolumeSlider::Music => settings.music_volume = value,
+            VolumeSlider::Effects => settings.effects_volume = value
+        }
+    }
+}
+
+/// Partie remplie de la glissière.
+#[derive(Component)]
+struct SliderFill;
+
+#[derive(Component)]
+struct BackButton;
+
+// endregion: --- Component
+
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_update(AppState::Settings)
+                    .with_system(button_press_system)
+                    .with_system(volume_slider_system)
+            )
+            .add_system_set(SystemSet::on_enter(AppState::Settings).with_system(setup_system))
+            .add_system_set(SystemSet::on_exit(AppState::Settings).with_system(cleanup_system));
+    }
+}
+
+fn button_press_system(
+    setting_buttons: Query<(&Interaction, &SettingButton, &Children), Changed<Interaction>>,
+    back_buttons: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut text_query: Query<&mut Text>,
+    mut settings: ResMut<Settings>,
+    theme_catalogue: Res<ThemeCatalogue>,
+    mut state: ResMut<State<AppState>>,
+) {
+    for (interaction, setting_button, children) in setting_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            setting_button.next_value(&mut settings, &theme_catalogue);
+            settings.save();
+
+            for child in children.iter() {
+                if let Ok(mut text) = text_query.get_mut(*child) {
+                    text.sections[0].value = setting_button.label(&settings);
+                }
+            }
+        }
+    }
+
+    for interaction in back_buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            state.set(AppState::MainMenu).expect("Couldn't switch state to MainMenu");
+        }
+    }
+}
+
+/// La valeur suit le curseur tant que le bouton de la souris est enfoncé ;
+/// elle n'est sauvegardée qu'au relâchement.
+fn volume_slider_system(
+    windows: Res<Windows>,
+    sliders: Query<(&Interaction, &VolumeSlider, &Node, &GlobalTransform, &Children)>,
+    mut fill_query: Query<&mut Style, With<SliderFill>>,
+    mut settings: ResMut<Settings>,
+    mut dragging: Local<bool>,
+) {
+    let cursor = windows.get_primary().and_then(|window| window.cursor_position());
+    let mut clicked = false;
+
+    for (interaction, slider, node, transform, children) in sliders.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        clicked = true;
+
+        if let Some(cursor) = cursor {
+            let left = transform.translation().x - node.size().x / 2.;
+            let value = ((cursor.x - left) / node.size().x).clamp(0., 1.);
+            slider.set_value(&mut settings, value);
+
+            for child in children.iter() {
+                if let Ok(mut style) = fill_query.get_mut(*child) {
+                    style.size.width = Val::Percent(value * 100.);
+                }
+            }
+        }
+    }
+
+    if *dragging && !clicked {
+        settings.save();
+    }
+    *dragging = clicked;
+}
+
+fn root() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::ColumnReverse,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn button() -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            size: Size::new(Val::Px(400.0), Val::Px(32.0)),
+            margin: UiRect::all(Val::Px(3.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn slider_row() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            size: Size::new(Val::Px(400.0), Val::Px(32.0)),
+            margin: UiRect::all(Val::Px(3.0)),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        background_color: Color::NONE.into(),
+        ..Default::default()
+    }
+}
+
+fn slider_track() -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            size: Size::new(Val::Px(200.0), Val::Px(16.0)),
+            margin: UiRect::all(Val::Px(4.0)),
+            ..Default::default()
+        },
+        background_color: Color::rgb(0.25, 0.25, 0.25).into(),
+        ..Default::default()
+    }
+}
+
+fn slider_fill(value: f32) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(value * 100.), Val::Percent(100.0)),
+            ..Default::default()
+        },
+        background_color: Color::rgb(0.75, 0.75, 0.75).into(),
+        // les clics atteignent la glissière
+        focus_policy: FocusPolicy::Pass,
+        ..Default::default()
+    }
+}
+
+fn text(asset_server: &Res<AssetServer>, label: &str, font_size: f32, color: Color) -> TextBundle {
+    TextBundle {
+        style: Style {
+            margin: UiRect::all(Val::Px(4.0)),
+            ..Default::default()
+        },
+        text: Text::from_section(
+            label,
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size,
+                color,
+            },
+        ),
+        ..Default::default()
+    }
+}
+
+fn setup_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    materials: Res<MenuMaterials>,
+    settings: Res<Settings>,
+    mut menu_data: ResMut<MenuData>
+) {
+    let ui_root = commands
+        .spawn(root())
+        .with_children(|parent| {
+            parent.spawn(text(&asset_server, "Options", 35., Color::WHITE));
+
+            for setting_button in SettingButton::ALL {
+                parent.spawn(button())
+                    .with_children(|parent| {
+                        parent.spawn(text(&asset_server, &setting_button.label(&settings), 22., materials.button_text));
+                    })
+                    .insert(setting_button);
+            }
+
+            for slider in VolumeSlider::ALL {
+                parent.spawn(slider_row())
+                    .with_children(|parent| {
+                        parent.spawn(text(&asset_server, slider.label(), 22., Color::WHITE));
+                        parent.spawn(slider_track())
+                            .with_children(|parent| {
+                                parent.spawn(slider_fill(slider.value(&settings))).insert(SliderFill);
+                            })
+                            .insert(slider);
+                    });
+            }
+
+            parent.spawn(button())
+                .with_children(|parent| {
+                    parent.spawn(text(&asset_server, "Retour", 30., materials.button_text));
+                })
+                .insert(BackButton);
+        })
+        .id();
+
+    menu_data.ui_root = ui_root;
+}
+
+fn cleanup_system(mut commands: Commands, menu_data: Res<MenuData>) {
+    commands.entity(menu_data.ui_root).despawn_recursive();
+    commands.entity(menu_data.camera_entity).despawn_recursive();
+
+    commands.remove_resource::<MenuData>();
+}
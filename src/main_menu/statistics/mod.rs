@@ -0,0 +1,216 @@
+use crate::{main_menu::{MenuMaterials, sub_menu::mode_name}, game::{profile::{Profiles, ModeStats}, stats::DeathCause, daily::{DailyHistory, DailyRecord}, leaderboard::{LeaderboardTop, LeaderboardEntry}, DAILY_HISTORY_SHOWN, LEADERBOARD_SHOWN}};
+
+use super::{AppState, MenuData};
+
+use bevy::prelude::*;
+
+const MODE_COLOR: Color = Color::rgb(1., 0.84, 0.);
+
+// region:    --- Component
+
+#[derive(Component)]
+struct BackButton;
+
+#[derive(Component)]
+struct LeaderboardText;
+
+// endregion: --- Component
+
+pub struct StatisticsMenuPlugin;
+
+impl Plugin for StatisticsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_system_set(
+                SystemSet::on_update(AppState::Statistics)
+                .with_system(button_press_system)
+                .with_system(leaderboard_text_system)
+            )
+            .add_system_set(SystemSet::on_enter(AppState::Statistics).with_system(setup_system))
+            .add_system_set(SystemSet::on_exit(AppState::Statistics).with_system(cleanup_system));
+    }
+}
+
+fn button_press_system(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    mut state: ResMut<State<AppState>>,
+) {
+    for interaction in buttons.iter() {
+        if *interaction == Interaction::Clicked {
+            state.set(AppState::MainMenu).expect("Couldn't switch state to MainMenu");
+        }
+    }
+}
+
+fn root() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            flex_direction: FlexDirection::ColumnReverse,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn button() -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            size: Size::new(Val::Px(200.0), Val::Px(50.0)),
+            margin: UiRect::all(Val::Px(10.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn text(asset_server: &Res<AssetServer>, label: &str, font_size: f32, color: Color) -> TextBundle {
+    TextBundle {
+        style: Style {
+            margin: UiRect::all(Val::Px(2.0)),
+            ..Default::default()
+        },
+        text: Text::from_section(
+            label,
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size,
+                color,
+            },
+        ),
+        ..Default::default()
+    }
+}
+
+fn play_time_label(play_time: f32) -> String {
+    let seconds = play_time as u32;
+    format!("{}h{:02}m{:02}s", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+fn mode_stats_label(mode_stats: &ModeStats) -> String {
+    format!(
+        "{} parties, {} victoires, meilleur score {}, serpent {}, {}",
+        mode_stats.games_played, mode_stats.wins, mode_stats.best_score, mode_stats.longest_snake, play_time_label(mode_stats.play_time)
+    )
+}
+
+fn daily_record_label(date: &str, daily_record: &DailyRecord) -> String {
+    let best_score = daily_record.best_score.map_or("-".to_owned(), |best_score| best_score.to_string());
+    format!("{} : meilleur score {}, {} parties", date, best_score, daily_record.attempts)
+}
+
+fn leaderboard_entries_label(entries: &[LeaderboardEntry]) -> String {
+    entries.iter()
+        .take(LEADERBOARD_SHOWN)
+        .enumerate()
+        .map(|(index, entry)| format!("{}. {} {}", index + 1, entry.name, entry.score))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Meilleurs scores du serveur pour les modes où quelqu'un a été classé.
+fn leaderboard_label(leaderboard_top: &LeaderboardTop) -> String {
+    match leaderboard_top {
+        LeaderboardTop::Unavailable => String::new(),
+        LeaderboardTop::Loading => "Classement réseau : ...".to_owned(),
+        LeaderboardTop::Failed(error) => format!("Classement réseau : {}", error),
+        LeaderboardTop::Loaded(modes) => {
+            let modes: Vec<String> = modes.iter()
+                .filter(|(_, entries)| !entries.is_empty())
+                .map(|(wall_type, entries)| format!("{} : {}", mode_name(*wall_type), leaderboard_entries_label(entries)))
+                .collect();
+            if modes.is_empty() {
+                "Classement réseau : aucun score".to_owned()
+            } else {
+                format!("Classement réseau\n{}", modes.join("\n"))
+            }
+        }
+    }
+}
+
+fn setup_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    materials: Res<MenuMaterials>,
+    profiles: Res<Profiles>,
+    daily_history: Res<DailyHistory>,
+    leaderboard_top: Res<LeaderboardTop>,
+    mut menu_data: ResMut<MenuData>
+) {
+    let ui_root = commands
+        .spawn(root())
+        .with_children(|parent| {
+            match profiles.current() {
+                Some(profile) => {
+                    let stats = &profile.stats;
+                    parent.spawn(text(&asset_server, &format!("Statistiques de {}", profile.name), 30., Color::WHITE));
+                    parent.spawn(text(&asset_server, &format!("Total : {}", mode_stats_label(&stats.total())), 16., Color::WHITE));
+
+                    let foods_eaten = stats.foods_eaten.iter()
+                        .map(|(name, count)| format!("{} {}", name, count))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    parent.spawn(text(&asset_server, &format!("Nourritures : {}", foods_eaten), 16., Color::WHITE));
+
+                    let deaths = DeathCause::ALL.iter()
+                        .map(|death_cause| format!("{} {}", death_cause.label(), stats.deaths.get(death_cause).unwrap_or(&0)))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    parent.spawn(text(&asset_server, &format!("Morts : {}", deaths), 16., Color::WHITE));
+
+                    for (wall_type, mode_stats) in stats.modes.iter() {
+                        parent.spawn(text(&asset_server, mode_name(*wall_type), 20., MODE_COLOR));
+                        parent.spawn(text(&asset_server, &mode_stats_label(mode_stats), 16., Color::WHITE));
+                    }
+                },
+                None => {
+                    parent.spawn(text(&asset_server, "Aucun profil sélectionné", 30., Color::WHITE));
+                }
+            }
+
+            // le défi du jour est commun à tous les profils
+            if !daily_history.days.is_empty() {
+                parent.spawn(text(&asset_server, "Défi du jour", 20., MODE_COLOR));
+                for (date, daily_record) in daily_history.days.iter().rev().take(DAILY_HISTORY_SHOWN) {
+                    parent.spawn(text(&asset_server, &daily_record_label(date, daily_record), 16., Color::WHITE));
+                }
+            }
+
+            // rempli à la réponse du serveur de classement
+            parent.spawn(text(&asset_server, &leaderboard_label(&leaderboard_top), 16., Color::WHITE))
+                .insert(LeaderboardText);
+
+            parent.spawn(button())
+                .with_children(|parent| {
+                    parent.spawn(text(&asset_server, "Retour", 30., materials.button_text));
+                })
+                .insert(BackButton);
+        })
+        .id();
+
+    menu_data.ui_root = ui_root;
+}
+
+fn leaderboard_text_system(
+    leaderboard_top: Res<LeaderboardTop>,
+    mut leaderboard_text_query: Query<&mut Text, With<LeaderboardText>>,
+) {
+    if !leaderboard_top.is_changed() {
+        return;
+    }
+
+    for mut text in leaderboard_text_query.iter_mut() {
+        text.sections[0].value = leaderboard_label(&leaderboard_top);
+    }
+}
+
+fn cleanup_system(mut commands: Commands, menu_data: Res<MenuData>) {
+    commands.entity(menu_data.ui_root).despawn_recursive();
+    commands.entity(menu_data.camera_entity).despawn_recursive();
+
+    commands.remove_resource::<MenuData>();
+}
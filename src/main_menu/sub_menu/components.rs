@@ -10,5 +10,12 @@ pub enum MenuButton {
     VerticalWall(u32),
     HorizontalWall(u32),
     VerticalAndHorizontalWall(u32),
+    MazeWall(u32),
     Quit,
-}
\ No newline at end of file
+    Enemies,
+    /// Défi du jour, le même pour tous les joueurs.
+    Daily,
+}
+
+#[derive(Component)]
+pub struct EnemyCountText;
\ No newline at end of file
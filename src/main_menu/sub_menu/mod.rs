@@ -1,48 +1,137 @@
-use crate::main_menu::MenuMaterials;
+use crate::{main_menu::MenuMaterials, game::daily::{daily_game_type, today}, ARENA_WIDTH, ARENA_HEIGHT};
 
 use super::{AppState, MenuData};
 
 mod components;
 use bevy::{prelude::*, ui::Interaction, app::AppExit};
-use components::MenuButton;
+use components::{MenuButton, EnemyCountText};
 use enum_index::EnumIndex;
+use serde::{Serialize, Deserialize};
 
 
 // region:    --- Resource
 
-#[derive(Resource)]
+#[derive(Resource, Clone, Serialize, Deserialize)]
 pub struct GameType {
 	pub wall_type: usize,
-    pub multiplier: u32
+    pub multiplier: u32,
+    pub seed: u64,
+    pub enemies: u32,
+    /// Taille de l'arène en cases, avant l'ajout des cases des murs intérieurs.
+    pub arena_width: u32,
+    pub arena_height: u32,
+    /// Multiplie la vitesse de déplacement des serpents.
+    pub speed: f32,
+    pub controller: Controller,
+    /// Nombre de déplacements avant la fin de la partie, sans limite par défaut.
+    #[serde(default)]
+    pub move_limit: Option<u64>,
+    /// Date du défi du jour joué (AAAA-MM-JJ).
+    #[serde(default)]
+    pub daily: Option<String>
 }
 
+impl Default for GameType {
+    fn default() -> Self {
+        GameType {
+            wall_type: 0,
+            multiplier: 1,
+            seed: 0,
+            enemies: 0,
+            arena_width: ARENA_WIDTH,
+            arena_height: ARENA_HEIGHT,
+            speed: 1.,
+            controller: Controller::Human,
+            move_limit: None,
+            daily: None
+        }
+    }
+}
+
+/// Qui dirige le serpent du joueur.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Controller {
+    Human,
+    /// Le serpent suit l'IA des adversaires.
+    Bot
+}
+
+/// Nombre d'adversaires choisi dans le sous-menu pour la prochaine partie.
+#[derive(Resource, Default)]
+pub struct EnemyCount(pub u32);
+
 // endregion: --- Resource
+
+pub const MAX_ENEMIES: u32 = 3;
+/// Nombre de modes de jeu, de `GameType::wall_type` 0 au dernier.
+pub const MODE_COUNT: usize = 6;
+
+/// Nom du mode de jeu correspondant à `GameType::wall_type`.
+pub fn mode_name(wall_type: usize) -> &'static str {
+    match wall_type {
+        0 => "Sans Obstacle",
+        1 => "Mur Extérieur",
+        2 => "Mur Vertical",
+        3 => "Mur Horizontal",
+        4 => "Mur Vertical et Horizontal",
+        5 => "Labyrinthe",
+        _ => "Inconnu"
+    }
+}
+
+/// Multiplicateur de score du mode de jeu, repris par son bouton du menu.
+pub fn mode_multiplier(wall_type: usize) -> u32 {
+    match wall_type {
+        1 => 3,
+        2 | 3 => 2,
+        4 => 5,
+        5 => 4,
+        _ => 1
+    }
+}
+
 pub struct SubMenuPlugin;
 
 impl Plugin for SubMenuPlugin {
     fn build(&self, app: &mut App) {
         app
+            .init_resource::<EnemyCount>()
             .add_system(button_press_system)
             .add_system_set(SystemSet::on_enter(AppState::SubMenu).with_system(setup_system))
             .add_system_set(SystemSet::on_exit(AppState::SubMenu).with_system(cleanup_system));
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn button_press_system(
     mut commands: Commands,
     buttons: Query<(&Interaction, &MenuButton), (Changed<Interaction>, With<Button>)>,
+    mut enemy_count_text_query: Query<&mut Text, With<EnemyCountText>>,
+    mut enemy_count: ResMut<EnemyCount>,
     mut state: ResMut<State<AppState>>,
     mut exit: EventWriter<AppExit>
 ) {
     for (interaction, button) in buttons.iter() {
         if *interaction == Interaction::Clicked {
+            let new_game_type = |multiplier: u32| GameType {wall_type: button.enum_index(), multiplier, seed: rand::random(), enemies: enemy_count.0, ..Default::default()};
+
             match button {
                 MenuButton::Quit => exit.send(AppExit),
-                MenuButton::WithoutWall(x) => commands.insert_resource(GameType {wall_type: button.enum_index(), multiplier: *x}),
-                MenuButton::ExteriorWall(x) => commands.insert_resource(GameType {wall_type: button.enum_index(), multiplier: *x}),
-                MenuButton::VerticalWall(x) => commands.insert_resource(GameType {wall_type: button.enum_index(), multiplier: *x}),
-                MenuButton::HorizontalWall(x) => commands.insert_resource(GameType {wall_type: button.enum_index(), multiplier: *x}),
-                MenuButton::VerticalAndHorizontalWall(x) => commands.insert_resource(GameType {wall_type: button.enum_index(), multiplier: *x}),
+                MenuButton::WithoutWall(x) => commands.insert_resource(new_game_type(*x)),
+                MenuButton::ExteriorWall(x) => commands.insert_resource(new_game_type(*x)),
+                MenuButton::VerticalWall(x) => commands.insert_resource(new_game_type(*x)),
+                MenuButton::HorizontalWall(x) => commands.insert_resource(new_game_type(*x)),
+                MenuButton::VerticalAndHorizontalWall(x) => commands.insert_resource(new_game_type(*x)),
+                MenuButton::MazeWall(x) => commands.insert_resource(new_game_type(*x)),
+                MenuButton::Daily => commands.insert_resource(daily_game_type(&today())),
+                MenuButton::Enemies => {
+                    // simple réglage : on reste dans le sous-menu
+                    enemy_count.0 = (enemy_count.0 + 1) % (MAX_ENEMIES + 1);
+                    for mut text in enemy_count_text_query.iter_mut() {
+                        text.sections[0].value = enemy_count_label(enemy_count.0);
+                    }
+                    continue;
+                }
             };
 
             state.set(AppState::InGame).expect("Couldn't switch state to InGame");
@@ -104,7 +193,7 @@ fn button(materials: &Res<MenuMaterials>) -> ButtonBundle {
 }
 
 fn button_text(asset_server: &Res<AssetServer>, materials: &Res<MenuMaterials>, label: &str) -> TextBundle {
-    return TextBundle {
+    TextBundle {
         style: Style {
             margin: UiRect::all(Val::Px(10.0)),
             ..Default::default()
@@ -114,17 +203,22 @@ fn button_text(asset_server: &Res<AssetServer>, materials: &Res<MenuMaterials>,
             TextStyle {
                 font: asset_server.load("fonts/FiraSans-Bold.ttf"),
                 font_size: 30.0,
-                color: materials.button_text.clone(),
+                color: materials.button_text,
             },
         ),
         ..Default::default()
-    };
+    }
+}
+
+fn enemy_count_label(enemy_count: u32) -> String {
+    format!("Adversaires : {}", enemy_count)
 }
 
 fn setup_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     materials: Res<MenuMaterials>,
+    enemy_count: Res<EnemyCount>,
     mut menu_data: ResMut<MenuData>
 ) {
     let ui_root = 
@@ -144,31 +238,47 @@ fn setup_system(
                                         parent.spawn(button_text(&asset_server,  &materials, "Quitter"));
                                     })
                                     .insert(MenuButton::Quit);
+                                parent.spawn(button(&materials))
+                                    .with_children(|parent| {
+                                        parent.spawn(button_text(&asset_server, &materials, &enemy_count_label(enemy_count.0)))
+                                            .insert(EnemyCountText);
+                                    })
+                                    .insert(MenuButton::Enemies);
+                                parent.spawn(button(&materials))
+                                    .with_children(|parent| {
+                                        parent.spawn(button_text(&asset_server, &materials, "Défi du jour"));
+                                    })
+                                    .insert(MenuButton::Daily);
+                                parent.spawn(button(&materials))
+                                    .with_children(|parent| {
+                                        parent.spawn(button_text(&asset_server, &materials, "Labyrinthe"));
+                                    })
+                                    .insert(MenuButton::MazeWall(mode_multiplier(5)));
                                 parent.spawn(button(&materials))
                                     .with_children(|parent| {
                                         parent.spawn(button_text(&asset_server, &materials, "Mur Vertical et Horizontal"));
                                     })
-                                    .insert(MenuButton::VerticalAndHorizontalWall(5));
+                                    .insert(MenuButton::VerticalAndHorizontalWall(mode_multiplier(4)));
                                 parent.spawn(button(&materials))
                                     .with_children(|parent| {
                                         parent.spawn(button_text(&asset_server, &materials, "Mur Extérieur"));
                                     })
-                                    .insert(MenuButton::ExteriorWall(3));
+                                    .insert(MenuButton::ExteriorWall(mode_multiplier(1)));
                                 parent.spawn(button(&materials))
                                     .with_children(|parent| {
                                         parent.spawn(button_text(&asset_server, &materials, "Mur Horizontal"));
                                     })
-                                    .insert(MenuButton::HorizontalWall(2));
+                                    .insert(MenuButton::HorizontalWall(mode_multiplier(3)));
                                 parent.spawn(button(&materials))
                                     .with_children(|parent| {
                                         parent.spawn(button_text(&asset_server, &materials, "Mur Vertical"));
                                     })
-                                    .insert(MenuButton::VerticalWall(2));
+                                    .insert(MenuButton::VerticalWall(mode_multiplier(2)));
                                 parent.spawn(button(&materials))
                                     .with_children(|parent| {
                                         parent.spawn(button_text(&asset_server, &materials, "Sans Obsctacle"));
                                     })
-                                    .insert(MenuButton::WithoutWall(1));
+                                    .insert(MenuButton::WithoutWall(mode_multiplier(0)));
                                 
                             });
                     });